@@ -0,0 +1,28 @@
+// build.rs
+
+//! Links the LAPACK backend selected by the `openblas`, `mkl` or `accelerate` feature.
+//! Without any of them, LAPACK must be supplied through RUSTFLAGS (see the README).
+
+fn main() {
+    let openblas = std::env::var_os("CARGO_FEATURE_OPENBLAS").is_some();
+    let mkl = std::env::var_os("CARGO_FEATURE_MKL").is_some();
+    let accelerate = std::env::var_os("CARGO_FEATURE_ACCELERATE").is_some();
+
+    // The backends all export the same Fortran symbols, so only one can be linked
+    if [openblas, mkl, accelerate].iter().filter(|&&enabled| enabled).count() > 1 {
+        panic!("Enable at most one of the `openblas`, `mkl` and `accelerate` features.");
+    }
+
+    if openblas {
+        println!("cargo:rustc-link-lib=openblas");
+    }
+    if mkl {
+        println!("cargo:rustc-link-lib=mkl_rt");
+    }
+    if accelerate {
+        if std::env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("macos") {
+            panic!("The `accelerate` feature is only available on macOS.");
+        }
+        println!("cargo:rustc-link-lib=framework=Accelerate");
+    }
+}
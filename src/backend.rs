@@ -0,0 +1,91 @@
+// src/backend.rs
+
+//! Module for reporting which BLAS/LAPACK backend the crate is linked against.
+
+use lapack_sys::ilaver_;
+use std::os::raw::c_int;
+
+/// Information about the linked LAPACK backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendInfo {
+    /// The backend selected at compile time: `"OpenBLAS"`, `"Intel MKL"`, `"Apple Accelerate"`,
+    /// or `"system"` when LAPACK was supplied through RUSTFLAGS without a feature flag.
+    pub vendor: String,
+    /// The LAPACK version reported by `ilaver`, e.g. `"3.12.0"`.
+    pub lapack_version: String,
+    /// A vendor-specific build string, where the backend exposes one.
+    pub details: Option<String>,
+}
+
+/// Returns the backend features compiled into this build.
+///
+/// The backends all export the same LAPACK symbols, so at most one can be linked and the
+/// backend cannot be switched at runtime; rebuild with a different feature to compare them.
+pub fn compiled_backends() -> Vec<&'static str> {
+    let mut backends = Vec::new();
+    if cfg!(feature = "openblas") {
+        backends.push("openblas");
+    }
+    if cfg!(feature = "mkl") {
+        backends.push("mkl");
+    }
+    if cfg!(feature = "accelerate") {
+        backends.push("accelerate");
+    }
+    backends
+}
+
+/// Reports the linked LAPACK vendor and version.
+pub fn backend_info() -> BackendInfo {
+    let vendor = if cfg!(feature = "openblas") {
+        "OpenBLAS"
+    } else if cfg!(feature = "mkl") {
+        "Intel MKL"
+    } else if cfg!(feature = "accelerate") {
+        "Apple Accelerate"
+    } else {
+        "system"
+    };
+
+    BackendInfo {
+        vendor: vendor.to_string(),
+        lapack_version: lapack_version(),
+        details: vendor_details(),
+    }
+}
+
+/// Queries the LAPACK version with `ilaver`.
+fn lapack_version() -> String {
+    let (mut major, mut minor, mut patch): (c_int, c_int, c_int) = (0, 0, 0);
+    unsafe {
+        ilaver_(&mut major, &mut minor, &mut patch);
+    }
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+#[cfg(feature = "openblas")]
+fn vendor_details() -> Option<String> {
+    extern "C" {
+        fn openblas_get_config() -> *const std::os::raw::c_char;
+    }
+    let config = unsafe { std::ffi::CStr::from_ptr(openblas_get_config()) };
+    Some(config.to_string_lossy().into_owned())
+}
+
+#[cfg(all(feature = "mkl", not(feature = "openblas")))]
+fn vendor_details() -> Option<String> {
+    extern "C" {
+        fn MKL_Get_Version_String(buffer: *mut std::os::raw::c_char, len: c_int);
+    }
+    let mut buffer = vec![0 as std::os::raw::c_char; 256];
+    unsafe {
+        MKL_Get_Version_String(buffer.as_mut_ptr(), buffer.len() as c_int);
+    }
+    let version = unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) };
+    Some(version.to_string_lossy().trim().to_string())
+}
+
+#[cfg(not(any(feature = "openblas", feature = "mkl")))]
+fn vendor_details() -> Option<String> {
+    None
+}
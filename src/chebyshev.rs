@@ -0,0 +1,123 @@
+// src/chebyshev.rs
+
+//! Module for matrix-free spectral estimates built on Chebyshev polynomial approximations.
+
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+use crate::laplacian::LaplacianOperator;
+
+/// Seed used by `stochastic_trace`, so repeated runs give identical estimates.
+pub const DEFAULT_PROBE_SEED: u64 = 0x5DEE_CE66_D1CE_5EED;
+
+/// Computes the Chebyshev coefficients of `f` on `[0, lambda_max]` up to degree `order`.
+///
+/// The interval is mapped to `[-1, 1]` with `x = 2λ / lambda_max - 1`, and the coefficients are
+/// obtained by Chebyshev–Gauss quadrature on `order + 1` nodes, so
+/// `f(λ) ≈ Σ_k c_k T_k(x)` (with `c_0` already halved).
+pub fn chebyshev_coefficients<F: Fn(f64) -> f64>(f: &F, order: usize, lambda_max: f64) -> Vec<f64> {
+    let nodes = order + 1;
+    let samples: Vec<(f64, f64)> = (0..nodes)
+        .map(|j| {
+            let theta = PI * (j as f64 + 0.5) / nodes as f64;
+            let lambda = (theta.cos() + 1.0) * lambda_max / 2.0;
+            (theta, f(lambda))
+        })
+        .collect();
+
+    (0..=order)
+        .map(|k| {
+            let sum: f64 = samples.iter().map(|&(theta, value)| value * (k as f64 * theta).cos()).sum();
+            let c = 2.0 * sum / nodes as f64;
+            if k == 0 {
+                c / 2.0
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Estimates `trace(f(L))` without an eigendecomposition, using Hutchinson's estimator with a
+/// Chebyshev approximation of `f` (probes seeded with `DEFAULT_PROBE_SEED`).
+///
+/// Each Rademacher probe `z` contributes `z^T p(L) z`, where `p` is the degree-`cheb_order`
+/// Chebyshev interpolant of `f` on `[0, lambda_max]`, evaluated with the three-term recurrence so
+/// every probe costs `cheb_order` products with `L`. The estimate is unbiased up to the polynomial
+/// error, with a standard error that shrinks like `1 / sqrt(num_probes)`.
+///
+/// # Arguments
+///
+/// * `operator` - The Laplacian to evaluate.
+/// * `f` - The scalar function, e.g. `|λ| (-t * λ).exp()` for the heat trace.
+/// * `num_probes` - Number of random probe vectors.
+/// * `cheb_order` - Degree of the Chebyshev approximation.
+/// * `lambda_max` - An upper bound on the largest eigenvalue (see `LaplacianOperator::lambda_max_bound`).
+///
+/// # Returns
+///
+/// The estimated trace.
+///
+/// # Panics
+///
+/// Panics if `num_probes` is zero or `lambda_max` is not positive.
+pub fn stochastic_trace(
+    operator: &LaplacianOperator,
+    f: impl Fn(f64) -> f64,
+    num_probes: usize,
+    cheb_order: usize,
+    lambda_max: f64,
+) -> f64 {
+    stochastic_trace_with_seed(operator, f, num_probes, cheb_order, lambda_max, DEFAULT_PROBE_SEED)
+}
+
+/// Same as `stochastic_trace`, with an explicit seed for the probe vectors.
+pub fn stochastic_trace_with_seed(
+    operator: &LaplacianOperator,
+    f: impl Fn(f64) -> f64,
+    num_probes: usize,
+    cheb_order: usize,
+    lambda_max: f64,
+    seed: u64,
+) -> f64 {
+    assert!(num_probes > 0, "At least one probe vector is needed.");
+    assert!(lambda_max > 0.0, "lambda_max must be positive.");
+
+    let n = operator.dim();
+    let coefficients = chebyshev_coefficients(&f, cheb_order, lambda_max);
+
+    // The shifted operator L~ = (2 / lambda_max) L - I has its spectrum in [-1, 1]
+    let shifted = |x: &Array1<f64>| operator.apply(x) * (2.0 / lambda_max) - x;
+
+    let mut state = seed;
+    let mut total = 0.0;
+    for _ in 0..num_probes {
+        let z = Array1::from_iter((0..n).map(|_| if splitmix64(&mut state) & 1 == 0 { 1.0 } else { -1.0 }));
+
+        let mut t_prev = z.clone();
+        let mut estimate = coefficients[0] * z.dot(&t_prev);
+        if cheb_order >= 1 {
+            let mut t_curr = shifted(&z);
+            estimate += coefficients[1] * z.dot(&t_curr);
+            for &c in &coefficients[2..] {
+                let t_next = shifted(&t_curr) * 2.0 - &t_prev;
+                t_prev = std::mem::replace(&mut t_curr, t_next);
+                estimate += c * z.dot(&t_curr);
+            }
+        }
+
+        total += estimate;
+    }
+
+    total / num_probes as f64
+}
+
+/// SplitMix64 step, used to draw the Rademacher probes (and `kmeans` seeds) without an external
+/// RNG dependency.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -2,8 +2,10 @@
 
 // Module for converting GFA file to adjacency matrix in edge list format.
 
+use ndarray::prelude::*;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, BufWriter, Write};
 use std::path::Path;
@@ -12,6 +14,47 @@ use std::time::Instant;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::graph::DuplicateEdgePolicy;
+
+/// The two magic bytes that start every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Opens a text input for buffered reading, decompressing it on the fly if it is gzipped.
+///
+/// Gzip is detected from the magic bytes rather than the extension, so `.gfa.gz` and `.csv.gz`
+/// files are read transparently. Decompression requires the `gzip` feature; without it a gzipped
+/// file is rejected with `InvalidData`.
+///
+/// # Arguments
+///
+/// * `path` - Path to the input file.
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any file or I/O errors encountered.
+pub fn open_input<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn BufRead + Send>> {
+    let mut reader = BufReader::new(File::open(path.as_ref())?);
+    let is_gzip = reader.fill_buf()?.starts_with(&GZIP_MAGIC);
+    if !is_gzip {
+        return Ok(Box::new(reader));
+    }
+
+    #[cfg(feature = "gzip")]
+    {
+        Ok(Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader))))
+    }
+    #[cfg(not(feature = "gzip"))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} is gzip-compressed; rebuild with the `gzip` feature to read it.",
+                path.as_ref().display()
+            ),
+        ))
+    }
+}
+
 /// Converts a GFA file to an adjacency matrix in edge list format.
 ///
 /// This function performs a two-pass approach:
@@ -68,8 +111,7 @@ pub fn convert_gfa_to_edge_list<P: AsRef<Path>>(gfa_path: P, output_path: P) ->
 ///
 /// This function does not explicitly panic.
 fn parse_segments<P: AsRef<Path>>(gfa_path: P) -> io::Result<(HashMap<String, u32>, u32)> {
-    let file = File::open(&gfa_path)?;
-    let reader = BufReader::new(file);
+    let reader = open_input(&gfa_path)?;
 
     let mut segment_names = Vec::new();
 
@@ -130,8 +172,7 @@ fn parse_links_and_write_edges<P: AsRef<Path>>(
     segment_indices: &HashMap<String, u32>,
     output_path: P,
 ) -> io::Result<()> {
-    let file = File::open(&gfa_path)?;
-    let reader = BufReader::new(file);
+    let reader = open_input(&gfa_path)?;
 
     let output_file = File::create(&output_path)?;
     let writer = Arc::new(Mutex::new(BufWriter::new(output_file)));
@@ -205,3 +246,793 @@ fn parse_links_and_write_edges<P: AsRef<Path>>(
 
     Ok(())
 }
+
+/// Copies a GFA file, appending a cluster-label tag such as `CL:i:3` to every segment line.
+///
+/// Labels are indexed like the adjacency matrix rows: segment names are sorted lexicographically
+/// and assigned indices in that order, exactly as `convert_gfa_to_edge_list` does. Segment lines
+/// are matched by name, so their order in the file does not matter. An existing tag with the same
+/// name on a segment line is replaced rather than duplicated. All other lines are copied verbatim.
+///
+/// # Arguments
+///
+/// * `input` - Path to the input GFA file.
+/// * `output` - Path to the annotated output GFA file.
+/// * `labels` - Cluster label for each node, in matrix index order.
+/// * `tag` - Two-character GFA tag name (for example `CL`).
+///
+/// # Errors
+///
+/// Returns an `io::Error` of kind `InvalidInput` if the tag name is not a valid GFA tag or if
+/// the number of labels does not match the number of unique segments, and any file or I/O
+/// errors encountered.
+///
+/// # Panics
+///
+/// This function does not explicitly panic.
+pub fn write_gfa_with_partition(
+    input: &Path,
+    output: &Path,
+    labels: &[usize],
+    tag: &str,
+) -> io::Result<()> {
+    let tag_bytes = tag.as_bytes();
+    if tag_bytes.len() != 2 || !tag_bytes[0].is_ascii_alphabetic() || !tag_bytes[1].is_ascii_alphanumeric() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid GFA tag name '{}': expected two characters like 'CL'.", tag),
+        ));
+    }
+
+    let (segment_indices, num_segments) = parse_segments(input)?;
+    if labels.len() != num_segments as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Expected {} labels (one per segment), got {}.",
+                num_segments,
+                labels.len()
+            ),
+        ));
+    }
+
+    let reader = open_input(input)?;
+    let mut writer = BufWriter::new(File::create(output)?);
+    let tag_prefix = format!("{}:", tag);
+
+    for line_result in reader.lines() {
+        let line = line_result?;
+        if line.starts_with("S\t") {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if let Some(&index) = parts.get(1).and_then(|name| segment_indices.get(*name)) {
+                // Keep every field except a previous copy of this tag
+                let kept: Vec<&str> = parts
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, field)| *i < 3 || !field.starts_with(&tag_prefix))
+                    .map(|(_, field)| *field)
+                    .collect();
+                writeln!(writer, "{}\t{}:i:{}", kept.join("\t"), tag, labels[index as usize])?;
+                continue;
+            }
+        }
+        writeln!(writer, "{}", line)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Errors produced while loading a GFA file into memory.
+#[derive(Debug)]
+pub enum GfaError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// A line could not be parsed (`line` is 1-based).
+    Parse { line: usize, message: String },
+    /// Two nodes ended up with the same name after prefixing.
+    DuplicateName(String),
+    /// Links present in one direction only, as `(from, to)` segment names.
+    Asymmetric(Vec<(String, String)>),
+}
+
+impl fmt::Display for GfaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GfaError::Io(error) => write!(f, "I/O error: {}", error),
+            GfaError::Parse { line, message } => write!(f, "Line {}: {}", line, message),
+            GfaError::DuplicateName(name) => write!(f, "Duplicate node name '{}'", name),
+            GfaError::Asymmetric(pairs) => {
+                let listed: Vec<String> = pairs.iter().map(|(from, to)| format!("{} -> {}", from, to)).collect();
+                write!(f, "{} links have no reverse: {}", pairs.len(), listed.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for GfaError {}
+
+impl From<io::Error> for GfaError {
+    fn from(error: io::Error) -> Self {
+        GfaError::Io(error)
+    }
+}
+
+impl From<GfaError> for io::Error {
+    fn from(error: GfaError) -> Self {
+        match error {
+            GfaError::Io(error) => error,
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
+
+/// Loads a GFA file into a dense symmetric adjacency matrix.
+///
+/// Nodes are indexed like `convert_gfa_to_edge_list` does (segment names sorted lexicographically),
+/// and every link sets both `(from, to)` and `(to, from)` to 1, matching `adjacency_matrix_to_ndarray`.
+/// Unlike the streaming converter, malformed lines and links to unknown segments are reported
+/// instead of skipped.
+///
+/// # Arguments
+///
+/// * `path` - Path to the input GFA file.
+///
+/// # Errors
+///
+/// Returns a `GfaError` on I/O errors, on `S`/`L` lines with too few fields, and on links that
+/// reference a segment the file does not define.
+///
+/// # Panics
+///
+/// This function does not explicitly panic.
+pub fn load_gfa(path: &Path) -> Result<(Array2<f64>, Vec<String>), GfaError> {
+    load_gfa_links(path, true, DuplicateEdgePolicy::Overwrite)
+}
+
+/// Loads a GFA file like `load_gfa`, resolving links listed more than once with `policy`: `Sum`
+/// counts parallel links, `First` keeps weight 1, and `Error` rejects the file. A link and its
+/// reverse (`L a + b +` and `L b - a -`) join the same pair of segments, so they are duplicates.
+///
+/// # Errors
+///
+/// Returns the errors of `load_gfa`, and `GfaError::Parse` for a duplicate link under
+/// `DuplicateEdgePolicy::Error`.
+pub fn load_gfa_with_policy(
+    path: &Path,
+    policy: DuplicateEdgePolicy,
+) -> Result<(Array2<f64>, Vec<String>), GfaError> {
+    load_gfa_links(path, true, policy)
+}
+
+/// Loads a GFA file like `load_gfa`, but without mirroring links that should be listed twice.
+///
+/// A link and its orientation reversal (`L a + b +` and `L b - a -`) are the same link, so most
+/// files list each link once; those are mirrored like `load_gfa`. Some tools instead write every
+/// link in both orientations. Once any link appears with its reversal, the file is taken to follow
+/// that convention and each listing sets only `(from, to)`, so the matrix is symmetric only if no
+/// reversal is missing. Pair it with `assert_gfa_symmetric` to find the one-sided links.
+pub fn load_gfa_directed(path: &Path) -> Result<(Array2<f64>, Vec<String>), GfaError> {
+    load_gfa_links(path, false, DuplicateEdgePolicy::Overwrite)
+}
+
+/// Checks that an adjacency matrix loaded from GFA (e.g. by `load_gfa_directed`) is symmetric.
+///
+/// # Errors
+///
+/// Returns `GfaError::Asymmetric` listing every `(from, to)` segment pair whose weight differs
+/// from that of `(to, from)`, named after the side that has the larger weight (the link that is
+/// present).
+pub fn assert_gfa_symmetric(adjacency: &Array2<f64>, names: &[String]) -> Result<(), GfaError> {
+    let mut asymmetric = Vec::new();
+    for i in 0..adjacency.nrows() {
+        for j in (i + 1)..adjacency.ncols() {
+            let (forward, backward) = (adjacency[[i, j]], adjacency[[j, i]]);
+            if forward != backward {
+                let (from, to) = if forward > backward { (i, j) } else { (j, i) };
+                asymmetric.push((names[from].clone(), names[to].clone()));
+            }
+        }
+    }
+
+    if asymmetric.is_empty() {
+        Ok(())
+    } else {
+        Err(GfaError::Asymmetric(asymmetric))
+    }
+}
+
+/// Shared body of `load_gfa`, `load_gfa_with_policy` and `load_gfa_directed`.
+fn load_gfa_links(
+    path: &Path,
+    symmetric: bool,
+    policy: DuplicateEdgePolicy,
+) -> Result<(Array2<f64>, Vec<String>), GfaError> {
+    let reader = open_input(path)?;
+    let mut names = HashSet::new();
+    let mut links = Vec::new();
+
+    for (line_index, line_result) in reader.lines().enumerate() {
+        let line = line_result?;
+        let parts: Vec<&str> = line.split('\t').collect();
+        let line_number = line_index + 1;
+        match parts[0] {
+            "S" => {
+                if parts.len() < 3 {
+                    return Err(GfaError::Parse {
+                        line: line_number,
+                        message: "Segment line needs a name and a sequence field.".to_string(),
+                    });
+                }
+                names.insert(parts[1].to_string());
+            }
+            "L" => {
+                if parts.len() < 6 {
+                    return Err(GfaError::Parse {
+                        line: line_number,
+                        message: "Link line needs six fields.".to_string(),
+                    });
+                }
+                let link = [parts[1], parts[2], parts[3], parts[4]].map(str::to_string);
+                links.push((line_number, link));
+            }
+            _ => {}
+        }
+    }
+
+    // Without a single link listed alongside its reversal, every reversal is implied
+    let symmetric = symmetric || {
+        let listed: HashSet<&[String; 4]> = links.iter().map(|(_, link)| link).collect();
+        !links.iter().any(|(_, link)| {
+            let reversal = reverse_gfa_link(link);
+            reversal != *link && listed.contains(&reversal)
+        })
+    };
+
+    let mut names: Vec<String> = names.into_iter().collect();
+    names.sort();
+    let indices: HashMap<&str, usize> = names.iter().enumerate().map(|(i, name)| (name.as_str(), i)).collect();
+
+    let mut adjacency = Array2::<f64>::zeros((names.len(), names.len()));
+    for (line_number, [from, _, to, _]) in &links {
+        let lookup = |name: &str| {
+            indices.get(name).copied().ok_or_else(|| GfaError::Parse {
+                line: *line_number,
+                message: format!("Link references unknown segment '{}'.", name),
+            })
+        };
+        let (a, b) = (lookup(from)?, lookup(to)?);
+        let weight = if adjacency[[a, b]] != 0.0 {
+            policy.combine(adjacency[[a, b]], 1.0).ok_or_else(|| GfaError::Parse {
+                line: *line_number,
+                message: format!("Duplicate link between '{}' and '{}'.", from, to),
+            })?
+        } else {
+            1.0
+        };
+        adjacency[[a, b]] = weight;
+        if symmetric {
+            adjacency[[b, a]] = weight;
+        }
+    }
+
+    Ok((adjacency, names))
+}
+
+/// Returns the orientation reversal of a `[from, from_orient, to, to_orient]` link, which joins the
+/// same segment ends: `L a + b -` becomes `L b + a -`.
+fn reverse_gfa_link([from, from_orient, to, to_orient]: &[String; 4]) -> [String; 4] {
+    let flip = |orientation: &str| match orientation {
+        "+" => "-".to_string(),
+        "-" => "+".to_string(),
+        other => other.to_string(),
+    };
+    [to.clone(), flip(to_orient), from.clone(), flip(from_orient)]
+}
+
+/// The linear reference position of an rGFA segment, from its `SN`, `SO` and `LN` tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgfaCoordinate {
+    /// Name of the reference sequence the segment lies on (`SN:Z:`).
+    pub contig: String,
+    /// 0-based offset of the segment on that sequence (`SO:i:`).
+    pub offset: u64,
+    /// Segment length, from `LN:i:` or else the sequence field (0 if neither is given).
+    pub length: u64,
+}
+
+/// Reads the rGFA reference coordinates of the segments in a GFA file.
+///
+/// Only segments carrying both an `SN:Z:` and an `SO:i:` tag are returned, so the map is empty for
+/// a plain GFA file; callers fall back to node indices for the rest.
+///
+/// # Errors
+///
+/// Returns a `GfaError` on I/O errors, on `S` lines with too few fields, and on an `SO` or `LN`
+/// tag whose value is not an integer.
+pub fn load_rgfa_coordinates(path: &Path) -> Result<HashMap<String, RgfaCoordinate>, GfaError> {
+    let reader = open_input(path)?;
+    let mut coordinates = HashMap::new();
+
+    for (line_index, line_result) in reader.lines().enumerate() {
+        let line = line_result?;
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts[0] != "S" {
+            continue;
+        }
+        let line_number = line_index + 1;
+        if parts.len() < 3 {
+            return Err(GfaError::Parse {
+                line: line_number,
+                message: "Segment line needs a name and a sequence field.".to_string(),
+            });
+        }
+
+        let integer_tag = |value: &str| {
+            value.parse::<u64>().map_err(|_| GfaError::Parse {
+                line: line_number,
+                message: format!("Tag value '{}' is not an integer.", value),
+            })
+        };
+        let (mut contig, mut offset, mut length) = (None, None, None);
+        for tag in &parts[3..] {
+            if let Some(value) = tag.strip_prefix("SN:Z:") {
+                contig = Some(value.to_string());
+            } else if let Some(value) = tag.strip_prefix("SO:i:") {
+                offset = Some(integer_tag(value)?);
+            } else if let Some(value) = tag.strip_prefix("LN:i:") {
+                length = Some(integer_tag(value)?);
+            }
+        }
+
+        if let (Some(contig), Some(offset)) = (contig, offset) {
+            let length = length.unwrap_or(if parts[2] == "*" { 0 } else { parts[2].len() as u64 });
+            coordinates.insert(parts[1].to_string(), RgfaCoordinate { contig, offset, length });
+        }
+    }
+
+    Ok(coordinates)
+}
+
+/// Loads several GFA files (for example one per chromosome) into one block-diagonal adjacency matrix.
+///
+/// The files are placed on the diagonal in the order given, each indexed internally like `load_gfa`.
+/// Node names are prefixed with the source file's stem, as `stem:name`, so `chr1.gfa` segment `s1`
+/// becomes `chr1:s1`.
+///
+/// # Arguments
+///
+/// * `paths` - Paths to the input GFA files.
+///
+/// # Errors
+///
+/// Returns any `GfaError` from `load_gfa`, and `GfaError::DuplicateName` if two prefixed names
+/// collide (for example two files with the same stem).
+///
+/// # Panics
+///
+/// This function does not explicitly panic.
+pub fn load_gfa_multi(paths: &[&Path]) -> Result<(Array2<f64>, Vec<String>), GfaError> {
+    let mut blocks = Vec::with_capacity(paths.len());
+    for path in paths {
+        let (adjacency, names) = load_gfa(path)?;
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        blocks.push((adjacency, names, stem));
+    }
+
+    let total: usize = blocks.iter().map(|(_, names, _)| names.len()).sum();
+    let mut combined = Array2::<f64>::zeros((total, total));
+    let mut all_names = Vec::with_capacity(total);
+    let mut seen = HashSet::new();
+
+    let mut offset = 0;
+    for (adjacency, names, stem) in blocks {
+        let size = names.len();
+        combined
+            .slice_mut(s![offset..offset + size, offset..offset + size])
+            .assign(&adjacency);
+        for name in names {
+            let prefixed = format!("{}:{}", stem, name);
+            if !seen.insert(prefixed.clone()) {
+                return Err(GfaError::DuplicateName(prefixed));
+            }
+            all_names.push(prefixed);
+        }
+        offset += size;
+    }
+
+    Ok((combined, all_names))
+}
+
+// DOT section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// A lexical token of the DOT language, with the line it starts on.
+#[derive(Debug, Clone, PartialEq)]
+enum DotToken {
+    /// An identifier, numeral, or quoted/HTML string (`quoted` is true for the latter two, which
+    /// are never keywords).
+    Id { text: String, quoted: bool },
+    /// `--` or `->`.
+    EdgeOp(&'static str),
+    /// One of `{ } [ ] = ; , :`.
+    Punct(char),
+}
+
+/// Loads a Graphviz DOT file into a dense symmetric adjacency matrix.
+///
+/// Nodes are indexed in order of first appearance, whether in a node statement or an edge, and
+/// `names` holds their DOT identifiers (unquoted). Every edge sets both `(a, b)` and `(b, a)` to
+/// its `weight=` attribute, or 1 without one; `edge [weight=...]` sets the default for the rest of
+/// the enclosing graph or subgraph. `digraph` files are symmetrized the same way, so `a -> b`
+/// and `b -> a` describe one edge; an edge listed again overwrites the earlier weight, as in
+/// `load_gfa`. Edge chains (`a -- b -- c`), ports (`a:n`), attribute statements, comments and
+/// nested `subgraph` blocks are understood; subgraphs as edge endpoints are not.
+///
+/// # Arguments
+///
+/// * `path` - Path to the input DOT file.
+///
+/// # Errors
+///
+/// Returns `InvalidData` with the offending line on syntax errors, on the wrong edge operator for
+/// the graph type, and on weights that are not finite numbers, and any I/O error from reading.
+pub fn load_dot_adjacency(path: &Path) -> Result<(Array2<f64>, Vec<String>), io::Error> {
+    let mut source = String::new();
+    io::Read::read_to_string(&mut open_input(path)?, &mut source)?;
+    let tokens = tokenize_dot(&source)?;
+
+    let mut parser = DotParser {
+        tokens: &tokens,
+        pos: 0,
+        directed: false,
+        indices: HashMap::new(),
+        names: Vec::new(),
+        edges: Vec::new(),
+    };
+    parser.parse_graph()?;
+
+    let n = parser.names.len();
+    let mut adjacency = Array2::<f64>::zeros((n, n));
+    for &(a, b, weight) in &parser.edges {
+        adjacency[[a, b]] = weight;
+        adjacency[[b, a]] = weight;
+    }
+    Ok((adjacency, parser.names))
+}
+
+/// The `InvalidData` error for a malformed DOT file.
+fn dot_error(line: usize, message: impl fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("DOT line {}: {}", line, message))
+}
+
+/// Splits DOT source into tokens, dropping whitespace, comments and `#` preprocessor lines.
+fn tokenize_dot(source: &str) -> io::Result<Vec<(DotToken, usize)>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line) = (0, 1);
+    let mut at_line_start = true;
+    let is_id_char = |c: char| c.is_alphanumeric() || c == '_' || c == '.' || !c.is_ascii();
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+            at_line_start = true;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let line_start = std::mem::replace(&mut at_line_start, false);
+        match c {
+            '#' if line_start => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let start = line;
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(dot_error(start, "Unterminated comment."));
+                }
+                i += 2;
+            }
+            '"' => {
+                let start = line;
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(dot_error(start, "Unterminated quoted string.")),
+                        Some('"') => break,
+                        Some('\\') if chars.get(i + 1) == Some(&'"') => {
+                            text.push('"');
+                            i += 1;
+                        }
+                        // A backslash-newline continues the string on the next line
+                        Some('\\') if chars.get(i + 1) == Some(&'\n') => {
+                            line += 1;
+                            i += 1;
+                        }
+                        Some(&other) => {
+                            if other == '\n' {
+                                line += 1;
+                            }
+                            text.push(other);
+                        }
+                    }
+                    i += 1;
+                }
+                i += 1;
+                tokens.push((DotToken::Id { text, quoted: true }, start));
+            }
+            '<' => {
+                let start = line;
+                let mut depth = 0;
+                let begin = i + 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(dot_error(start, "Unterminated HTML string.")),
+                        Some('<') => depth += 1,
+                        Some('>') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some('\n') => line += 1,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                let text: String = chars[begin..i].iter().collect();
+                i += 1;
+                tokens.push((DotToken::Id { text, quoted: true }, start));
+            }
+            '-' if chars.get(i + 1) == Some(&'-') => {
+                tokens.push((DotToken::EdgeOp("--"), line));
+                i += 2;
+            }
+            '-' if chars.get(i + 1) == Some(&'>') => {
+                tokens.push((DotToken::EdgeOp("->"), line));
+                i += 2;
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' | ':' => {
+                tokens.push((DotToken::Punct(c), line));
+                i += 1;
+            }
+            _ if is_id_char(c) || c == '-' => {
+                let begin = i;
+                i += 1;
+                while i < chars.len() && is_id_char(chars[i]) {
+                    i += 1;
+                }
+                let text: String = chars[begin..i].iter().collect();
+                tokens.push((DotToken::Id { text, quoted: false }, line));
+            }
+            other => return Err(dot_error(line, format!("Unexpected character '{}'.", other))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over DOT tokens, collecting nodes and weighted edges.
+struct DotParser<'a> {
+    tokens: &'a [(DotToken, usize)],
+    pos: usize,
+    directed: bool,
+    indices: HashMap<String, usize>,
+    names: Vec<String>,
+    edges: Vec<(usize, usize, f64)>,
+}
+
+impl DotParser<'_> {
+    fn peek(&self) -> Option<&DotToken> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map(|&(_, line)| line)
+            .unwrap_or(1)
+    }
+
+    /// Whether the next token is the unquoted keyword `word` (DOT keywords are case-insensitive).
+    fn at_keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(DotToken::Id { text, quoted: false }) if text.eq_ignore_ascii_case(word))
+    }
+
+    fn eat_punct(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&DotToken::Punct(punct)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: char) -> io::Result<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(dot_error(self.line(), format!("Expected '{}'.", punct)))
+        }
+    }
+
+    fn expect_id(&mut self) -> io::Result<String> {
+        match self.peek() {
+            Some(DotToken::Id { text, .. }) => {
+                let text = text.clone();
+                self.pos += 1;
+                Ok(text)
+            }
+            _ => Err(dot_error(self.line(), "Expected an identifier.")),
+        }
+    }
+
+    fn node(&mut self, name: String) -> usize {
+        if let Some(&index) = self.indices.get(&name) {
+            return index;
+        }
+        let index = self.names.len();
+        self.indices.insert(name.clone(), index);
+        self.names.push(name);
+        index
+    }
+
+    /// `[strict] (graph | digraph) [ID] '{' stmt_list '}'`
+    fn parse_graph(&mut self) -> io::Result<()> {
+        if self.at_keyword("strict") {
+            self.pos += 1;
+        }
+        if self.at_keyword("digraph") {
+            self.directed = true;
+        } else if !self.at_keyword("graph") {
+            return Err(dot_error(self.line(), "Expected 'graph' or 'digraph'."));
+        }
+        self.pos += 1;
+        if matches!(self.peek(), Some(DotToken::Id { .. })) {
+            self.pos += 1;
+        }
+        self.expect_punct('{')?;
+        self.parse_statements(1.0)?;
+        if self.pos < self.tokens.len() {
+            return Err(dot_error(self.line(), "Unexpected content after the graph."));
+        }
+        Ok(())
+    }
+
+    /// Parses statements up to and including the closing `}`, with `default_weight` for edges.
+    fn parse_statements(&mut self, mut default_weight: f64) -> io::Result<()> {
+        loop {
+            match self.peek() {
+                None => return Err(dot_error(self.line(), "Missing '}'.")),
+                Some(DotToken::Punct('}')) => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(DotToken::Punct(';')) | Some(DotToken::Punct(',')) => self.pos += 1,
+                _ => self.parse_statement(&mut default_weight)?,
+            }
+        }
+    }
+
+    fn parse_statement(&mut self, default_weight: &mut f64) -> io::Result<()> {
+        // Attribute statements: `graph [...]`, `node [...]`, `edge [...]`
+        if self.at_keyword("graph") || self.at_keyword("node") || self.at_keyword("edge") {
+            let is_edge = self.at_keyword("edge");
+            self.pos += 1;
+            let attributes = self.parse_attributes()?;
+            if is_edge {
+                if let Some(weight) = self.weight(&attributes)? {
+                    *default_weight = weight;
+                }
+            }
+            return Ok(());
+        }
+
+        if self.at_keyword("subgraph") || self.peek() == Some(&DotToken::Punct('{')) {
+            if self.at_keyword("subgraph") {
+                self.pos += 1;
+                if matches!(self.peek(), Some(DotToken::Id { .. })) {
+                    self.pos += 1;
+                }
+            }
+            self.expect_punct('{')?;
+            self.parse_statements(*default_weight)?;
+            if matches!(self.peek(), Some(DotToken::EdgeOp(_))) {
+                return Err(dot_error(self.line(), "Subgraphs as edge endpoints are not supported."));
+            }
+            return Ok(());
+        }
+
+        let first = self.expect_id()?;
+        // Graph attribute `ID = ID`
+        if self.eat_punct('=') {
+            self.expect_id()?;
+            return Ok(());
+        }
+        self.skip_port()?;
+
+        let mut chain = vec![self.node(first)];
+        while let Some(&DotToken::EdgeOp(op)) = self.peek() {
+            let expected = if self.directed { "->" } else { "--" };
+            if op != expected {
+                return Err(dot_error(self.line(), format!("'{}' is not valid in this graph; use '{}'.", op, expected)));
+            }
+            self.pos += 1;
+            if self.at_keyword("subgraph") || self.peek() == Some(&DotToken::Punct('{')) {
+                return Err(dot_error(self.line(), "Subgraphs as edge endpoints are not supported."));
+            }
+            let name = self.expect_id()?;
+            self.skip_port()?;
+            chain.push(self.node(name));
+        }
+
+        let attributes = self.parse_attributes()?;
+        if chain.len() > 1 {
+            let weight = self.weight(&attributes)?.unwrap_or(*default_weight);
+            for pair in chain.windows(2) {
+                self.edges.push((pair[0], pair[1], weight));
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips an optional `:port[:compass]` suffix of a node ID.
+    fn skip_port(&mut self) -> io::Result<()> {
+        while self.eat_punct(':') {
+            self.expect_id()?;
+        }
+        Ok(())
+    }
+
+    /// Parses zero or more `[a = b, ...]` lists into `(name, value, line)` triples.
+    fn parse_attributes(&mut self) -> io::Result<Vec<(String, String, usize)>> {
+        let mut attributes = Vec::new();
+        while self.eat_punct('[') {
+            while !self.eat_punct(']') {
+                let line = self.line();
+                let name = self.expect_id()?;
+                self.expect_punct('=')?;
+                let value = self.expect_id()?;
+                attributes.push((name, value, line));
+                if !self.eat_punct(',') {
+                    self.eat_punct(';');
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// The value of the last `weight` attribute, if any.
+    fn weight(&self, attributes: &[(String, String, usize)]) -> io::Result<Option<f64>> {
+        match attributes.iter().rev().find(|(name, _, _)| name == "weight") {
+            None => Ok(None),
+            Some((_, value, line)) => match value.parse::<f64>() {
+                Ok(weight) if weight.is_finite() => Ok(Some(weight)),
+                _ => Err(dot_error(*line, format!("Edge weight '{}' is not a finite number.", value))),
+            },
+        }
+    }
+}
@@ -5,42 +5,572 @@
 // Try: export RUSTFLAGS="-llapack -lopenblas"
 // export RUSTFLAGS="-L/usr/lib/x86_64-linux-gnu -llapack -lopenblas"
 
-use lapack_sys::dsbevd_;
+use lapack_sys::{dgbtrf_, dgbtrs_, dsbevd_, dsbevx_, dsbtrd_, dstemr_, dstevd_, dsyevd_};
 use ndarray::prelude::*;
 use std::ffi::c_char;
 use std::os::raw::c_int;
+use std::fmt;
 use std::io::{self, Write};
 use std::path::Path;
-use csv::WriterBuilder;
+use csv::{ReaderBuilder, WriterBuilder};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 use nalgebra::{DVector, DMatrix, SymmetricEigen};
+use rayon::prelude::*;
+
+use crate::chebyshev::splitmix64;
+use crate::trace::stage_span;
+use crate::convert::open_input;
+use crate::graph::connected_components;
+use crate::laplacian::{build_laplacian, combinatorial_laplacian, LaplacianKind};
+
+// Error section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// Errors produced by eigen computations.
+/// Converts into `io::Error` so it composes with the crate's `io::Result` functions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EigenError {
+    /// The input matrix or parameters are unsuitable for the computation.
+    InvalidInput(String),
+    /// An iterative method did not reach the requested tolerance.
+    NotConverged { iterations: usize, residual: f64 },
+    /// The computation did not finish within the allowed time.
+    Timeout { limit: std::time::Duration },
+    /// A decomposition (possibly relayed from a worker thread) failed with an `io::Error`, kept by
+    /// kind and message so converting back into `io::Error` restores the kind.
+    Io { kind: io::ErrorKind, message: String },
+}
+
+impl fmt::Display for EigenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EigenError::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+            EigenError::NotConverged { iterations, residual } => write!(
+                f,
+                "Did not converge after {} iterations (residual = {:e})",
+                iterations, residual
+            ),
+            EigenError::Timeout { limit } => write!(f, "Did not finish within {:?}", limit),
+            EigenError::Io { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for EigenError {}
+
+/// Recovers the `EigenError` inside an `io::Error` built by the conversion below, so a
+/// `NotConverged` survives a round trip. Other `InvalidInput` errors become
+/// `EigenError::InvalidInput`, and everything else is kept as `EigenError::Io`.
+impl From<io::Error> for EigenError {
+    fn from(error: io::Error) -> Self {
+        if let Some(inner) = error.get_ref().and_then(|inner| inner.downcast_ref::<EigenError>()) {
+            return inner.clone();
+        }
+        if error.kind() == io::ErrorKind::InvalidInput {
+            return EigenError::InvalidInput(error.to_string());
+        }
+        EigenError::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<EigenError> for io::Error {
+    fn from(error: EigenError) -> Self {
+        let kind = match error {
+            EigenError::InvalidInput(_) => io::ErrorKind::InvalidInput,
+            EigenError::Timeout { .. } => io::ErrorKind::TimedOut,
+            EigenError::Io { kind, .. } => kind,
+            _ => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, error)
+    }
+}
+
+/// Errors produced while reading a numeric CSV file written by `save_array_to_csv_dsbevd`.
+#[derive(Debug)]
+pub enum CsvError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// A cell is not a number (`row` and `column` are 1-based).
+    Parse { row: usize, column: usize, token: String },
+    /// The rows do not form the expected rectangular (or square) shape.
+    Shape(String),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(error) => write!(f, "I/O error: {}", error),
+            CsvError::Parse { row, column, token } => {
+                write!(f, "Row {}, column {}: '{}' is not a number", row, column, token)
+            }
+            CsvError::Shape(message) => write!(f, "Invalid shape: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> Self {
+        CsvError::Io(error)
+    }
+}
+
+impl From<csv::Error> for CsvError {
+    fn from(error: csv::Error) -> Self {
+        CsvError::Io(error.into())
+    }
+}
+
+impl From<CsvError> for io::Error {
+    fn from(error: CsvError) -> Self {
+        match error {
+            CsvError::Io(error) => error,
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
+
+// Input validation section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// Maximum number of offending coordinates recorded in a `ScrubReport`.
+pub const MAX_REPORTED_COORDINATES: usize = 10;
+
+/// What `scrub_non_finite_with_policy` does with NaN and infinite entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinitePolicy {
+    /// Replace them with zero.
+    ReplaceWithZero,
+    /// Leave the matrix untouched and return an error.
+    Error,
+}
+
+/// Summary of the non-finite entries found by `scrub_non_finite`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScrubReport {
+    /// Number of NaN entries.
+    pub nan_count: usize,
+    /// Number of positive or negative infinite entries.
+    pub inf_count: usize,
+    /// The first `MAX_REPORTED_COORDINATES` offending `(row, column)` pairs in row-major order.
+    pub coordinates: Vec<(usize, usize)>,
+}
+
+impl ScrubReport {
+    /// Whether the matrix had no non-finite entries.
+    pub fn is_clean(&self) -> bool {
+        self.nan_count == 0 && self.inf_count == 0
+    }
+}
+
+impl fmt::Display for ScrubReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} NaN and {} infinite entries", self.nan_count, self.inf_count)?;
+        if !self.coordinates.is_empty() {
+            write!(f, ", first at {:?}", self.coordinates)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replaces NaN and infinite entries with zero and reports what was replaced.
+pub fn scrub_non_finite(matrix: &mut Array2<f64>) -> ScrubReport {
+    let mut report = ScrubReport::default();
+    for ((i, j), value) in matrix.indexed_iter_mut() {
+        if value.is_finite() {
+            continue;
+        }
+        if value.is_nan() {
+            report.nan_count += 1;
+        } else {
+            report.inf_count += 1;
+        }
+        if report.coordinates.len() < MAX_REPORTED_COORDINATES {
+            report.coordinates.push((i, j));
+        }
+        *value = 0.0;
+    }
+    report
+}
+
+/// Scrubs non-finite entries according to `policy`; see `scrub_non_finite`.
+///
+/// With `NonFinitePolicy::Error` the matrix is only modified if it was already clean, and any
+/// non-finite entry produces `EigenError::InvalidInput` describing the report.
+pub fn scrub_non_finite_with_policy(
+    matrix: &mut Array2<f64>,
+    policy: NonFinitePolicy,
+) -> Result<ScrubReport, EigenError> {
+    match policy {
+        NonFinitePolicy::ReplaceWithZero => Ok(scrub_non_finite(matrix)),
+        NonFinitePolicy::Error => {
+            let report = scrub_non_finite(&mut matrix.clone());
+            if report.is_clean() {
+                Ok(report)
+            } else {
+                Err(EigenError::InvalidInput(format!("Matrix contains {}.", report)))
+            }
+        }
+    }
+}
+
+/// Checks that a matrix is square and symmetric to within `tol` (absolute, per entry).
+///
+/// # Returns
+///
+/// * `Ok(())` - If `|A[i][j] - A[j][i]| <= tol` for every pair.
+/// * `Err(EigenError::InvalidInput)` - Naming the shape or the most asymmetric pair otherwise.
+pub fn check_symmetric(matrix: &Array2<f64>, tol: f64) -> Result<(), EigenError> {
+    if !matrix.is_square() {
+        return Err(EigenError::InvalidInput(format!(
+            "Matrix must be square, got {:?}.",
+            matrix.dim()
+        )));
+    }
+
+    let mut worst = (0.0_f64, 0, 0);
+    for ((i, j), &value) in matrix.indexed_iter() {
+        let difference = (value - matrix[[j, i]]).abs();
+        if j > i && (difference > worst.0 || difference.is_nan()) {
+            worst = (difference, i, j);
+        }
+    }
+
+    if worst.0 <= tol {
+        Ok(())
+    } else {
+        Err(EigenError::InvalidInput(format!(
+            "Matrix is not symmetric: |A[{}][{}] - A[{}][{}]| = {:e} exceeds {:e}.",
+            worst.1, worst.2, worst.2, worst.1, worst.0, tol
+        )))
+    }
+}
+
+/// Checks that the columns of an eigenvector matrix are orthonormal, `V^T V = I` to within `tol`
+/// (absolute, per entry of the Gram matrix), e.g. after reordering or sign flips.
+///
+/// # Returns
+///
+/// * `Ok(())` - If every entry of `V^T V - I` is at most `tol` in magnitude.
+/// * `Err(EigenError::InvalidInput)` - Naming the worst entry otherwise: a diagonal entry means a
+///   column is not unit length, an off-diagonal one that two columns are not orthogonal.
+pub fn check_orthonormal(eigvecs: &Array2<f64>, tol: f64) -> Result<(), EigenError> {
+    let gram = eigvecs.t().dot(eigvecs);
+
+    let mut worst = (0.0_f64, 0, 0);
+    for ((i, j), &value) in gram.indexed_iter() {
+        let deviation = (value - if i == j { 1.0 } else { 0.0 }).abs();
+        if j >= i && (deviation > worst.0 || deviation.is_nan()) {
+            worst = (deviation, i, j);
+        }
+    }
+
+    if worst.0 <= tol {
+        Ok(())
+    } else if worst.1 == worst.2 {
+        Err(EigenError::InvalidInput(format!(
+            "Eigenvectors are not orthonormal: column {} has |v^T v - 1| = {:e}, exceeding {:e}.",
+            worst.1, worst.0, tol
+        )))
+    } else {
+        Err(EigenError::InvalidInput(format!(
+            "Eigenvectors are not orthonormal: columns {} and {} have |v_i^T v_j| = {:e}, exceeding {:e}.",
+            worst.1, worst.2, worst.0, tol
+        )))
+    }
+}
+
+/// Returns the symmetric part `(A + A^T) / 2` of a square matrix, e.g. to feed a directed
+/// adjacency matrix to the symmetric eigensolvers.
+///
+/// # Panics
+///
+/// Panics if the matrix is not square.
+pub fn symmetrize(matrix: &Array2<f64>) -> Array2<f64> {
+    assert!(matrix.is_square(), "Only square matrices can be symmetrized.");
+    (matrix + &matrix.t()) / 2.0
+}
+
+/// Returns the antisymmetric part `(A - A^T) / 2` of a square matrix, so that
+/// `symmetrize(A) + skew_symmetrize(A) == A`.
+///
+/// # Panics
+///
+/// Panics if the matrix is not square.
+pub fn skew_symmetrize(matrix: &Array2<f64>) -> Array2<f64> {
+    assert!(matrix.is_square(), "Only square matrices can be skew-symmetrized.");
+    (matrix - &matrix.t()) / 2.0
+}
 
 // determine which matrix algorithm to use =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
-/// Computes the eigendecomposition of the Laplacian matrix, choosing between LAPACK's dsbevd and SymmetricEigen based on the matrix's bandedness.
+
+/// Default bandwidth ratio above which a matrix is treated as dense: `kd > n / 3` skips banding.
+pub const DEFAULT_DENSE_RATIO: f64 = 1.0 / 3.0;
+
+/// The eigensolver chosen for a given matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverPolicy {
+    /// LAPACK's dsbevd on the banded (upper) storage.
+    Banded,
+    /// LAPACK's dsyevd on the full matrix, skipping `to_banded_format` entirely.
+    Dense,
+    /// LAPACK's dstevd on the diagonal and first off-diagonal, for `kd <= 1` (e.g. path graphs).
+    Tridiagonal,
+    /// LAPACK's dsbtrd reduction of the banded storage followed by dstemr (MRRR) on the
+    /// tridiagonal form. Only chosen when `EigenOptions::use_mrrr` is set.
+    Mrrr,
+}
+
+/// Names the LAPACK routines behind each policy, e.g. for a status line.
+impl fmt::Display for SolverPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routines = match self {
+            SolverPolicy::Banded => "dsbevd",
+            SolverPolicy::Dense => "dsyevd",
+            SolverPolicy::Tridiagonal => "dstevd",
+            SolverPolicy::Mrrr => "dsbtrd + dstemr (MRRR)",
+        };
+        write!(f, "LAPACK's {}", routines)
+    }
+}
+
+/// Options controlling how `call_eigendecomp_with_options` dispatches a decomposition.
+#[derive(Debug, Clone)]
+pub struct EigenOptions {
+    /// A matrix with `kd > n * dense_ratio` is decomposed densely, since banding it has no benefit.
+    pub dense_ratio: f64,
+    /// If set, a matrix with `kd` above this cap is rejected before any storage is allocated, so an
+    /// accidentally dense input fails fast instead of exhausting memory. `None` (the default) has no cap.
+    pub max_band: Option<usize>,
+    /// Decompose banded matrices with dsbtrd + dstemr (`SolverPolicy::Mrrr`) instead of dsbevd.
+    /// MRRR computes each eigenvector in `O(n)`, so it is often faster for large `n`. Off by default.
+    pub use_mrrr: bool,
+    /// Do not print the line naming the chosen solver. Off by default.
+    pub quiet: bool,
+}
+
+impl Default for EigenOptions {
+    fn default() -> Self {
+        EigenOptions {
+            dense_ratio: DEFAULT_DENSE_RATIO,
+            max_band: None,
+            use_mrrr: false,
+            quiet: false,
+        }
+    }
+}
+
+/// Checks a bandwidth against an optional cap (see `EigenOptions::max_band`).
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `kd` exceeds `max_band`, suggesting a bandwidth-reducing reordering
+/// or the dense solver.
+pub fn check_max_band(kd: usize, max_band: Option<usize>) -> io::Result<()> {
+    match max_band {
+        Some(cap) if kd > cap => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Bandwidth kd = {} exceeds the maximum of {}. Reorder the nodes to reduce it \
+                 (graph::reorder_for_bandwidth, or `graphome band --reorder`), or raise the cap \
+                 to use the dense solver.",
+                kd, cap
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Chooses the eigensolver for a matrix of size `n` with bandwidth `kd`.
+/// Tridiagonal matrices always take the dstevd fast path, whatever the dense ratio.
+pub fn select_solver_policy(kd: i32, n: i32, dense_ratio: f64) -> SolverPolicy {
+    if kd <= 1 {
+        SolverPolicy::Tridiagonal
+    } else if kd as f64 > n as f64 * dense_ratio {
+        SolverPolicy::Dense
+    } else {
+        SolverPolicy::Banded
+    }
+}
+
+/// Computes the eigendecomposition of the Laplacian matrix with the default options.
+/// Eigenvalues are returned in ascending order with the matching eigenvectors as columns.
 pub fn call_eigendecomp(laplacian: &Array2<f64>) -> io::Result<(Array1<f64>, Array2<f64>)> {
-    // Compute the maximum bandedness (kd) of the matrix
-    let kd = max_band(laplacian);
+    let (eigvals, eigvecs, _) = call_eigendecomp_with_options(laplacian, &EigenOptions::default())?;
+    Ok((eigvals, eigvecs))
+}
+
+/// Computes the eigendecomposition of the Laplacian matrix, choosing between LAPACK's dsbevd, dsyevd and
+/// dstevd based on the matrix's bandedness (or dsbtrd + dstemr instead of dsbevd if
+/// `options.use_mrrr` is set), and reports which solver was used.
+/// Eigenvalues are returned in ascending order with the matching eigenvectors as columns.
+/// A bandwidth above `options.max_band` is rejected with `InvalidInput` before any solver runs.
+pub fn call_eigendecomp_with_options(
+    laplacian: &Array2<f64>,
+    options: &EigenOptions,
+) -> io::Result<(Array1<f64>, Array2<f64>, SolverPolicy)> {
+    // Compute the maximum bandedness (kd) of the matrix; tridiagonal matrices skip the full scan
+    let kd = if is_tridiagonal(laplacian) { 1 } else { max_band(laplacian) };
     let n = laplacian.nrows() as i32;
+    let _span = stage_span!("eigendecomp", n = n as usize, kd = kd as usize);
+    check_max_band(kd as usize, options.max_band)?;
 
     // Decide which eigendecomposition method to use based on kd
-    if kd < (n as f64 / 3.0).ceil() as i32 {
-        // Use LAPACK's dsbevd for banded matrices
-        println!("Using LAPACK's dsbevd for banded matrices (kd = {}, n = {})", kd, n);
-        compute_eigenvalues_and_vectors_sym_band(laplacian, kd)
-    } else {
-        // Use nalgebra's SymmetricEigen for non-banded or less banded matrices
-        println!("Using nalgebra's SymmetricEigen for non-banded matrices (kd = {}), n = {}", kd, n);
-        let (eigvals, eigvecs) = compute_eigenvalues_and_vectors_sym(laplacian)?;
+    let policy = match select_solver_policy(kd, n, options.dense_ratio) {
+        SolverPolicy::Banded if options.use_mrrr => SolverPolicy::Mrrr,
+        policy => policy,
+    };
+    let report = |line: String| {
+        if !options.quiet {
+            println!("{}", line);
+        }
+    };
+    let (eigvals, eigvecs) = match policy {
+        SolverPolicy::Banded => {
+            // Use LAPACK's dsbevd for banded matrices
+            report(format!("Using LAPACK's dsbevd for banded matrices (kd = {}, n = {})", kd, n));
+            compute_eigenvalues_and_vectors_sym_band(laplacian, kd)?
+        }
+        SolverPolicy::Dense => {
+            // Banding a nearly full matrix only wastes conversion time
+            report(format!(
+                "Using LAPACK's dsyevd for dense matrices (kd = {} > {:.2} * n, n = {})",
+                kd, options.dense_ratio, n
+            ));
+            compute_eigenvalues_and_vectors_dense(laplacian)?
+        }
+        SolverPolicy::Tridiagonal => {
+            // No band reduction is needed, dstevd works on the two diagonals directly
+            report(format!("Using LAPACK's dstevd for tridiagonal matrices (kd = {}, n = {})", kd, n));
+            compute_eigenvalues_and_vectors_tridiagonal(laplacian)?
+        }
+        SolverPolicy::Mrrr => {
+            report(format!("Using LAPACK's dsbtrd + dstemr (MRRR) for banded matrices (kd = {}, n = {})", kd, n));
+            compute_eigenvalues_and_vectors_mrrr(laplacian, kd)?
+        }
+    };
+
+    Ok((eigvals, eigvecs, policy))
+}
+
+/// Relative gap (scaled by the largest eigenvalue magnitude) below which two eigenvalues are treated as degenerate.
+pub const DEGENERACY_TOLERANCE: f64 = 1e-8;
+
+/// Computes the eigendecomposition like `call_eigendecomp`, then canonicalizes it with
+/// `canonicalize_eigenpairs` so the output does not depend on the solver or platform.
+pub fn call_eigendecomp_canonical(matrix: &Array2<f64>) -> io::Result<(Array1<f64>, Array2<f64>)> {
+    let (eigvals, eigvecs) = call_eigendecomp(matrix)?;
+    Ok(canonicalize_eigenpairs(&eigvals, &eigvecs))
+}
+
+/// Computes the eigendecomposition like `call_eigendecomp`, in f64, and returns the eigenvectors
+/// downcast to f32 to halve the memory they occupy afterwards (the eigenvalues stay f64).
+///
+/// Each entry is rounded to the nearest f32, a relative error of at most `f32::EPSILON / 2`
+/// (about 6e-8), so the columns are orthonormal only to about 1e-7 and components smaller than
+/// about 1e-38 flush to zero. That is ample for clustering or embeddings, but recompute in f64
+/// before using the vectors in further linear algebra. The f64 matrix is still held while it is
+/// converted, so the peak memory of the decomposition itself is unchanged.
+pub fn call_eigendecomp_f32(laplacian: &Array2<f64>) -> io::Result<(Array1<f64>, Array2<f32>)> {
+    let (eigvals, eigvecs) = call_eigendecomp(laplacian)?;
+    Ok((eigvals, eigvecs.mapv(|value| value as f32)))
+}
+
+/// Puts an eigendecomposition into a reproducible canonical form.
+///
+/// Eigenpairs are sorted by ascending eigenvalue. Within each cluster of degenerate eigenvalues
+/// (see `DEGENERACY_TOLERANCE`) the eigenvectors are replaced by a basis that depends only on the
+/// eigenspace: the standard basis vectors are projected onto it and orthonormalized with greedy
+/// pivoting on the largest remaining norm. Finally each eigenvector is flipped so its
+/// largest-magnitude entry is positive.
+///
+/// # Arguments
+///
+/// * `eigvals` - The eigenvalues, in any order.
+/// * `eigvecs` - The matching orthonormal eigenvectors as columns.
+///
+/// # Returns
+///
+/// The sorted eigenvalues and the canonical eigenvectors.
+pub fn canonicalize_eigenpairs(
+    eigvals: &Array1<f64>,
+    eigvecs: &Array2<f64>,
+) -> (Array1<f64>, Array2<f64>) {
+    let n = eigvals.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| eigvals[a].total_cmp(&eigvals[b]));
+
+    let vals = Array1::from_iter(order.iter().map(|&i| eigvals[i]));
+    let mut vecs = eigvecs.select(Axis(1), &order);
+
+    let scale = vals.iter().fold(1.0_f64, |acc, v| acc.max(v.abs()));
+    canonicalize_degenerate(&vals, &mut vecs, DEGENERACY_TOLERANCE * scale);
+
+    for mut column in vecs.columns_mut() {
+        let pivot = column
+            .iter()
+            .enumerate()
+            .fold((0, 0.0_f64), |best, (i, v)| if v.abs() > best.1 { (i, v.abs()) } else { best });
+        if !column.is_empty() && column[pivot.0] < 0.0 {
+            column.mapv_inplace(|v| -v);
+        }
+    }
+
+    (vals, vecs)
+}
+
+/// Replaces the eigenvectors of every degenerate multiplet with a reproducible basis of the same
+/// eigenspace, so solvers that return different bases within it agree afterwards.
+///
+/// `eigvals` must be in ascending order; consecutive eigenvalues at most `tol` apart form a
+/// multiplet (as in `degeneracy_structure`). Each multiplet's basis is built from the eigenspace
+/// alone: the standard basis vectors are projected onto it and orthonormalized with greedy
+/// pivoting on the largest remaining norm, which also fixes the signs. Eigenvectors of simple
+/// eigenvalues are left untouched; `canonicalize_eigenpairs` additionally sorts and fixes their signs.
+pub fn canonicalize_degenerate(eigvals: &Array1<f64>, eigvecs: &mut Array2<f64>, tol: f64) {
+    let n = eigvals.len();
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        while end < n && eigvals[end] - eigvals[end - 1] <= tol {
+            end += 1;
+        }
+        if end - start > 1 {
+            let basis = canonical_eigenspace_basis(&eigvecs.slice(s![.., start..end]));
+            eigvecs.slice_mut(s![.., start..end]).assign(&basis);
+        }
+        start = end;
+    }
+}
 
-        // Convert nalgebra's DVector and DMatrix to ndarray's Array1 and Array2
-        let eigvals_nd = Array1::from(eigvals.iter().cloned().collect::<Vec<f64>>());
-        let eigvecs_nd = Array2::from_shape_vec(
-            (eigvecs.nrows(), eigvecs.ncols()),
-            eigvecs.iter().cloned().collect(),
-        ).map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+/// Builds a basis for the span of the orthonormal columns of `basis` that is independent of
+/// which orthonormal basis was passed in.
+fn canonical_eigenspace_basis(basis: &ArrayView2<f64>) -> Array2<f64> {
+    let k = basis.ncols();
 
-        Ok((eigvals_nd, eigvecs_nd))
+    // The columns of the projector P = V V^T are the projections of the standard basis vectors.
+    let mut residual = basis.dot(&basis.t());
+    let mut result = Array2::<f64>::zeros(basis.raw_dim());
+
+    for step in 0..k {
+        let norms: Vec<f64> = residual.columns().into_iter().map(|c| c.dot(&c)).collect();
+        let pivot = norms
+            .iter()
+            .enumerate()
+            .fold(0, |best, (j, &norm)| if norm > norms[best] { j } else { best });
+
+        let q = residual.column(pivot).to_owned() / norms[pivot].sqrt();
+        // R <- R - q (q^T R)
+        let projection = q.dot(&residual);
+        for (mut column, p) in residual.columns_mut().into_iter().zip(projection.iter()) {
+            column.scaled_add(-p, &q);
+        }
+        result.column_mut(step).assign(&q);
     }
+
+    result
 }
 
 
@@ -68,331 +598,3244 @@ pub fn max_band(laplacian: &Array2<f64>) -> i32 {
     1
 }
 
-// dsbevd eigendecomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
-
-/// Converts a 2D matrix to a banded matrix representation required for dsbevd, with column-major order.
-pub fn to_banded_format(matrix: &Array2<f64>, kd: i32) -> Array2<f64> {
-    let (n, _) = matrix.dim();
-    let mut banded = Array2::<f64>::zeros(((kd + 1) as usize, n));
+/// Checks whether a symmetric matrix is tridiagonal (`max_band <= 1`), reading the upper triangle
+/// row by row and stopping at the first entry beyond the superdiagonal.
+///
+/// A tridiagonal matrix still has to be read in full to prove it, but row-major access is much
+/// cheaper than `max_band`'s diagonal-by-diagonal scan from the corner, and a matrix that is not
+/// tridiagonal is usually rejected within its first row.
+pub fn is_tridiagonal(matrix: &Array2<f64>) -> bool {
+    matrix
+        .rows()
+        .into_iter()
+        .enumerate()
+        .all(|(i, row)| row.iter().skip(i + 2).all(|&value| value == 0.0))
+}
 
-    // Store the upper triangle
-    for j in 0..n {
-        let i_min = if j >= kd as usize { j - kd as usize } else { 0 };
-        let i_max = j;
-
-        for i in i_min..=i_max {
-            let row = kd as usize + i - j;
-            let col = j;
-
-            if row >= banded.nrows() || col >= banded.ncols() {
-                panic!(
-                    "Attempting to access banded[{}, {}], which is out of bounds.",
-                    row, col
-                );
-            }
+// Resource estimation section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// Sustained floating-point throughput assumed by `estimate_resources` (one modern core running an optimized LAPACK).
+pub const ASSUMED_FLOPS_PER_SECOND: f64 = 1e10;
+
+/// Predicted resource usage of a dsbevd decomposition with eigenvectors (see `estimate_resources`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceEstimate {
+    /// Banded storage `AB`: `(kd + 1) * n` f64 values.
+    pub banded_bytes: u64,
+    /// Eigenvectors `Z` (`n * n` f64) plus eigenvalues `W` (`n` f64).
+    pub output_bytes: u64,
+    /// dsbevd workspace: `WORK` with `1 + 5n + 2n^2` f64 values and `IWORK` with `3 + 5n` i32 values.
+    pub workspace_bytes: u64,
+    /// Sum of the above, i.e. the memory held by LAPACK during the call.
+    pub peak_bytes: u64,
+    /// Approximate floating-point operation count.
+    pub flops: f64,
+    /// `flops / ASSUMED_FLOPS_PER_SECOND`.
+    pub seconds: f64,
+}
 
-            banded[[row, col]] = matrix[[i, j]];
-        }
+/// Estimates the peak memory and running time of decomposing an `n x n` matrix with bandwidth `kd`
+/// via dsbevd, before committing to the computation.
+///
+/// The memory figures are the exact LAPACK workspace sizes for `JOBZ = 'V'`; the caller's own copy of
+/// the input matrix (another `n * n` f64 for a dense `Array2`) is not included. The time assumes
+/// about `6 kd n^2` flops for the band reduction with accumulated `Q` plus `4/3 n^3` for divide and
+/// conquer, so it is an upper-end estimate: deflation often makes divide and conquer several times
+/// faster, and a multithreaded BLAS divides the time by the core count.
+///
+/// # Arguments
+///
+/// * `n` - The matrix dimension.
+/// * `kd` - The number of superdiagonals (see `max_band`).
+///
+/// # Returns
+///
+/// The memory breakdown in bytes and the estimated time in seconds.
+pub fn estimate_resources(n: usize, kd: usize) -> ResourceEstimate {
+    let n_u = n as u64;
+    let f64_bytes = std::mem::size_of::<f64>() as u64;
+    let i32_bytes = std::mem::size_of::<i32>() as u64;
+
+    let banded_bytes = (kd as u64 + 1) * n_u * f64_bytes;
+    let output_bytes = (n_u * n_u + n_u) * f64_bytes;
+    let (lwork, liwork) = if n <= 1 { (1, 1) } else { (1 + 5 * n_u + 2 * n_u * n_u, 3 + 5 * n_u) };
+    let workspace_bytes = lwork * f64_bytes + liwork * i32_bytes;
+
+    let n_f = n as f64;
+    let flops = 6.0 * kd as f64 * n_f * n_f + 4.0 / 3.0 * n_f.powi(3);
+
+    ResourceEstimate {
+        banded_bytes,
+        output_bytes,
+        workspace_bytes,
+        peak_bytes: banded_bytes + output_bytes + workspace_bytes,
+        flops,
+        seconds: flops / ASSUMED_FLOPS_PER_SECOND,
     }
-
-    banded // Return the banded matrix
 }
 
-
-/// Computes eigenvalues and eigenvectors for a symmetric band matrix using LAPACK's dsbevd.
-/// Assumes that the upper triangle is stored in the banded matrix.
-pub fn compute_eigenvalues_and_vectors_sym_band(
-    laplacian: &Array2<f64>,
-    kd: i32,
-) -> io::Result<(Array1<f64>, Array2<f64>)> {
-    let n = laplacian.nrows() as c_int;
-
-    // Convert to the banded format expected by dsbevd (upper triangle)
-    let banded_matrix = to_banded_format(laplacian, kd); // Has to be column-major order
-
-    // Force the banded matrix to be contiguous in memory by copying it
-    let banded_matrix_contiguous = banded_matrix.to_owned();
-
-    // Now safely get the slice (since it's contiguous)
-    let banded_ptr = banded_matrix_contiguous.as_slice().ok_or_else(|| {
-        io::Error::new(io::ErrorKind::Other, "Failed to get a contiguous slice for the banded matrix.")
-    })?.as_ptr();
-
-    // Initialize workspace query parameters
-    let jobz = b'V' as c_char; // Compute eigenvalues and eigenvectors
-    let uplo = b'U' as c_char; // Upper triangle
-
-    // Workspace query: set LWORK = -1 and LIWORK = -1
-    let mut work_query = vec![0.0_f64];
-    let mut iwork_query = vec![0_i32];
-    let lwork_query = -1;
-    let liwork_query = -1;
+/// Asks the linked LAPACK how many f64 values of `WORK` dsbevd wants for an `n x n` band matrix
+/// with `kd` superdiagonals, by running its workspace query (`LWORK = -1`).
+///
+/// Unlike `estimate_resources`, which uses the reference LAPACK formula, this reports what the
+/// backend in use will actually request, so vendor libraries that want more scratch are covered.
+///
+/// # Arguments
+///
+/// * `n` - The matrix dimension.
+/// * `kd` - The number of superdiagonals.
+/// * `jobz` - `'V'` to compute eigenvectors, `'N'` for eigenvalues only.
+///
+/// # Returns
+///
+/// The recommended `LWORK`, in f64 elements.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `jobz` is not `'V'` or `'N'` or a dimension does not fit LAPACK's
+/// 32-bit integers, and `Other` if LAPACK rejects the query.
+pub fn optimal_workspace(n: usize, kd: usize, jobz: char) -> io::Result<usize> {
+    if jobz != 'V' && jobz != 'N' {
+        return Err(EigenError::InvalidInput(format!("jobz must be 'V' or 'N', got '{}'.", jobz)).into());
+    }
+    let (Ok(n), Ok(kd)) = (c_int::try_from(n), c_int::try_from(kd)) else {
+        return Err(EigenError::InvalidInput(format!("n = {} and kd = {} must fit in a 32-bit integer.", n, kd)).into());
+    };
+
+    let jobz = jobz as u8 as c_char;
+    let uplo = b'U' as c_char;
+    let ldz = n.max(1);
+
+    let mut ab_dummy = [0.0_f64; 1];
+    let mut eigvals_dummy = [0.0_f64; 1];
+    let mut eigvecs_dummy = [0.0_f64; 1];
+    let mut work_query = [0.0_f64; 1];
+    let mut iwork_query = [0_i32; 1];
     let mut info: c_int = 0;
 
-    let mut eigvals_dummy = vec![0.0_f64; 1];
-    let mut eigvecs_dummy = vec![0.0_f64; 1];
-
-    // Perform workspace query
     unsafe {
         dsbevd_(
             &jobz,
             &uplo,
             &n,
             &kd,
-            banded_ptr as *mut f64,
+            ab_dummy.as_mut_ptr(),
             &(kd + 1),
             eigvals_dummy.as_mut_ptr(),
             eigvecs_dummy.as_mut_ptr(),
-            &n,
+            &ldz,
             work_query.as_mut_ptr(),
-            &lwork_query,
+            &-1,
             iwork_query.as_mut_ptr(),
-            &liwork_query,
+            &-1,
             &mut info,
         );
     }
 
     if info != 0 {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!(
-                "LAPACK dsbevd (workspace query) failed with error code {}",
-                info
-            ),
-        ));
+        return Err(io::Error::other(format!(
+            "LAPACK dsbevd (workspace query) failed with error code {}",
+            info
+        )));
+    }
+    Ok(work_query[0] as usize)
+}
+
+// dsbevd eigendecomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// Converts a 2D matrix to a banded matrix representation required for dsbevd, with column-major order.
+/// Stores the upper triangle; see `to_banded_format_uplo`.
+pub fn to_banded_format(matrix: &Array2<f64>, kd: i32) -> Array2<f64> {
+    to_banded_format_uplo(matrix, kd as usize, 'U').expect("'U' is a valid uplo")
+}
+
+/// Converts a symmetric matrix to `to_banded_format` storage in place, reusing the dense matrix's
+/// buffer, so the dense and banded forms never coexist. The buffer is shrunk to `(kd + 1) * n`
+/// values afterwards, which lets the allocator return the rest.
+///
+/// The bands are compacted line by line (rows for C order, columns for Fortran order), with only
+/// `kd + 1` values of scratch. For a C-order input the band is read from the lower triangle, so the
+/// result matches `to_banded_format` for symmetric matrices, which is all dsbevd accepts anyway.
+/// Matrices that are not contiguous, or with `kd >= n`, fall back to `to_banded_format`. The
+/// result is in Fortran (column-major) order.
+pub fn into_banded_format(matrix: Array2<f64>, kd: usize) -> Array2<f64> {
+    let n = matrix.nrows();
+    let contiguous = matrix.is_standard_layout() || matrix.t().is_standard_layout();
+    if !matrix.is_square() || !contiguous || kd >= n {
+        return to_banded_format(&matrix, kd as i32);
+    }
+
+    let (mut values, offset) = matrix.into_raw_vec_and_offset();
+    if offset != Some(0) {
+        let matrix = Array2::from_shape_vec((n, n), values).expect("shape matches the buffer");
+        return to_banded_format(&matrix, kd as i32);
+    }
+
+    // Band column j comes from memory line j, which starts at j * n. Writes to column j end
+    // before j * (kd + 1) + kd + 1 <= (j + 1) * n, so they never reach a line still to be read.
+    let mut scratch = vec![0.0_f64; kd + 1];
+    for j in 0..n {
+        for (r, value) in scratch.iter_mut().enumerate() {
+            // Band row r of column j holds A[j - kd + r][j], zero above the matrix
+            *value = if r + j >= kd { values[j * n + j + r - kd] } else { 0.0 };
+        }
+        values[j * (kd + 1)..(j + 1) * (kd + 1)].copy_from_slice(&scratch);
+    }
+
+    values.truncate((kd + 1) * n);
+    values.shrink_to_fit();
+    Array2::from_shape_vec((kd + 1, n).f(), values).expect("shape matches the buffer")
+}
+
+/// Converts a 2D matrix to LAPACK symmetric band storage for the triangle selected by `uplo`.
+///
+/// With `'U'`, `banded[kd + i - j][j] = A[i][j]` for `max(0, j - kd) <= i <= j`; with `'L'`,
+/// `banded[i - j][j] = A[i][j]` for `j <= i <= min(n - 1, j + kd)`. Unused corners are zero. These
+/// are the `AB` layouts dsbevd expects for the matching `UPLO` argument.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `uplo` is not `'U'` or `'L'`.
+pub fn to_banded_format_uplo(matrix: &Array2<f64>, kd: usize, uplo: char) -> Result<Array2<f64>, EigenError> {
+    let n = matrix.nrows();
+    let mut banded = Array2::<f64>::zeros((kd + 1, n));
+
+    // Tridiagonal storage is just the diagonal and one off-diagonal, copied as slices
+    if kd == 1 && n > 1 && (uplo == 'U' || uplo == 'L') {
+        let (diagonal_row, off_row, off_cols) = if uplo == 'U' { (1, 0, s![1..]) } else { (0, 1, s![..n - 1]) };
+        banded.row_mut(diagonal_row).assign(&matrix.diag());
+        banded.row_mut(off_row).slice_mut(off_cols).assign(&matrix.slice(s![..n - 1, 1..]).diag());
+        return Ok(banded);
+    }
+
+    match uplo {
+        'U' => {
+            for j in 0..n {
+                for i in j.saturating_sub(kd)..=j {
+                    banded[[kd + i - j, j]] = matrix[[i, j]];
+                }
+            }
+        }
+        'L' => {
+            for j in 0..n {
+                for i in j..n.min(j + kd + 1) {
+                    banded[[i - j, j]] = matrix[[i, j]];
+                }
+            }
+        }
+        other => {
+            return Err(EigenError::InvalidInput(format!(
+                "uplo must be 'U' or 'L', got '{}'.",
+                other
+            )))
+        }
+    }
+
+    Ok(banded)
+}
+
+/// Computes `A x` for a symmetric matrix directly from its `to_banded_format` storage (upper,
+/// `(kd + 1) x n`) in `O(n kd)`, e.g. as the `matvec` of a Lanczos run on a banded matrix.
+///
+/// Every stored off-diagonal entry `A[i][j]` contributes to both `y[i]` and `y[j]`.
+///
+/// # Panics
+///
+/// Panics if `banded` is not `(kd + 1) x n` or `x` does not have length `n`.
+pub fn banded_matvec(banded: &Array2<f64>, kd: usize, n: usize, x: &Array1<f64>) -> Array1<f64> {
+    assert_eq!(banded.dim(), (kd + 1, n), "Banded storage must be (kd + 1) x n.");
+    assert_eq!(x.len(), n, "Vector length must match the matrix size.");
+
+    let mut y = Array1::<f64>::zeros(n);
+    for j in 0..n {
+        y[j] += banded[[kd, j]] * x[j];
+        for i in j.saturating_sub(kd)..j {
+            let value = banded[[kd + i - j, j]];
+            y[i] += value * x[j];
+            y[j] += value * x[i];
+        }
+    }
+    y
+}
+
+/// Computes `A X` for a block of vectors (the columns of `x`), from the same banded storage as
+/// `banded_matvec`, in `O(n kd k)` for `k` columns.
+///
+/// Each stored entry is read once and applied to a whole row of `x`, so the band is streamed
+/// through the cache once instead of once per vector, as a block Lanczos iteration needs.
+///
+/// # Panics
+///
+/// Panics if `banded` is not `(kd + 1) x n` or `x` does not have `n` rows.
+pub fn banded_matmat(banded: &Array2<f64>, kd: usize, n: usize, x: &Array2<f64>) -> Array2<f64> {
+    assert_eq!(banded.dim(), (kd + 1, n), "Banded storage must be (kd + 1) x n.");
+    assert_eq!(x.nrows(), n, "Block row count must match the matrix size.");
+
+    let mut y = Array2::<f64>::zeros(x.dim());
+    for j in 0..n {
+        y.row_mut(j).scaled_add(banded[[kd, j]], &x.row(j));
+        for i in j.saturating_sub(kd)..j {
+            let value = banded[[kd + i - j, j]];
+            y.row_mut(i).scaled_add(value, &x.row(j));
+            y.row_mut(j).scaled_add(value, &x.row(i));
+        }
+    }
+    y
+}
+
+/// Rearranges `to_banded_format` output into a row-per-matrix-row view for inspection.
+///
+/// Entry `[i, offset]` of the result is `A[i][i + offset]`, so column 0 is the main diagonal and
+/// column `k` is the `k`-th superdiagonal, each aligned with the row it starts in. Positions past
+/// the end of the matrix (`i + offset >= n`) are NaN so they cannot be mistaken for stored zeros.
+/// Use `print_banded_display` for a labeled printout.
+pub fn banded_to_display(banded: &Array2<f64>, kd: usize) -> Array2<f64> {
+    let n = banded.ncols();
+    let mut display = Array2::<f64>::from_elem((n, kd + 1), f64::NAN);
+
+    for i in 0..n {
+        for offset in 0..=kd.min(n - 1 - i) {
+            display[[i, offset]] = banded[[kd - offset, i + offset]];
+        }
+    }
+
+    display
+}
+
+/// Prints a `banded_to_display` view with row labels and one `+k` column header per diagonal.
+pub fn print_banded_display(display: &Array2<f64>) {
+    let header: Vec<String> = (0..display.ncols()).map(|k| format!("{:>10}", format!("+{}", k))).collect();
+    println!("{:>6} {}", "row", header.join(""));
+
+    for (i, row) in display.rows().into_iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|v| if v.is_nan() { format!("{:>10}", "·") } else { format!("{:>10.4}", v) })
+            .collect();
+        println!("{:>6} {}", i, cells.join(""));
+    }
+}
+
+
+/// Returns a copy of the matrix with every entry farther than `kd` from the main diagonal set to zero.
+///
+/// This trades accuracy for speed ahead of `dsbevd`: the result is a *different* matrix, so its
+/// spectrum only approximates the original one. Use `truncation_error` to bound the damage.
+pub fn truncate_bandwidth(matrix: &Array2<f64>, kd: usize) -> Array2<f64> {
+    let mut truncated = matrix.clone();
+    for ((i, j), value) in truncated.indexed_iter_mut() {
+        if i.abs_diff(j) > kd {
+            *value = 0.0;
+        }
+    }
+    truncated
+}
+
+/// Computes the Frobenius norm of the entries `truncate_bandwidth` would discard for the given `kd`.
+///
+/// For symmetric matrices this bounds the induced eigenvalue error (Hoffman–Wielandt):
+/// `sqrt(sum_i (λ_i - μ_i)^2) <= ||A - A_kd||_F`, and in particular every individual
+/// eigenvalue moves by at most this amount.
+pub fn truncation_error(matrix: &Array2<f64>, kd: usize) -> f64 {
+    matrix
+        .indexed_iter()
+        .filter(|((i, j), _)| i.abs_diff(*j) > kd)
+        .map(|(_, value)| value * value)
+        .sum::<f64>()
+        .sqrt()
+}
+
+
+/// Computes eigenvalues and eigenvectors for a symmetric band matrix using LAPACK's dsbevd.
+/// Assumes that the upper triangle is stored in the banded matrix.
+pub fn compute_eigenvalues_and_vectors_sym_band(
+    laplacian: &Array2<f64>,
+    kd: i32,
+) -> io::Result<(Array1<f64>, Array2<f64>)> {
+    let n = laplacian.nrows() as c_int;
+
+    // Convert to the banded format expected by dsbevd (upper triangle)
+    let banding_span = stage_span!("banding", n = n as usize, kd = kd as usize);
+    let banded_matrix = to_banded_format(laplacian, kd);
+
+    // LAPACK reads AB in column-major order, so copy the band into Fortran layout
+    let mut banded_matrix_contiguous = Array2::<f64>::zeros(banded_matrix.dim().f());
+    banded_matrix_contiguous.assign(&banded_matrix);
+    drop(banding_span);
+
+    // Now safely get the slice (since it's contiguous)
+    let banded_ptr = banded_matrix_contiguous.as_slice_memory_order_mut().ok_or_else(|| {
+        io::Error::other("Failed to get a contiguous slice for the banded matrix.")
+    })?.as_mut_ptr();
+
+    // Initialize workspace query parameters
+    let jobz = b'V' as c_char; // Compute eigenvalues and eigenvectors
+    let uplo = b'U' as c_char; // Upper triangle
+
+    // Workspace query: set LWORK = -1 and LIWORK = -1
+    let mut work_query = vec![0.0_f64];
+    let mut iwork_query = vec![0_i32];
+    let lwork_query = -1;
+    let liwork_query = -1;
+    let mut info: c_int = 0;
+
+    let mut eigvals_dummy = vec![0.0_f64; 1];
+    let mut eigvecs_dummy = vec![0.0_f64; 1];
+
+    // Perform workspace query
+    let lapack_span = stage_span!("lapack.dsbevd", n = n as usize, kd = kd as usize);
+    unsafe {
+        dsbevd_(
+            &jobz,
+            &uplo,
+            &n,
+            &kd,
+            banded_ptr,
+            &(kd + 1),
+            eigvals_dummy.as_mut_ptr(),
+            eigvecs_dummy.as_mut_ptr(),
+            &n,
+            work_query.as_mut_ptr(),
+            &lwork_query,
+            iwork_query.as_mut_ptr(),
+            &liwork_query,
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dsbevd (workspace query) failed with error code {}",
+            info
+        )));
+    }
+
+    // Extract optimal LWORK and LIWORK from the workspace query
+    let optimal_lwork = work_query[0] as usize;
+    let optimal_liwork = iwork_query[0] as usize;
+
+    // Allocate WORK and IWORK with optimal sizes
+    let mut work = vec![0.0_f64; optimal_lwork];
+    let mut iwork = vec![0_i32; optimal_liwork];
+    let mut eigvals = vec![0.0_f64; n as usize];
+    let mut eigvecs = vec![0.0_f64; (n * n) as usize]; // Flat array for eigenvectors
+
+    // Perform the actual eigendecomposition with optimal WORK and IWORK
+    unsafe {
+        dsbevd_(
+            &jobz,
+            &uplo,
+            &n,
+            &kd,
+            banded_ptr,
+            &(kd + 1),
+            eigvals.as_mut_ptr(),
+            eigvecs.as_mut_ptr(),
+            &n,
+            work.as_mut_ptr(),
+            &(optimal_lwork as c_int),
+            iwork.as_mut_ptr(),
+            &(optimal_liwork as c_int),
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dsbevd failed with error code {}",
+            info
+        )));
+    }
+    drop(lapack_span);
+
+    // Convert outputs to ndarray types (Z comes back column-major, one eigenvector per column)
+    let _span = stage_span!("vector_assembly", n = n as usize);
+    let eigvals_nd = Array1::from(eigvals);
+    let eigvecs_nd = Array2::from_shape_vec((n as usize, n as usize).f(), eigvecs)
+        .map_err(|e| io::Error::other(e.to_string()))?
+        .as_standard_layout()
+        .to_owned();
+
+    Ok((eigvals_nd, eigvecs_nd))
+}
+
+/// Computes only the eigenpairs `il..=iu` (1-based, ascending) of a matrix in `to_banded_format`
+/// storage with LAPACK's dsbevx, e.g. to slice a cached banded matrix repeatedly.
+///
+/// The band dimensions are taken from the storage: `kd = banded.nrows() - 1`, `n = banded.ncols()`.
+/// Selecting `m` eigenpairs costs the band reduction plus `O(n m)` work with bisection and inverse
+/// iteration, instead of the full divide and conquer.
+///
+/// # Returns
+///
+/// The `iu - il + 1` selected eigenvalues in ascending order and their eigenvectors as columns, or
+/// `InvalidInput` unless `1 <= il <= iu <= n`.
+pub fn compute_eigenvalues_and_vectors_banded_range(
+    banded: &Array2<f64>,
+    il: usize,
+    iu: usize,
+) -> io::Result<(Array1<f64>, Array2<f64>)> {
+    let n = banded.ncols();
+    if banded.nrows() == 0 || il < 1 || il > iu || iu > n {
+        return Err(EigenError::InvalidInput(format!(
+            "Need 1 <= il <= iu <= n for banded storage of shape {:?}, got il = {}, iu = {}.",
+            banded.dim(),
+            il,
+            iu
+        ))
+        .into());
+    }
+    let kd = banded.nrows() - 1;
+
+    // dsbevx overwrites AB, so work on a column-major copy
+    let mut ab = Array2::<f64>::zeros(banded.dim().f());
+    ab.assign(banded);
+    let ab_ptr = ab
+        .as_slice_memory_order_mut()
+        .ok_or_else(|| io::Error::other("Failed to get a contiguous slice for the banded matrix."))?
+        .as_mut_ptr();
+
+    let jobz = b'V' as c_char;
+    let range = b'I' as c_char;
+    let uplo = b'U' as c_char;
+    let (n_c, kd_c) = (n as c_int, kd as c_int);
+    let (il_c, iu_c) = (il as c_int, iu as c_int);
+    let selected = iu - il + 1;
+
+    let mut q = vec![0.0_f64; n * n];
+    let mut found: c_int = 0;
+    let mut eigvals = vec![0.0_f64; n];
+    let mut eigvecs = vec![0.0_f64; n * selected];
+    let mut work = vec![0.0_f64; 7 * n];
+    let mut iwork = vec![0_i32; 5 * n];
+    let mut ifail = vec![0_i32; n];
+    let mut info: c_int = 0;
+
+    unsafe {
+        dsbevx_(
+            &jobz,
+            &range,
+            &uplo,
+            &n_c,
+            &kd_c,
+            ab_ptr,
+            &(kd_c + 1),
+            q.as_mut_ptr(),
+            &n_c,
+            &0.0,
+            &0.0,
+            &il_c,
+            &iu_c,
+            &0.0,
+            &mut found,
+            eigvals.as_mut_ptr(),
+            eigvecs.as_mut_ptr(),
+            &n_c,
+            work.as_mut_ptr(),
+            iwork.as_mut_ptr(),
+            ifail.as_mut_ptr(),
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dsbevx failed with error code {}",
+            info
+        )));
+    }
+
+    let found = found as usize;
+    eigvals.truncate(found);
+    eigvecs.truncate(n * found);
+    let eigvecs = Array2::from_shape_vec((n, found).f(), eigvecs)
+        .map_err(|error| io::Error::other(error.to_string()))?;
+    Ok((Array1::from(eigvals), eigvecs))
+}
+
+/// Reduces a matrix in `to_banded_format` storage to symmetric tridiagonal form with LAPACK's dsbtrd,
+/// the first stage of dsbevd.
+///
+/// The tridiagonal matrix is orthogonally similar to the banded one, so it has the same eigenvalues
+/// and can be handed to `compute_eigenvalues_and_vectors_tridiagonal` or any other tridiagonal
+/// solver. The orthogonal factor is not formed.
+///
+/// # Arguments
+///
+/// * `banded` - Upper band storage with `kd + 1` rows and `n` columns.
+/// * `kd` - The number of superdiagonals.
+/// * `n` - The matrix dimension.
+///
+/// # Returns
+///
+/// The diagonal (length `n`) and the off-diagonal (length `n - 1`).
+pub fn reduce_to_tridiagonal(banded: &Array2<f64>, kd: usize, n: usize) -> io::Result<(Array1<f64>, Array1<f64>)> {
+    if banded.dim() != (kd + 1, n) {
+        return Err(EigenError::InvalidInput(format!(
+            "Expected banded storage of shape ({}, {}), got {:?}.",
+            kd + 1,
+            n,
+            banded.dim()
+        ))
+        .into());
+    }
+    if n == 0 {
+        return Ok((Array1::zeros(0), Array1::zeros(0)));
+    }
+
+    // dsbtrd overwrites AB, so work on a column-major copy
+    let mut ab = Array2::<f64>::zeros(banded.dim().f());
+    ab.assign(banded);
+    let ab_ptr = ab
+        .as_slice_memory_order_mut()
+        .ok_or_else(|| io::Error::other("Failed to get a contiguous slice for the banded matrix."))?
+        .as_mut_ptr();
+
+    let vect = b'N' as c_char;
+    let uplo = b'U' as c_char;
+    let n_c = n as c_int;
+    let kd_c = kd as c_int;
+    let mut d = vec![0.0_f64; n];
+    let mut e = vec![0.0_f64; n.max(2) - 1];
+    let mut q_dummy = [0.0_f64; 1];
+    let mut work = vec![0.0_f64; n];
+    let mut info: c_int = 0;
+
+    unsafe {
+        dsbtrd_(
+            &vect,
+            &uplo,
+            &n_c,
+            &kd_c,
+            ab_ptr,
+            &(kd_c + 1),
+            d.as_mut_ptr(),
+            e.as_mut_ptr(),
+            q_dummy.as_mut_ptr(),
+            &1,
+            work.as_mut_ptr(),
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dsbtrd failed with error code {}",
+            info
+        )));
+    }
+
+    e.truncate(n - 1);
+    Ok((Array1::from(d), Array1::from(e)))
+}
+
+/// Yields the eigenvalues of a symmetric tridiagonal matrix one at a time, in ascending order, by
+/// Sturm-sequence bisection, so the spectrum never has to be held in memory.
+///
+/// The `k`-th eigenvalue is bracketed between the Gershgorin bounds (or the previous eigenvalue)
+/// and bisected on the Sturm count, i.e. the number of negative pivots of `T - x I = L D L^T`,
+/// which equals the number of eigenvalues below `x`, to an absolute accuracy of about `ε ||T||`.
+/// Each eigenvalue costs `O(n)` per bisection step and about 55 steps, so the whole spectrum
+/// costs `O(n^2)` time but only the `O(n)` diagonals are stored. Prefer `compute_eigenvalues_and_vectors_tridiagonal`
+/// when the spectrum fits in memory.
+#[derive(Debug, Clone)]
+pub struct EigenvalueIter {
+    diag: Array1<f64>,
+    off_squared: Array1<f64>,
+    lower: f64,
+    upper: f64,
+    /// Absolute bisection tolerance, `ε` times the spectral scale (as dstebz's default `ABSTOL`).
+    abs_tol: f64,
+    /// Smallest pivot magnitude allowed in the Sturm recurrence.
+    pivmin: f64,
+    next: usize,
+    previous: Option<f64>,
+}
+
+impl EigenvalueIter {
+    /// Creates the iterator from the diagonal and off-diagonal of a symmetric tridiagonal matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `off` does not have `diag.len() - 1` entries (or 0 for an empty matrix).
+    pub fn new(diag: Array1<f64>, off: &Array1<f64>) -> Self {
+        let n = diag.len();
+        assert_eq!(off.len(), n.saturating_sub(1), "The off-diagonal must have n - 1 entries.");
+
+        // Gershgorin discs bound every eigenvalue
+        let radius = |i: usize| {
+            let left = if i > 0 { off[i - 1].abs() } else { 0.0 };
+            let right = if i + 1 < n { off[i].abs() } else { 0.0 };
+            left + right
+        };
+        let lower = (0..n).map(|i| diag[i] - radius(i)).fold(f64::INFINITY, f64::min);
+        let upper = (0..n).map(|i| diag[i] + radius(i)).fold(f64::NEG_INFINITY, f64::max);
+        // Widen the bounds so an eigenvalue on a disc edge is strictly inside
+        let scale = lower.abs().max(upper.abs()).max(f64::MIN_POSITIVE);
+        let abs_tol = f64::EPSILON * scale;
+
+        EigenvalueIter {
+            off_squared: off.mapv(|v| v * v),
+            diag,
+            lower: lower - 2.0 * abs_tol,
+            upper: upper + 2.0 * abs_tol,
+            abs_tol,
+            // Replace a zero pivot by a tiny one, as LAPACK's dstebz does
+            pivmin: f64::MIN_POSITIVE * off.iter().fold(1.0_f64, |acc, &v| acc.max(v * v)),
+            next: 0,
+            previous: None,
+        }
+    }
+
+    /// Creates the iterator for a banded symmetric matrix, reducing it to tridiagonal form with
+    /// `reduce_to_tridiagonal` first (same arguments).
+    pub fn from_banded(banded: &Array2<f64>, kd: usize, n: usize) -> io::Result<Self> {
+        let (diag, off) = reduce_to_tridiagonal(banded, kd, n)?;
+        Ok(Self::new(diag, &off))
+    }
+
+    /// Number of eigenvalues strictly below `x`.
+    fn sturm_count(&self, x: f64) -> usize {
+        let n = self.diag.len();
+        let mut count = 0;
+        let mut q = 1.0;
+        for i in 0..n {
+            let coupling = if i > 0 { self.off_squared[i - 1] / q } else { 0.0 };
+            q = self.diag[i] - x - coupling;
+            if q.abs() < self.pivmin {
+                q = -self.pivmin;
+            }
+            if q < 0.0 {
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+impl Iterator for EigenvalueIter {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        let k = self.next;
+        if k >= self.diag.len() {
+            return None;
+        }
+
+        // The previous eigenvalue is a tighter lower bound, unless rounding put it above λ_k
+        let mut lo = self.lower;
+        if let Some(previous) = self.previous {
+            let candidate = previous - 2.0 * self.abs_tol;
+            if self.sturm_count(candidate) <= k {
+                lo = lo.max(candidate);
+            }
+        }
+        let mut hi = self.upper;
+
+        // Invariant: sturm_count(lo) <= k < sturm_count(hi)
+        loop {
+            let mid = 0.5 * (lo + hi);
+            if mid <= lo || mid >= hi || hi - lo <= self.abs_tol + 2.0 * f64::EPSILON * lo.abs().max(hi.abs()) {
+                break;
+            }
+            if self.sturm_count(mid) > k {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        let value = 0.5 * (lo + hi);
+        self.next += 1;
+        self.previous = Some(value);
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.diag.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for EigenvalueIter {}
+
+// dstevd eigendecomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// Computes eigenvalues and eigenvectors of a symmetric tridiagonal matrix using LAPACK's dstevd.
+/// Only the main diagonal and the first superdiagonal are read; anything outside the band is ignored.
+pub fn compute_eigenvalues_and_vectors_tridiagonal(
+    matrix: &Array2<f64>,
+) -> io::Result<(Array1<f64>, Array2<f64>)> {
+    let n = matrix.nrows() as c_int;
+    let nu = matrix.nrows();
+
+    // dstevd overwrites D with the eigenvalues and uses E as scratch space
+    let mut d: Vec<f64> = (0..nu).map(|i| matrix[[i, i]]).collect();
+    let mut e: Vec<f64> = (0..nu.saturating_sub(1)).map(|i| matrix[[i, i + 1]]).collect();
+    e.push(0.0);
+    let mut z = vec![0.0_f64; nu * nu];
+
+    let jobz = b'V' as c_char; // Compute eigenvalues and eigenvectors
+    let ldz = n.max(1);
+
+    // Workspace query: set LWORK = -1 and LIWORK = -1
+    let mut work_query = vec![0.0_f64];
+    let mut iwork_query = vec![0_i32];
+    let mut info: c_int = 0;
+
+    let lapack_span = stage_span!("lapack.dstevd", n = nu);
+    unsafe {
+        dstevd_(
+            &jobz,
+            &n,
+            d.as_mut_ptr(),
+            e.as_mut_ptr(),
+            z.as_mut_ptr(),
+            &ldz,
+            work_query.as_mut_ptr(),
+            &-1,
+            iwork_query.as_mut_ptr(),
+            &-1,
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dstevd (workspace query) failed with error code {}",
+            info
+        )));
+    }
+
+    let optimal_lwork = (work_query[0] as usize).max(1);
+    let optimal_liwork = (iwork_query[0] as usize).max(1);
+    let mut work = vec![0.0_f64; optimal_lwork];
+    let mut iwork = vec![0_i32; optimal_liwork];
+
+    unsafe {
+        dstevd_(
+            &jobz,
+            &n,
+            d.as_mut_ptr(),
+            e.as_mut_ptr(),
+            z.as_mut_ptr(),
+            &ldz,
+            work.as_mut_ptr(),
+            &(optimal_lwork as c_int),
+            iwork.as_mut_ptr(),
+            &(optimal_liwork as c_int),
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dstevd failed with error code {}",
+            info
+        )));
+    }
+    drop(lapack_span);
+
+    // Z is column-major, so read it with Fortran strides
+    let _span = stage_span!("vector_assembly", n = nu);
+    let eigvecs = Array2::from_shape_vec((nu, nu).f(), z)
+        .map_err(|e| io::Error::other(format!("Failed to shape the eigenvector matrix: {}", e)))?;
+    Ok((Array1::from(d), eigvecs.as_standard_layout().to_owned()))
+}
+
+// dstemr (MRRR) eigendecomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// Computes eigenvalues and eigenvectors of a symmetric banded matrix by reducing it to tridiagonal
+/// form with LAPACK's dsbtrd and solving that with dstemr (Multiple Relatively Robust Representations).
+///
+/// dsbtrd also accumulates the orthogonal factor `Q` with `A = Q T Q^T`, so the eigenvectors of `A`
+/// are `Q` times those of `T`. MRRR finds each tridiagonal eigenvector in `O(n)` work, against the
+/// `O(n^2)` per vector of the divide and conquer in dsbevd, though the final `Q Z` product is
+/// still `O(n^3)`. The spectrum agrees with `compute_eigenvalues_and_vectors_sym_band` to rounding.
+///
+/// # Arguments
+///
+/// * `laplacian` - The symmetric matrix; only the upper band of width `kd` is read.
+/// * `kd` - The number of superdiagonals.
+///
+/// # Returns
+///
+/// Eigenvalues in ascending order and the matching eigenvectors as columns.
+pub fn compute_eigenvalues_and_vectors_mrrr(
+    laplacian: &Array2<f64>,
+    kd: i32,
+) -> io::Result<(Array1<f64>, Array2<f64>)> {
+    let nu = laplacian.nrows();
+    let n = nu as c_int;
+    if nu == 0 {
+        return Ok((Array1::zeros(0), Array2::zeros((0, 0))));
+    }
+
+    let banding_span = stage_span!("banding", n = nu, kd = kd as usize);
+    let banded_matrix = to_banded_format(laplacian, kd);
+    let mut ab = Array2::<f64>::zeros(banded_matrix.dim().f());
+    ab.assign(&banded_matrix);
+    drop(banding_span);
+    let ab_ptr = ab
+        .as_slice_memory_order_mut()
+        .ok_or_else(|| io::Error::other("Failed to get a contiguous slice for the banded matrix."))?
+        .as_mut_ptr();
+
+    // Reduce to tridiagonal form, forming Q (column-major, n x n)
+    let mut d = vec![0.0_f64; nu];
+    let mut e = vec![0.0_f64; nu];
+    let mut q = vec![0.0_f64; nu * nu];
+    let mut work = vec![0.0_f64; nu];
+    let mut info: c_int = 0;
+
+    let reduction_span = stage_span!("lapack.dsbtrd", n = nu, kd = kd as usize);
+    unsafe {
+        dsbtrd_(
+            &(b'V' as c_char),
+            &(b'U' as c_char),
+            &n,
+            &kd,
+            ab_ptr,
+            &(kd + 1),
+            d.as_mut_ptr(),
+            e.as_mut_ptr(),
+            q.as_mut_ptr(),
+            &n,
+            work.as_mut_ptr(),
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dsbtrd failed with error code {}",
+            info
+        )));
+    }
+    drop(reduction_span);
+
+    // dstemr needs E of length n (the last entry is scratch) and overwrites both diagonals
+    let jobz = b'V' as c_char;
+    let range = b'A' as c_char;
+    let mut found: c_int = 0;
+    let mut eigvals = vec![0.0_f64; nu];
+    let mut z = vec![0.0_f64; nu * nu];
+    let mut isuppz = vec![0_i32; 2 * nu];
+    let mut tryrac: c_int = 1;
+    let mut work_query = vec![0.0_f64];
+    let mut iwork_query = vec![0_i32];
+
+    // Workspace query: set LWORK = -1 and LIWORK = -1
+    let lapack_span = stage_span!("lapack.dstemr", n = nu);
+    unsafe {
+        dstemr_(
+            &jobz,
+            &range,
+            &n,
+            d.as_mut_ptr(),
+            e.as_mut_ptr(),
+            &0.0,
+            &0.0,
+            &0,
+            &0,
+            &mut found,
+            eigvals.as_mut_ptr(),
+            z.as_mut_ptr(),
+            &n,
+            &n,
+            isuppz.as_mut_ptr(),
+            &mut tryrac,
+            work_query.as_mut_ptr(),
+            &-1,
+            iwork_query.as_mut_ptr(),
+            &-1,
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dstemr (workspace query) failed with error code {}",
+            info
+        )));
+    }
+
+    let optimal_lwork = (work_query[0] as usize).max(1);
+    let optimal_liwork = (iwork_query[0] as usize).max(1);
+    let mut work = vec![0.0_f64; optimal_lwork];
+    let mut iwork = vec![0_i32; optimal_liwork];
+
+    unsafe {
+        dstemr_(
+            &jobz,
+            &range,
+            &n,
+            d.as_mut_ptr(),
+            e.as_mut_ptr(),
+            &0.0,
+            &0.0,
+            &0,
+            &0,
+            &mut found,
+            eigvals.as_mut_ptr(),
+            z.as_mut_ptr(),
+            &n,
+            &n,
+            isuppz.as_mut_ptr(),
+            &mut tryrac,
+            work.as_mut_ptr(),
+            &(optimal_lwork as c_int),
+            iwork.as_mut_ptr(),
+            &(optimal_liwork as c_int),
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dstemr failed with error code {}",
+            info
+        )));
+    }
+    if found != n {
+        return Err(io::Error::other(format!(
+            "LAPACK dstemr returned {} of {} eigenpairs",
+            found, n
+        )));
+    }
+    drop(lapack_span);
+
+    // Both Q and Z are column-major; the eigenvectors of A are Q Z
+    let _span = stage_span!("vector_assembly", n = nu);
+    let q = Array2::from_shape_vec((nu, nu).f(), q).map_err(|e| io::Error::other(e.to_string()))?;
+    let z = Array2::from_shape_vec((nu, nu).f(), z).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok((Array1::from(eigvals), q.dot(&z)))
+}
+
+// dsyevd eigendecomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// Computes eigenvalues and eigenvectors for a dense symmetric matrix using LAPACK's dsyevd.
+pub fn compute_eigenvalues_and_vectors_dense(
+    matrix: &Array2<f64>,
+) -> io::Result<(Array1<f64>, Array2<f64>)> {
+    let n = matrix.nrows() as c_int;
+
+    // The matrix is symmetric, so its row-major data is also its column-major data.
+    // dsyevd overwrites it with the eigenvectors.
+    let mut a = matrix.as_standard_layout().to_owned();
+    let a_ptr = a.as_slice_mut().ok_or_else(|| {
+        io::Error::other("Failed to get a contiguous slice for the dense matrix.")
+    })?.as_mut_ptr();
+
+    let jobz = b'V' as c_char; // Compute eigenvalues and eigenvectors
+    let uplo = b'U' as c_char; // Upper triangle
+    let lda = n.max(1);
+    let mut eigvals = vec![0.0_f64; n as usize];
+
+    // Workspace query: set LWORK = -1 and LIWORK = -1
+    let mut work_query = vec![0.0_f64];
+    let mut iwork_query = vec![0_i32];
+    let mut info: c_int = 0;
+
+    let lapack_span = stage_span!("lapack.dsyevd", n = n as usize);
+    unsafe {
+        dsyevd_(
+            &jobz,
+            &uplo,
+            &n,
+            a_ptr,
+            &lda,
+            eigvals.as_mut_ptr(),
+            work_query.as_mut_ptr(),
+            &-1,
+            iwork_query.as_mut_ptr(),
+            &-1,
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dsyevd (workspace query) failed with error code {}",
+            info
+        )));
+    }
+
+    let optimal_lwork = work_query[0] as usize;
+    let optimal_liwork = iwork_query[0] as usize;
+    let mut work = vec![0.0_f64; optimal_lwork];
+    let mut iwork = vec![0_i32; optimal_liwork];
+
+    unsafe {
+        dsyevd_(
+            &jobz,
+            &uplo,
+            &n,
+            a_ptr,
+            &lda,
+            eigvals.as_mut_ptr(),
+            work.as_mut_ptr(),
+            &(optimal_lwork as c_int),
+            iwork.as_mut_ptr(),
+            &(optimal_liwork as c_int),
+            &mut info,
+        );
+    }
+
+    if info != 0 {
+        return Err(io::Error::other(format!(
+            "LAPACK dsyevd failed with error code {}",
+            info
+        )));
+    }
+    drop(lapack_span);
+
+    // The eigenvectors are stored column-major, i.e. as the rows of `a`
+    let _span = stage_span!("vector_assembly", n = n as usize);
+    Ok((Array1::from(eigvals), a.reversed_axes().as_standard_layout().to_owned()))
+}
+
+/// Converts the adjacency matrix edge list to ndarray::Array2<f64>
+pub fn adjacency_matrix_to_ndarray(
+    edges: &[(u32, u32)],
+    start_node: usize,
+    end_node: usize,
+) -> Array2<f64> {
+    let size = end_node - start_node + 1;
+    let mut adj_array = Array2::<f64>::zeros((size, size));
+    for &(a, b) in edges {
+        let local_a = a as usize - start_node;
+        let local_b = b as usize - start_node;
+        if local_a < size && local_b < size {
+            adj_array[(local_a, local_b)] = 1.0;
+            adj_array[(local_b, local_a)] = 1.0;
+        }
+    }
+    adj_array
+}
+
+/// Saves a 2D ndarray::Array2<f64> to a CSV file
+pub fn save_array_to_csv_dsbevd<P: AsRef<Path>>(matrix: &Array2<f64>, csv_path: P) -> io::Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(csv_path)?;
+    for row in matrix.rows() {
+        wtr.serialize(row.to_vec())?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Saves a 2D ndarray::Array2<f64> to a CSV file labeled with a header row of `column_names` and
+/// a leading column of `row_names`, e.g. the node names from `convert::load_gfa`.
+///
+/// The top-left cell is empty, so `pandas.read_csv(path, index_col=0)` picks up both labels. For a
+/// node-by-node matrix pass the node names twice; for eigenvectors (one row per node) name the
+/// columns after the eigenvalue indices. The output reads back with `read_labeled_csv`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if the number of names does not match the matrix dimensions.
+pub fn save_array_to_csv_labeled<P: AsRef<Path>>(
+    matrix: &Array2<f64>,
+    row_names: &[String],
+    column_names: &[String],
+    csv_path: P,
+) -> io::Result<()> {
+    if row_names.len() != matrix.nrows() || column_names.len() != matrix.ncols() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Got {} row and {} column names for a {} x {} matrix.",
+                row_names.len(),
+                column_names.len(),
+                matrix.nrows(),
+                matrix.ncols()
+            ),
+        ));
+    }
+
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(csv_path)?;
+    wtr.write_record(std::iter::once("").chain(column_names.iter().map(String::as_str)))?;
+    for (name, row) in row_names.iter().zip(matrix.rows()) {
+        let values = row.iter().map(|value| value.to_string());
+        wtr.write_record(std::iter::once(name.clone()).chain(values))?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// A matrix with named rows and columns, as read by `read_labeled_csv`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabeledMatrix {
+    pub matrix: Array2<f64>,
+    pub row_names: Vec<String>,
+    pub column_names: Vec<String>,
+}
+
+/// Reads a CSV file written by `save_array_to_csv_labeled`.
+///
+/// # Returns
+///
+/// * `Ok(LabeledMatrix)` - The matrix with its row and column names.
+/// * `Err(CsvError)` - If the file cannot be read, a cell is not a number, or the rows differ in length.
+pub fn read_labeled_csv<P: AsRef<Path>>(csv_path: P) -> Result<LabeledMatrix, CsvError> {
+    // Flexible, so a ragged row reaches the length check below as `CsvError::Shape`
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(open_input(csv_path)?);
+    let mut records = reader.records();
+
+    let header = match records.next() {
+        Some(record) => record?,
+        None => {
+            return Ok(LabeledMatrix {
+                matrix: Array2::zeros((0, 0)),
+                row_names: Vec::new(),
+                column_names: Vec::new(),
+            })
+        }
+    };
+    let column_names: Vec<String> = header.iter().skip(1).map(str::to_string).collect();
+
+    let mut row_names = Vec::new();
+    let mut values = Vec::new();
+    for (row, record) in records.enumerate() {
+        let record = record?;
+        if record.len() != column_names.len() + 1 {
+            return Err(CsvError::Shape(format!(
+                "Row {} has {} columns, expected {}.",
+                row + 2,
+                record.len(),
+                column_names.len() + 1
+            )));
+        }
+        row_names.push(record[0].to_string());
+        for (column, token) in record.iter().enumerate().skip(1) {
+            let value = token.trim().parse::<f64>().map_err(|_| CsvError::Parse {
+                row: row + 2,
+                column: column + 1,
+                token: token.to_string(),
+            })?;
+            values.push(value);
+        }
+    }
+
+    let matrix = Array2::from_shape_vec((row_names.len(), column_names.len()), values)
+        .map_err(|error| CsvError::Shape(error.to_string()))?;
+    Ok(LabeledMatrix {
+        matrix,
+        row_names,
+        column_names,
+    })
+}
+
+/// Saves a 2D ndarray::Array2<f64> to a gzip-compressed CSV file (requires the `gzip` feature).
+/// The output reads back with `read_array_from_csv`.
+#[cfg(feature = "gzip")]
+pub fn save_array_to_csv_gz<P: AsRef<Path>>(matrix: &Array2<f64>, csv_path: P) -> io::Result<()> {
+    let encoder = flate2::write::GzEncoder::new(
+        io::BufWriter::new(std::fs::File::create(csv_path)?),
+        flate2::Compression::default(),
+    );
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(encoder);
+    for row in matrix.rows() {
+        wtr.serialize(row.to_vec())?;
+    }
+    let encoder = wtr.into_inner().map_err(|error| error.into_error())?;
+    encoder.finish()?.flush()?;
+    Ok(())
+}
+
+/// Saves a 1D ndarray::Array1<f64> to a CSV file
+pub fn save_vector_to_csv_dsbevd<P: AsRef<Path>>(vector: &Array1<f64>, csv_path: P) -> io::Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(csv_path)?;
+    let row = vector.iter().cloned().collect::<Vec<f64>>();
+    wtr.serialize(row)?;
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Reads a 2D CSV file (no header) as written by `save_array_to_csv_dsbevd`. Gzipped files are
+/// decompressed transparently with the `gzip` feature (see `convert::open_input`).
+///
+/// # Returns
+///
+/// * `Ok(Array2<f64>)` - The parsed matrix.
+/// * `Err(CsvError)` - If the file cannot be read, a cell is not a number, or the rows differ in length.
+pub fn read_array_from_csv<P: AsRef<Path>>(csv_path: P) -> Result<Array2<f64>, CsvError> {
+    let mut values = Vec::new();
+    let (num_rows, num_cols) = for_each_csv_row(csv_path, |_, row| {
+        values.extend_from_slice(row);
+        Ok(())
+    })?;
+
+    Array2::from_shape_vec((num_rows, num_cols), values)
+        .map_err(|error| CsvError::Shape(error.to_string()))
+}
+
+/// Parses a CSV file (no header) one row at a time, so only a single row is held in memory.
+/// Every row must have the length of the first; `visit` gets the 0-based row index and the values.
+///
+/// Returns the number of rows and columns read.
+fn for_each_csv_row<P, F>(csv_path: P, mut visit: F) -> Result<(usize, usize), CsvError>
+where
+    P: AsRef<Path>,
+    F: FnMut(usize, &[f64]) -> Result<(), CsvError>,
+{
+    // Flexible, so a ragged row reaches the length check below as `CsvError::Shape`
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(open_input(csv_path)?);
+
+    let mut values = Vec::new();
+    let mut num_cols = None;
+    let mut num_rows = 0;
+    for (row, record) in reader.records().enumerate() {
+        let record = record?;
+        match num_cols {
+            None => num_cols = Some(record.len()),
+            Some(expected) if expected != record.len() => {
+                return Err(CsvError::Shape(format!(
+                    "Row {} has {} columns, expected {}.",
+                    row + 1,
+                    record.len(),
+                    expected
+                )));
+            }
+            _ => {}
+        }
+        values.clear();
+        for (column, token) in record.iter().enumerate() {
+            let value = token.trim().parse::<f64>().map_err(|_| CsvError::Parse {
+                row: row + 1,
+                column: column + 1,
+                token: token.to_string(),
+            })?;
+            values.push(value);
+        }
+        visit(row, &values)?;
+        num_rows += 1;
+    }
+
+    Ok((num_rows, num_cols.unwrap_or(0)))
+}
+
+/// Computes the `max_band` of a square CSV adjacency in one streaming pass, without loading it.
+pub fn csv_max_band<P: AsRef<Path>>(csv_path: P) -> Result<usize, CsvError> {
+    let mut kd = 0;
+    let (rows, cols) = for_each_csv_row(csv_path, |i, row| {
+        if let Some(j) = row.iter().rposition(|&value| value != 0.0) {
+            kd = kd.max(j.abs_diff(i));
+        }
+        if let Some(j) = row.iter().position(|&value| value != 0.0) {
+            kd = kd.max(j.abs_diff(i));
+        }
+        Ok(())
+    })?;
+    check_square_csv(rows, cols)?;
+    Ok(kd)
+}
+
+/// Streams a square CSV adjacency into the Laplacian `kind` in `to_banded_format` storage
+/// (upper, `(kd + 1) x n`), never forming a dense matrix.
+///
+/// Degrees are the full row sums; the rest of the band is read from the upper triangle, so the
+/// adjacency is assumed symmetric. Isolated nodes follow the conventions of `build_laplacian`.
+/// Use `csv_max_band` for the smallest valid `kd`. `load_array_from_npy`, `save_array_to_npy`
+/// and `compute_eigenvalues_and_vectors_banded_range` accept the result directly.
+///
+/// # Errors
+///
+/// Returns `CsvError::Shape` if the matrix is not square or has a nonzero entry farther than `kd`
+/// from the diagonal, `CsvError::Io` with `InvalidInput` for the random-walk Laplacian, which is
+/// not symmetric and has no banded form, and the usual read and parse errors.
+pub fn csv_laplacian_to_banded<P: AsRef<Path>>(
+    csv_path: P,
+    kd: usize,
+    kind: LaplacianKind,
+) -> Result<Array2<f64>, CsvError> {
+    if !kind.is_symmetric() {
+        return Err(CsvError::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("The {} Laplacian is not symmetric and has no banded form.", kind),
+        )));
+    }
+
+    // First collect the adjacency band and the degrees; the normalized variant needs the degree
+    // of both endpoints of every entry, so the Laplacian is formed only once all rows are read.
+    let mut band = Array2::<f64>::zeros((kd + 1, 0));
+    let mut degrees = Vec::new();
+    let (rows, cols) = for_each_csv_row(csv_path, |i, row| {
+        let n = row.len();
+        if i == 0 {
+            band = Array2::<f64>::zeros((kd + 1, n));
+        }
+        if i >= n {
+            return Err(CsvError::Shape(format!("More than {} rows in a {}-column matrix.", n, n)));
+        }
+        if let Some(j) = row.iter().enumerate().position(|(j, &value)| value != 0.0 && j.abs_diff(i) > kd) {
+            return Err(CsvError::Shape(format!(
+                "Entry ({}, {}) lies outside the band kd = {}.",
+                i + 1,
+                j + 1,
+                kd
+            )));
+        }
+
+        degrees.push(row.iter().sum::<f64>());
+        for (j, &value) in row.iter().enumerate().take(n.min(i + kd + 1)).skip(i) {
+            band[[kd + i - j, j]] = value;
+        }
+        Ok(())
+    })?;
+    check_square_csv(rows, cols)?;
+
+    let inv_sqrt: Vec<f64> = degrees.iter().map(|&d| if d > 0.0 { 1.0 / d.sqrt() } else { 0.0 }).collect();
+    for ((r, j), value) in band.indexed_iter_mut() {
+        if j + r < kd {
+            continue;
+        }
+        let i = j + r - kd;
+        let diagonal = i == j;
+        *value = match kind {
+            LaplacianKind::Combinatorial if diagonal => degrees[i] - *value,
+            LaplacianKind::Combinatorial => -*value,
+            LaplacianKind::Signless if diagonal => degrees[i] + *value,
+            LaplacianKind::Signless => *value,
+            LaplacianKind::Normalized => {
+                let identity = if diagonal && degrees[i] > 0.0 { 1.0 } else { 0.0 };
+                identity - inv_sqrt[i] * *value * inv_sqrt[j]
+            }
+            LaplacianKind::RandomWalk => unreachable!("rejected above"),
+        };
+    }
+    Ok(band)
+}
+
+fn check_square_csv(rows: usize, cols: usize) -> Result<(), CsvError> {
+    if rows != cols {
+        return Err(CsvError::Shape(format!(
+            "Expected a square matrix, got {} x {}.",
+            rows, cols
+        )));
+    }
+    Ok(())
+}
+
+/// Reads a square matrix from a CSV file, e.g. a saved Laplacian or eigenvector matrix.
+/// See `read_array_from_csv`.
+pub fn read_square_csv<P: AsRef<Path>>(csv_path: P) -> Result<Array2<f64>, CsvError> {
+    let matrix = read_array_from_csv(csv_path)?;
+    if !matrix.is_square() {
+        return Err(CsvError::Shape(format!(
+            "Expected a square matrix, got {} x {}.",
+            matrix.nrows(),
+            matrix.ncols()
+        )));
+    }
+    Ok(matrix)
+}
+
+/// The magic string that starts every `.npy` file.
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Saves a 2D ndarray::Array2<f64> as a NumPy `.npy` file (format 1.0, little-endian f64, C order),
+/// e.g. to cache `to_banded_format` output. It loads with `numpy.load` and `load_array_from_npy`.
+pub fn save_array_to_npy<P: AsRef<Path>>(matrix: &Array2<f64>, npy_path: P) -> io::Result<()> {
+    write_npy(npy_path, &format!("({}, {})", matrix.nrows(), matrix.ncols()), matrix.iter())
+}
+
+/// Saves a 1D ndarray::Array1<f64> as a NumPy `.npy` file of shape `(n,)`, e.g. a spectrum.
+pub fn save_vector_to_npy<P: AsRef<Path>>(vector: &Array1<f64>, npy_path: P) -> io::Result<()> {
+    write_npy(npy_path, &format!("({},)", vector.len()), vector.iter())
+}
+
+/// Writes a version 1.0 `.npy` file with the given shape tuple and values in C order.
+fn write_npy<'a, P: AsRef<Path>>(npy_path: P, shape: &str, values: impl Iterator<Item = &'a f64>) -> io::Result<()> {
+    let mut header = format!("{{'descr': '<f8', 'fortran_order': False, 'shape': {}, }}", shape);
+    // Pad with spaces and a newline so the data starts on a 64-byte boundary
+    let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let mut writer = io::BufWriter::new(std::fs::File::create(npy_path)?);
+    writer.write_all(NPY_MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    for value in values {
+        writer.write_all(&value.to_le_bytes())?;
+    }
+    writer.flush()
+}
+
+/// Loads a 2D little-endian f64 NumPy `.npy` file, in C or Fortran order.
+///
+/// # Errors
+///
+/// Returns `InvalidData` if the file is not a `.npy` file, or holds another dtype or a shape that
+/// is not two-dimensional.
+pub fn load_array_from_npy<P: AsRef<Path>>(npy_path: P) -> io::Result<Array2<f64>> {
+    let bytes = std::fs::read(npy_path)?;
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    if bytes.len() < 10 || &bytes[..6] != NPY_MAGIC {
+        return Err(invalid("Not a .npy file.".to_string()));
+    }
+    let (header_start, header_len) = match bytes[6] {
+        1 => (10, u16::from_le_bytes([bytes[8], bytes[9]]) as usize),
+        2 | 3 if bytes.len() >= 12 => (12, u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize),
+        version => return Err(invalid(format!("Unsupported .npy version {}.", version))),
+    };
+    let data_start = header_start + header_len;
+    let header = bytes
+        .get(header_start..data_start)
+        .map(String::from_utf8_lossy)
+        .ok_or_else(|| invalid("Truncated .npy header.".to_string()))?;
+
+    if !header.contains("'descr': '<f8'") {
+        return Err(invalid(format!("Only little-endian f64 ('<f8') is supported, header: {}", header.trim())));
+    }
+    let fortran_order = header.contains("'fortran_order': True");
+    let shape: Vec<usize> = header
+        .split("'shape': (")
+        .nth(1)
+        .and_then(|rest| rest.split(')').next())
+        .ok_or_else(|| invalid("Missing shape in .npy header.".to_string()))?
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| dim.parse().map_err(|_| invalid(format!("Invalid dimension '{}'.", dim))))
+        .collect::<io::Result<_>>()?;
+    if shape.len() != 2 {
+        return Err(invalid(format!("Expected a 2D array, got shape {:?}.", shape)));
+    }
+
+    let values: Vec<f64> = bytes[data_start..]
+        .chunks_exact(8)
+        .map(|chunk| f64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes")))
+        .collect();
+    let dim = (shape[0], shape[1]);
+    let array = if fortran_order {
+        Array2::from_shape_vec(dim.f(), values)
+    } else {
+        Array2::from_shape_vec(dim, values)
+    };
+    array.map_err(|error| invalid(error.to_string()))
+}
+
+/// Saves a 2D ndarray::Array2<f64> as a Matrix Market coordinate file (`real general`, 1-based
+/// indices, nonzero entries only), which `scipy.io.mmread` and most sparse toolkits read.
+pub fn save_array_to_mtx<P: AsRef<Path>>(matrix: &Array2<f64>, mtx_path: P) -> io::Result<()> {
+    let nonzeros: Vec<((usize, usize), f64)> =
+        matrix.indexed_iter().filter(|(_, &value)| value != 0.0).map(|(index, &value)| (index, value)).collect();
+
+    let mut writer = io::BufWriter::new(std::fs::File::create(mtx_path)?);
+    writeln!(writer, "%%MatrixMarket matrix coordinate real general")?;
+    writeln!(writer, "{} {} {}", matrix.nrows(), matrix.ncols(), nonzeros.len())?;
+    for ((i, j), value) in nonzeros {
+        writeln!(writer, "{} {} {}", i + 1, j + 1, value)?;
+    }
+    writer.flush()
+}
+
+/// Formats a value as a JSON number; JSON has no NaN or infinity, so those become `null`.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Formats a string as a quoted JSON string.
+fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats the values as a JSON array.
+fn json_array<'a>(values: impl Iterator<Item = &'a f64>) -> String {
+    let values: Vec<String> = values.map(|&value| json_number(value)).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// Saves a 2D ndarray::Array2<f64> as JSON: an array of rows, each an array of numbers.
+pub fn save_array_to_json<P: AsRef<Path>>(matrix: &Array2<f64>, json_path: P) -> io::Result<()> {
+    let rows: Vec<String> = matrix.rows().into_iter().map(|row| json_array(row.iter())).collect();
+    let mut writer = io::BufWriter::new(std::fs::File::create(json_path)?);
+    writeln!(writer, "[{}]", rows.join(",\n "))?;
+    writer.flush()
+}
+
+/// Saves a 1D ndarray::Array1<f64> as a JSON array of numbers.
+pub fn save_vector_to_json<P: AsRef<Path>>(vector: &Array1<f64>, json_path: P) -> io::Result<()> {
+    let mut writer = io::BufWriter::new(std::fs::File::create(json_path)?);
+    writeln!(writer, "{}", json_array(vector.iter()))?;
+    writer.flush()
+}
+
+/// Saves a labeled matrix as JSON, `{"rows": [...], "columns": [...], "data": [[...], ...]}`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if the number of names does not match the matrix shape, and any I/O error.
+pub fn save_array_to_json_labeled<P: AsRef<Path>>(
+    matrix: &Array2<f64>,
+    row_names: &[String],
+    column_names: &[String],
+    json_path: P,
+) -> io::Result<()> {
+    if row_names.len() != matrix.nrows() || column_names.len() != matrix.ncols() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Got {} row and {} column names for a {} x {} matrix.",
+                row_names.len(),
+                column_names.len(),
+                matrix.nrows(),
+                matrix.ncols()
+            ),
+        ));
+    }
+
+    let names = |names: &[String]| {
+        let quoted: Vec<String> = names.iter().map(|name| json_string(name)).collect();
+        format!("[{}]", quoted.join(", "))
+    };
+    let rows: Vec<String> = matrix.rows().into_iter().map(|row| json_array(row.iter())).collect();
+    let mut writer = io::BufWriter::new(std::fs::File::create(json_path)?);
+    writeln!(
+        writer,
+        "{{\"rows\": {}, \"columns\": {}, \"data\": [{}]}}",
+        names(row_names),
+        names(column_names),
+        rows.join(", ")
+    )?;
+    writer.flush()
+}
+
+/// The file formats an output subcommand can write with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Comma-separated values, no header (`save_array_to_csv_dsbevd`)
+    Csv,
+    /// NumPy `.npy`, little-endian f64
+    Npy,
+    /// Matrix Market coordinate format, nonzeros only
+    Mtx,
+    /// JSON arrays
+    Json,
+}
+
+/// The kinds of data an output subcommand writes, for `OutputFormat::check_supports`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputData {
+    /// A plain matrix, e.g. a Laplacian or eigenvectors.
+    Matrix,
+    /// A 1D vector, e.g. a spectrum.
+    Vector,
+    /// A matrix with row and column names, e.g. the spectral distance table.
+    LabeledMatrix,
+}
+
+impl OutputFormat {
+    /// The file extension of this format, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Npy => "npy",
+            OutputFormat::Mtx => "mtx",
+            OutputFormat::Json => "json",
+        }
+    }
+
+    /// Checks that this format can hold `data`. Matrices and vectors go to every format (a vector
+    /// is a one-column Matrix Market matrix); names only fit in CSV and JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` for a labeled matrix in `npy` or `mtx`.
+    pub fn check_supports(self, data: OutputData) -> io::Result<()> {
+        match (self, data) {
+            (OutputFormat::Npy | OutputFormat::Mtx, OutputData::LabeledMatrix) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The {} format cannot store row and column names; use csv or json.", self.extension()),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Saves a matrix in the selected format.
+pub fn save_matrix<P: AsRef<Path>>(matrix: &Array2<f64>, path: P, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Csv => save_array_to_csv_dsbevd(matrix, path),
+        OutputFormat::Npy => save_array_to_npy(matrix, path),
+        OutputFormat::Mtx => save_array_to_mtx(matrix, path),
+        OutputFormat::Json => save_array_to_json(matrix, path),
+    }
+}
+
+/// Saves a vector in the selected format (a single CSV row, a `(n,)` `.npy` array, an `n x 1`
+/// Matrix Market matrix, or a JSON array).
+pub fn save_vector<P: AsRef<Path>>(vector: &Array1<f64>, path: P, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Csv => save_vector_to_csv_dsbevd(vector, path),
+        OutputFormat::Npy => save_vector_to_npy(vector, path),
+        OutputFormat::Mtx => save_array_to_mtx(&vector.clone().insert_axis(Axis(1)), path),
+        OutputFormat::Json => save_vector_to_json(vector, path),
+    }
+}
+
+// Block tridiagonal section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// A symmetric block tridiagonal matrix, e.g. the Laplacian of a graph linearized along a reference
+/// where every node only links to nodes in its own or a neighbouring window.
+///
+/// Block `i` on the diagonal is `diagonal[i]`; `off_diagonal[i]` couples block `i` (rows) with block
+/// `i + 1` (columns), and its transpose fills the mirrored position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockTridiagonal {
+    diagonal: Vec<Array2<f64>>,
+    off_diagonal: Vec<Array2<f64>>,
+}
+
+impl BlockTridiagonal {
+    /// Creates a block tridiagonal matrix, checking that the diagonal blocks are square and symmetric
+    /// and that each off-diagonal block has the shape `size_i x size_{i+1}`.
+    pub fn new(diagonal: Vec<Array2<f64>>, off_diagonal: Vec<Array2<f64>>) -> Result<Self, EigenError> {
+        if off_diagonal.len() + 1 != diagonal.len().max(1) {
+            return Err(EigenError::InvalidInput(format!(
+                "Expected {} off-diagonal blocks for {} diagonal blocks, got {}.",
+                diagonal.len().saturating_sub(1),
+                diagonal.len(),
+                off_diagonal.len()
+            )));
+        }
+
+        for (i, block) in diagonal.iter().enumerate() {
+            if !block.is_square() {
+                return Err(EigenError::InvalidInput(format!("Diagonal block {} is not square.", i)));
+            }
+            if block.iter().zip(block.t().iter()).any(|(a, b)| a != b) {
+                return Err(EigenError::InvalidInput(format!("Diagonal block {} is not symmetric.", i)));
+            }
+        }
+
+        for (i, block) in off_diagonal.iter().enumerate() {
+            let expected = (diagonal[i].nrows(), diagonal[i + 1].nrows());
+            if block.dim() != expected {
+                return Err(EigenError::InvalidInput(format!(
+                    "Off-diagonal block {} has shape {:?}, expected {:?}.",
+                    i,
+                    block.dim(),
+                    expected
+                )));
+            }
+        }
+
+        Ok(BlockTridiagonal { diagonal, off_diagonal })
+    }
+
+    /// Splits a dense symmetric matrix into blocks of the given sizes, checking that every entry
+    /// outside the block tridiagonal pattern is zero.
+    pub fn from_dense(matrix: &Array2<f64>, block_sizes: &[usize]) -> Result<Self, EigenError> {
+        let n: usize = block_sizes.iter().sum();
+        if matrix.dim() != (n, n) {
+            return Err(EigenError::InvalidInput(format!(
+                "Block sizes sum to {}, but the matrix is {}x{}.",
+                n,
+                matrix.nrows(),
+                matrix.ncols()
+            )));
+        }
+
+        let mut offsets = vec![0];
+        for &size in block_sizes {
+            offsets.push(offsets.last().unwrap() + size);
+        }
+        let block_of = |index: usize| offsets.partition_point(|&offset| offset <= index) - 1;
+
+        for ((i, j), &value) in matrix.indexed_iter() {
+            if value != 0.0 && block_of(i).abs_diff(block_of(j)) > 1 {
+                return Err(EigenError::InvalidInput(format!(
+                    "Entry ({}, {}) lies outside the block tridiagonal pattern.",
+                    i, j
+                )));
+            }
+        }
+
+        let block = |a: usize, b: usize| {
+            matrix
+                .slice(s![offsets[a]..offsets[a + 1], offsets[b]..offsets[b + 1]])
+                .to_owned()
+        };
+        let diagonal = (0..block_sizes.len()).map(|b| block(b, b)).collect();
+        let off_diagonal = (1..block_sizes.len()).map(|b| block(b - 1, b)).collect();
+
+        Self::new(diagonal, off_diagonal)
+    }
+
+    /// Sizes of the diagonal blocks.
+    pub fn block_sizes(&self) -> Vec<usize> {
+        self.diagonal.iter().map(|block| block.nrows()).collect()
+    }
+
+    /// Total dimension of the matrix.
+    pub fn dim(&self) -> usize {
+        self.diagonal.iter().map(|block| block.nrows()).sum()
+    }
+
+    /// Upper bound on the bandwidth implied by the block structure: `max(size_i + size_{i+1}) - 1`.
+    pub fn block_bandwidth(&self) -> usize {
+        let sizes = self.block_sizes();
+        let within = sizes.iter().map(|s| s.saturating_sub(1)).max().unwrap_or(0);
+        let across = sizes.windows(2).map(|pair| pair[0] + pair[1] - 1).max().unwrap_or(0);
+        within.max(across)
+    }
+
+    /// Assembles the full symmetric matrix.
+    pub fn to_dense(&self) -> Array2<f64> {
+        let n = self.dim();
+        let mut dense = Array2::<f64>::zeros((n, n));
+
+        let mut offset = 0;
+        for (i, block) in self.diagonal.iter().enumerate() {
+            let size = block.nrows();
+            dense.slice_mut(s![offset..offset + size, offset..offset + size]).assign(block);
+            if let Some(coupling) = self.off_diagonal.get(i) {
+                let next = offset + size;
+                let next_size = coupling.ncols();
+                dense.slice_mut(s![offset..next, next..next + next_size]).assign(coupling);
+                dense.slice_mut(s![next..next + next_size, offset..next]).assign(&coupling.t());
+            }
+            offset += size;
+        }
+
+        dense
+    }
+}
+
+/// Computes the eigendecomposition of a block tridiagonal matrix.
+///
+/// This currently assembles the matrix and runs dsbevd with the bandwidth found by `max_band`,
+/// which is never larger than `block_bandwidth`. A solver that works on the blocks directly
+/// (e.g. block divide and conquer) can replace it without changing the signature.
+/// Eigenvalues are returned in ascending order with the matching eigenvectors as columns.
+pub fn compute_eigenvalues_block_tridiagonal(
+    matrix: &BlockTridiagonal,
+) -> io::Result<(Array1<f64>, Array2<f64>)> {
+    let dense = matrix.to_dense();
+    let kd = max_band(&dense);
+    compute_eigenvalues_and_vectors_sym_band(&dense, kd)
+}
+
+// Component decomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// The eigendecomposition of one connected component (see `decompose_by_components`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDecomposition {
+    /// Indices of the component's nodes in the full matrix, ascending. Row `k` of `eigvecs`
+    /// belongs to node `nodes[k]`.
+    pub nodes: Vec<usize>,
+    /// The component's eigenvalues in ascending order.
+    pub eigvals: Array1<f64>,
+    /// The component's eigenvectors as columns.
+    pub eigvecs: Array2<f64>,
+}
+
+/// Decomposes a block-diagonal symmetric matrix (e.g. the Laplacian of a disconnected graph) one
+/// connected component at a time with `call_eigendecomp`.
+///
+/// The union of the per-component spectra is the full spectrum, at a cost of `sum n_c^3` instead
+/// of `n^3`. Components come in the order of `graph::connected_components`.
+pub fn decompose_by_components(matrix: &Array2<f64>) -> io::Result<Vec<ComponentDecomposition>> {
+    connected_components(matrix)
+        .into_iter()
+        .map(|nodes| decompose_component(matrix, nodes))
+        .collect()
+}
+
+/// Same as `decompose_by_components`, decomposing the components concurrently on the rayon
+/// thread pool. The output order and contents match the serial version.
+pub fn decompose_by_components_parallel(matrix: &Array2<f64>) -> io::Result<Vec<ComponentDecomposition>> {
+    connected_components(matrix)
+        .into_par_iter()
+        .map(|nodes| decompose_component(matrix, nodes))
+        .collect()
+}
+
+/// Decomposes the principal submatrix of `matrix` on `nodes`.
+fn decompose_component(matrix: &Array2<f64>, nodes: Vec<usize>) -> io::Result<ComponentDecomposition> {
+    let submatrix = matrix.select(Axis(0), &nodes).select(Axis(1), &nodes);
+    let (eigvals, eigvecs) = call_eigendecomp(&submatrix)?;
+    Ok(ComponentDecomposition { nodes, eigvals, eigvecs })
+}
+
+// Background decomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// The eigenpairs produced by a decomposition (see `call_eigendecomp`).
+type Eigenpairs = (Array1<f64>, Array2<f64>);
+
+/// A decomposition running on a worker thread, returned by `spawn_eigendecomp`.
+///
+/// Poll it with `try_result` (e.g. from a UI loop) or block on it with `join`. Dropping the handle
+/// detaches the worker, which finishes on its own and discards its result.
+#[derive(Debug)]
+pub struct EigenHandle {
+    receiver: std::sync::mpsc::Receiver<io::Result<Eigenpairs>>,
+    worker: Option<std::thread::JoinHandle<()>>,
+    result: Option<io::Result<Eigenpairs>>,
+}
+
+impl EigenHandle {
+    /// Returns the result if the decomposition has finished, without blocking. Once it has
+    /// arrived, every later call returns the same result.
+    pub fn try_result(&mut self) -> Option<&io::Result<Eigenpairs>> {
+        if self.result.is_none() {
+            match self.receiver.try_recv() {
+                Ok(result) => self.result = Some(result),
+                Err(std::sync::mpsc::TryRecvError::Empty) => return None,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => self.result = Some(Err(worker_panicked())),
+            }
+            if let Some(worker) = self.worker.take() {
+                let _ = worker.join();
+            }
+        }
+        self.result.as_ref()
+    }
+
+    /// Waits for the decomposition to finish and returns its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns the decomposition's error, or `io::ErrorKind::Other` if the worker panicked.
+    pub fn join(mut self) -> io::Result<Eigenpairs> {
+        if let Some(result) = self.result.take() {
+            return result;
+        }
+        let result = self.receiver.recv().unwrap_or_else(|_| Err(worker_panicked()));
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        result
+    }
+}
+
+/// The error reported when the worker thread dies without sending a result.
+fn worker_panicked() -> io::Error {
+    io::Error::other("The eigendecomposition worker thread panicked.")
+}
+
+/// Runs `call_eigendecomp` on `matrix` in a new thread and returns immediately, so an interactive
+/// caller can stay responsive while LAPACK works.
+///
+/// The matrix is moved into the worker. LAPACK itself may be multithreaded (e.g. OpenBLAS), so
+/// several concurrent handles compete for the same cores.
+pub fn spawn_eigendecomp(matrix: Array2<f64>) -> EigenHandle {
+    spawn_job(move || call_eigendecomp(&matrix))
+}
+
+/// Runs `job` on a new thread behind an `EigenHandle`.
+fn spawn_job<F>(job: F) -> EigenHandle
+where
+    F: FnOnce() -> io::Result<Eigenpairs> + Send + 'static,
+{
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let worker = std::thread::spawn(move || {
+        // The receiver may already be gone if the handle was dropped
+        let _ = sender.send(job());
+    });
+    EigenHandle {
+        receiver,
+        worker: Some(worker),
+        result: None,
+    }
+}
+
+/// Runs `call_eigendecomp` on a worker thread and gives up waiting after `timeout`, so a job on a
+/// shared machine cannot hold the caller indefinitely.
+///
+/// The matrix is copied into the worker. LAPACK routines cannot be interrupted, so on timeout the
+/// worker is detached rather than cancelled: it keeps its cores and memory until the routine
+/// returns, then discards the result. Control returns to the caller promptly either way; to
+/// actually reclaim the resources, run the job in a separate process and kill it.
+///
+/// # Errors
+///
+/// * `EigenError::Timeout` - If no result arrived within `timeout`.
+/// * `EigenError::Io` - If the decomposition itself failed, with the kind and message of its
+///   `io::Error`, or the worker panicked (`io::ErrorKind::Other`).
+pub fn call_eigendecomp_with_timeout(
+    matrix: &Array2<f64>,
+    timeout: std::time::Duration,
+) -> Result<Eigenpairs, EigenError> {
+    let matrix = matrix.clone();
+    run_eigendecomp_with_timeout(move || call_eigendecomp(&matrix), timeout)
+}
+
+/// Runs any decomposition `job` (e.g. `call_eigendecomp_with_options` with captured options) on a
+/// worker thread with the same time limit and detach-on-timeout behavior as
+/// `call_eigendecomp_with_timeout`.
+///
+/// # Errors
+///
+/// The same as `call_eigendecomp_with_timeout`.
+pub fn run_eigendecomp_with_timeout<F>(job: F, timeout: std::time::Duration) -> Result<Eigenpairs, EigenError>
+where
+    F: FnOnce() -> io::Result<Eigenpairs> + Send + 'static,
+{
+    let handle = spawn_job(job);
+    match handle.receiver.recv_timeout(timeout) {
+        Ok(result) => result.map_err(EigenError::from),
+        Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(EigenError::Timeout { limit: timeout }),
+        Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => Err(worker_panicked().into()),
+    }
+}
+
+// SymmetricEigen eigendecomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// Computes eigenvalues and eigenvectors for a given Laplacian matrix with SymmetricEigen.
+/// Eigenpairs are returned in ascending order of eigenvalue, like LAPACK.
+pub fn compute_eigenvalues_and_vectors_sym(
+    laplacian: &Array2<f64>,
+) -> io::Result<(DVector<f64>, DMatrix<f64>)> {
+    // Convert ndarray::Array2<f64> to nalgebra::DMatrix<f64>
+    let nalgebra_laplacian = ndarray_to_nalgebra_matrix(laplacian)?;
+
+    // Compute eigendecomposition using nalgebra's SymmetricEigen
+    let symmetric_eigen = SymmetricEigen::new(nalgebra_laplacian);
+
+    // Sort eigenpairs in ascending order of eigenvalue to match LAPACK's convention
+    let n = symmetric_eigen.eigenvalues.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| symmetric_eigen.eigenvalues[a].total_cmp(&symmetric_eigen.eigenvalues[b]));
+
+    let eigvals = DVector::from_iterator(n, order.iter().map(|&i| symmetric_eigen.eigenvalues[i]));
+    let eigvecs = DMatrix::from_fn(n, n, |row, col| symmetric_eigen.eigenvectors[(row, order[col])]);
+
+    Ok((eigvals, eigvecs))
+}
+
+/// Converts an ndarray::Array2<f64> to nalgebra::DMatrix<f64>
+pub fn ndarray_to_nalgebra_matrix(matrix: &Array2<f64>) -> io::Result<DMatrix<f64>> {
+    let (rows, cols) = matrix.dim();
+    let mut nalgebra_matrix = DMatrix::<f64>::zeros(rows, cols);
+
+    for ((i, j), value) in matrix.indexed_iter() {
+        nalgebra_matrix[(i, j)] = *value;
+    }
+
+    Ok(nalgebra_matrix)
+}
+
+/// Saves a nalgebra::DMatrix<f64> to a CSV file
+pub fn save_nalgebra_matrix_to_csv<P: AsRef<Path>>(
+    matrix: &DMatrix<f64>,
+    csv_path: P,
+) -> io::Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(csv_path)?;
+    for i in 0..matrix.nrows() {
+        let row = matrix.row(i).iter().cloned().collect::<Vec<f64>>();
+        wtr.serialize(row)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Saves a nalgebra::DVector<f64> to a CSV file
+pub fn save_nalgebra_vector_to_csv<P: AsRef<Path>>(
+    vector: &DVector<f64>,
+    csv_path: P,
+) -> io::Result<()> {
+    let mut wtr = WriterBuilder::new()
+        .has_headers(false)
+        .from_path(csv_path)?;
+    let row = vector.iter().cloned().collect::<Vec<f64>>();
+    wtr.serialize(row)?;
+    wtr.flush()?;
+    Ok(())
+}
+
+
+// Backend comparison section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// The discrepancies between the LAPACK and SymmetricEigen decompositions of one matrix.
+#[derive(Debug, Clone)]
+pub struct BackendComparison {
+    /// `|λ_lapack - λ_symmetric|` for each eigenvalue, in ascending order.
+    pub eigenvalue_diffs: Array1<f64>,
+    /// Largest absolute entry difference of each canonicalized eigenvector pair.
+    pub eigenvector_diffs: Array1<f64>,
+    /// Largest entry of `eigenvalue_diffs` (zero for an empty matrix).
+    pub max_eigenvalue_diff: f64,
+    /// Largest entry of `eigenvector_diffs` (zero for an empty matrix).
+    pub max_eigenvector_diff: f64,
+    /// Whether both maxima are within the tolerance.
+    pub passed: bool,
+}
+
+/// Decomposes `matrix` with both LAPACK (`call_eigendecomp`) and nalgebra's SymmetricEigen and
+/// reports how far apart they are.
+///
+/// Both results go through `canonicalize_eigenpairs` first, so sign flips and rotations within
+/// degenerate eigenspaces do not count as mismatches.
+///
+/// # Arguments
+///
+/// * `matrix` - A symmetric matrix.
+/// * `tol` - The largest acceptable difference for both eigenvalues and eigenvector entries.
+///
+/// # Returns
+///
+/// The per-eigenpair differences and whether they pass `tol`.
+pub fn compare_backends(matrix: &Array2<f64>, tol: f64) -> io::Result<BackendComparison> {
+    let (lapack_vals, lapack_vecs) = call_eigendecomp(matrix)?;
+    let (lapack_vals, lapack_vecs) = canonicalize_eigenpairs(&lapack_vals, &lapack_vecs);
+
+    let (sym_vals, sym_vecs) = compute_eigenvalues_and_vectors_sym(matrix)?;
+    let sym_vals = Array1::from(sym_vals.as_slice().to_vec());
+    let sym_vecs = Array2::from_shape_fn(sym_vecs.shape(), |(i, j)| sym_vecs[(i, j)]);
+    let (sym_vals, sym_vecs) = canonicalize_eigenpairs(&sym_vals, &sym_vecs);
+
+    let eigenvalue_diffs = (&lapack_vals - &sym_vals).mapv(f64::abs);
+    let eigenvector_diffs = Array1::from_iter(
+        lapack_vecs
+            .columns()
+            .into_iter()
+            .zip(sym_vecs.columns())
+            .map(|(a, b)| a.iter().zip(b.iter()).fold(0.0_f64, |acc, (x, y)| acc.max((x - y).abs()))),
+    );
+
+    let max_eigenvalue_diff = eigenvalue_diffs.iter().fold(0.0_f64, |acc, &v| acc.max(v));
+    let max_eigenvector_diff = eigenvector_diffs.iter().fold(0.0_f64, |acc, &v| acc.max(v));
+
+    Ok(BackendComparison {
+        eigenvalue_diffs,
+        eigenvector_diffs,
+        max_eigenvalue_diff,
+        max_eigenvector_diff,
+        passed: max_eigenvalue_diff <= tol && max_eigenvector_diff <= tol,
+    })
+}
+
+/// Compares two spectra entry by entry, accepting
+/// `|a_i - b_i| <= max(abs_tol, rel_tol * max(|a_i|, |b_i|))`.
+///
+/// Different LAPACK builds (and x86 versus ARM) disagree in the last few ULPs, which a fixed
+/// absolute tolerance either misses on large eigenvalues or hides on small ones. The absolute part
+/// covers eigenvalues near zero, the relative part scales with the magnitude.
+///
+/// # Returns
+///
+/// `true` if both spectra have the same length and every pair is close; `false` otherwise,
+/// including when either side has a NaN.
+pub fn spectra_approx_eq(a: &Array1<f64>, b: &Array1<f64>, abs_tol: f64, rel_tol: f64) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b.iter()).all(|(&x, &y)| {
+            x == y || (x - y).abs() <= abs_tol.max(rel_tol * x.abs().max(y.abs()))
+        })
+}
+
+
+// Compute Normalized Global Eigen-Complexity (NGEC) =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// Eigenvalues within this distance of zero, relative to `max(1, |λ_max|)`, are numerical zeros.
+/// Every spectral measure (NGEC, entropy, spanning trees, diameter bound, Kirchhoff index,
+/// commute times and embeddings) clamps with it through `clamp_spectrum`, so they agree on which
+/// eigenvalues vanish.
+pub const DEFAULT_SPECTRUM_FLOOR: f64 = 1e-9;
+
+/// Sets every eigenvalue with `|λ| <= relative_spectrum_floor(eigenvalues, floor)` to exactly
+/// zero, leaving the others unchanged.
+///
+/// Tiny negative eigenvalues of a positive semidefinite matrix are rounding noise; clamping them
+/// keeps logarithms and normalizations well defined. The floor scales with the spectrum, so the
+/// same `floor` works for weighted Laplacians of any scale and is exactly `floor` for a spectrum
+/// within `[-1, 1]`. Significantly negative values are kept so callers can still reject them.
+pub fn clamp_spectrum(eigenvalues: &Array1<f64>, floor: f64) -> Array1<f64> {
+    let floor = relative_spectrum_floor(eigenvalues, floor);
+    eigenvalues.mapv(|x| if x.abs() <= floor { 0.0 } else { x })
+}
+
+/// Scales `floor` by the spectrum's magnitude, `floor * max(1, max |λ|)`: the threshold
+/// `clamp_spectrum` applies.
+///
+/// Rounding noise in a computed eigenvalue grows with `||L||`, so a fixed floor misses the noise
+/// once the weights are large. Pass the result to `log_pseudo_determinant` or
+/// `count_trivial_modes` to match `clamp_spectrum`.
+pub fn relative_spectrum_floor(eigenvalues: &Array1<f64>, floor: f64) -> f64 {
+    floor * eigenvalues.iter().fold(1.0_f64, |acc, v| acc.max(v.abs()))
+}
+
+/// Returns the normalized eigenvalue distribution `p_i = λ_i / Σ λ` that the von Neumann entropy
+/// and NGEC are computed from, e.g. to plot where the entropy comes from.
+/// Eigenvalues are clamped with `clamp_spectrum` and `DEFAULT_SPECTRUM_FLOOR` first.
+///
+/// # Returns
+///
+/// * `Ok(Array1<f64>)` - The nonnegative distribution, summing to one, in the input order.
+/// * `Err(EigenError)` - If the spectrum is empty, has significant negative values, or sums to zero.
+pub fn ngec_distribution(eigenvalues: &Array1<f64>) -> Result<Array1<f64>, EigenError> {
+    ngec_distribution_with_floor(eigenvalues, DEFAULT_SPECTRUM_FLOOR)
+}
+
+/// Same as `ngec_distribution`, clamping with `floor` instead of `DEFAULT_SPECTRUM_FLOOR`. Lower it
+/// to keep a genuinely tiny eigenvalue (e.g. a small Fiedler value) from being clamped to zero.
+pub fn ngec_distribution_with_floor(eigenvalues: &Array1<f64>, floor: f64) -> Result<Array1<f64>, EigenError> {
+    if eigenvalues.is_empty() {
+        return Err(EigenError::InvalidInput("Eigenvalues array is empty.".to_string()));
+    }
+
+    // Eigenvalues still negative after clamping are significantly below zero
+    let clamped = clamp_spectrum(eigenvalues, floor);
+    if clamped.iter().any(|&x| x < 0.0) {
+        let negative_eigenvalues: Vec<f64> = clamped
+            .iter()
+            .filter(|&&x| x < 0.0)
+            .cloned()
+            .take(5)
+            .collect();
+    
+        println!("❗ Significant negative eigenvalues found: {:?}", negative_eigenvalues);
+    
+        return Err(EigenError::InvalidInput(
+            "Eigenvalues contain significant negative values.".to_string(),
+        ));
+    }
+
+    let sum_eigen = clamped.sum();
+    if sum_eigen <= 0.0 {
+        return Err(EigenError::InvalidInput("Sum of eigenvalues must be positive.".to_string()));
+    }
+
+    Ok(clamped / sum_eigen)
+}
+
+/// Computes the von Neumann entropy `S = -Σ p_i ln p_i` of the density matrix `ρ = L / tr(L)`,
+/// whose eigenvalues are the `ngec_distribution` `p_i = λ_i / Σ λ`.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - A reference to an Array1<f64> containing the eigenvalues.
+///
+/// # Returns
+///
+/// * `Ok(f64)` - The entropy, between 0 and `ln(m)`.
+/// * `Err(io::Error)` - If the spectrum is empty, has significant negative values, or sums to zero.
+pub fn von_neumann_entropy(eigenvalues: &Array1<f64>) -> io::Result<f64> {
+    von_neumann_entropy_with_floor(eigenvalues, DEFAULT_SPECTRUM_FLOOR)
+}
+
+/// Same as `von_neumann_entropy`, clamping with `floor` (see `ngec_distribution_with_floor`).
+pub fn von_neumann_entropy_with_floor(eigenvalues: &Array1<f64>, floor: f64) -> io::Result<f64> {
+    let distribution = ngec_distribution_with_floor(eigenvalues, floor)?;
+
+    // Zero eigenvalues contribute nothing (0 ln 0 = 0)
+    let entropy: f64 = distribution
+        .iter()
+        .filter(|&&p| p > 0.0)
+        .map(|&p| -p * p.ln())
+        .sum();
+
+    Ok(entropy)
+}
+
+/// Computes the Normalized Global Eigen-Complexity (NGEC) based on eigenvalues: the von Neumann
+/// entropy divided by its maximum `ln(m)`.
+/// Ignores eigenvalues that are negative within `DEFAULT_SPECTRUM_FLOOR` due to floating-point precision.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - A reference to an Array1<f64> containing the eigenvalues.
+///
+/// # Returns
+///
+/// * `Ok(f64)` - The computed NGEC value.
+/// * `Err(io::Error)` - If the computation fails due to invalid input.
+pub fn compute_ngec(eigenvalues: &Array1<f64>) -> io::Result<f64> {
+    compute_ngec_with_floor(eigenvalues, DEFAULT_SPECTRUM_FLOOR)
+}
+
+/// Same as `compute_ngec`, clamping with `floor` (see `ngec_distribution_with_floor`).
+pub fn compute_ngec_with_floor(eigenvalues: &Array1<f64>, floor: f64) -> io::Result<f64> {
+    let entropy = von_neumann_entropy_with_floor(eigenvalues, floor)?;
+
+    // Calculate log(m)
+    let log_m = (eigenvalues.len() as f64).ln();
+    if log_m == 0.0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Logarithm of the number of eigenvalues resulted in zero.",
+        ));
+    }
+
+    // Compute NGEC
+    let ngec = entropy / log_m;
+
+    Ok(ngec)
+}
+
+
+/// Computes an NGEC-style entropy from the adjacency spectrum instead of a Laplacian's.
+///
+/// Adjacency eigenvalues are indefinite (they sum to the number of self-loops, often zero), so
+/// they are shifted by the smallest one, `μ_i = λ_i - λ_min`, which makes them nonnegative with
+/// at least one zero, and the shifted spectrum goes through `compute_ngec`. The shift amounts to
+/// using `A - λ_min I`, so the value is not comparable to the Laplacian NGEC, only to itself
+/// across graphs.
+///
+/// # Returns
+///
+/// * `Ok(f64)` - The NGEC of the shifted adjacency spectrum, in `[0, 1]`.
+/// * `Err(EigenError)` - If the matrix is not square, the decomposition fails (see the
+///   `From<io::Error>` conversion, which keeps a `NotConverged` and the error kind), or the
+///   spectrum is constant (e.g. a graph without edges), so the shifted values sum to zero.
+pub fn compute_ngec_from_adjacency(adjacency: &Array2<f64>) -> Result<f64, EigenError> {
+    if !adjacency.is_square() {
+        return Err(EigenError::InvalidInput(format!(
+            "Adjacency matrix must be square, got {:?}.",
+            adjacency.dim()
+        )));
+    }
+
+    let (eigvals, _) = call_eigendecomp(adjacency)?;
+    let min = eigvals.iter().copied().fold(f64::INFINITY, f64::min);
+    let shifted = eigvals.mapv(|value| value - min);
+    Ok(compute_ngec(&shifted)?)
+}
+
+// Spectrum selection section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
+
+/// Returns the minimal number of eigenvalues, taken from the top of the spectrum, whose sum
+/// reaches `fraction` of the total spectral energy (the sum of all eigenvalues).
+///
+/// Assumes `eigenvalues` is sorted in ascending order, as returned by `call_eigendecomp`,
+/// so the top eigenvalues are the last entries. The selected eigenpairs are therefore the
+/// last `k` columns of the matching eigenvector matrix.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - Eigenvalues in ascending order.
+/// * `fraction` - Fraction of the total energy to capture, clamped to `[0, 1]`.
+///
+/// # Returns
+///
+/// The number of eigenvalues needed. Returns every eigenvalue when `fraction` is 1 (including the
+/// zero eigenvalues of a Laplacian, which add no energy), and 0 when `fraction` is 0 or the total
+/// energy is not positive.
+pub fn select_by_energy(eigenvalues: &Array1<f64>, fraction: f64) -> usize {
+    let fraction = fraction.clamp(0.0, 1.0);
+    if fraction >= 1.0 {
+        return eigenvalues.len();
+    }
+    let total: f64 = eigenvalues.sum();
+    if fraction == 0.0 || total <= 0.0 {
+        return 0;
+    }
+
+    let target = fraction * total;
+    let mut captured = 0.0;
+    for (count, &value) in eigenvalues.iter().rev().enumerate() {
+        captured += value;
+        if captured >= target {
+            return count + 1;
+        }
+    }
+
+    // Rounding in the running sum can leave it just short of the target
+    eigenvalues.len()
+}
+
+
+// Spectral statistics section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// Computes the consecutive-gap ratios `r_i = min(g_i, g_{i-1}) / max(g_i, g_{i-1})` of a sorted spectrum,
+/// where `g_i = λ_{i+1} - λ_i`.
+///
+/// The ratios do not require unfolding the spectrum, which makes them a convenient way to distinguish
+/// Poisson (uncorrelated, mean ≈ 0.386) from Wigner-Dyson (GOE, mean ≈ 0.536) level statistics.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - Eigenvalues in ascending order.
+///
+/// # Returns
+///
+/// An array of `n - 2` ratios in `[0, 1]` (empty for fewer than three eigenvalues).
+/// Two consecutive zero gaps (a degenerate triplet) are treated as equal gaps with ratio 1.
+pub fn gap_ratios(eigenvalues: &Array1<f64>) -> Array1<f64> {
+    let gaps: Vec<f64> = eigenvalues
+        .windows(2)
+        .into_iter()
+        .map(|pair| pair[1] - pair[0])
+        .collect();
+
+    gaps.windows(2)
+        .map(|pair| {
+            let (smaller, larger) = if pair[0] <= pair[1] { (pair[0], pair[1]) } else { (pair[1], pair[0]) };
+            if larger == 0.0 {
+                1.0
+            } else {
+                smaller / larger
+            }
+        })
+        .collect()
+}
+
+/// Computes the mean consecutive-gap ratio of a sorted spectrum (see `gap_ratios`).
+///
+/// Returns `None` if the spectrum has fewer than three eigenvalues.
+pub fn mean_gap_ratio(eigenvalues: &Array1<f64>) -> Option<f64> {
+    gap_ratios(eigenvalues).mean()
+}
+
+/// Groups a spectrum into degenerate multiplets, e.g. to expose the symmetry of a graph
+/// (automorphisms usually force repeated eigenvalues).
+///
+/// Eigenvalues are sorted and consecutive values at most `tol` apart are chained into one multiplet,
+/// so a multiplet can span more than `tol` if it is spread out in small steps.
+///
+/// # Returns
+///
+/// One `(mean, multiplicity)` pair per multiplet, in ascending order. The multiplicities sum to
+/// the number of eigenvalues.
+pub fn degeneracy_structure(eigenvalues: &Array1<f64>, tol: f64) -> Vec<(f64, usize)> {
+    let mut sorted = eigenvalues.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let mut multiplets = Vec::new();
+    let mut start = 0;
+    while start < sorted.len() {
+        let mut end = start + 1;
+        while end < sorted.len() && sorted[end] - sorted[end - 1] <= tol {
+            end += 1;
+        }
+        let members = &sorted[start..end];
+        multiplets.push((members.iter().sum::<f64>() / members.len() as f64, members.len()));
+        start = end;
+    }
+    multiplets
+}
+
+/// Computes the natural logarithm of the number of spanning trees from the Laplacian spectrum.
+///
+/// By Kirchhoff's matrix-tree theorem the count is `(1 / n) * Π λ_i` over the nonzero eigenvalues;
+/// the product is accumulated in log space so large graphs do not overflow. Eigenvalues are
+/// clamped with `clamp_spectrum` and `DEFAULT_SPECTRUM_FLOOR`, i.e. within `1e-9 * max(1, |λ_max|)`
+/// of zero they are treated as zero.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - The eigenvalues of a combinatorial Laplacian, in any order.
+///
+/// # Returns
+///
+/// The log of the spanning tree count, or negative infinity if the graph is disconnected
+/// (more than one zero eigenvalue) or the spectrum is empty.
+pub fn log_spanning_tree_count(eigenvalues: &Array1<f64>) -> f64 {
+    let n = eigenvalues.len();
+    let clamped = clamp_spectrum(eigenvalues, DEFAULT_SPECTRUM_FLOOR);
+
+    let zero_count = clamped.iter().filter(|&&v| v == 0.0).count();
+    if n == 0 || zero_count != 1 {
+        return f64::NEG_INFINITY;
+    }
+
+    log_pseudo_determinant(&clamped, 0.0) - (n as f64).ln()
+}
+
+/// Computes the log pseudo-determinant `Σ ln λ_i` over the eigenvalues above `tol`.
+///
+/// For a connected graph's Laplacian this is `ln(n * τ)`, where `τ` is the spanning tree count
+/// (see `log_spanning_tree_count`). Summing logarithms avoids the overflow of the plain product.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - The eigenvalues, in any order.
+/// * `tol` - Eigenvalues at or below this value are excluded. For numerical zeros use
+///   `relative_spectrum_floor(eigenvalues, DEFAULT_SPECTRUM_FLOOR)`, which scales with the weights.
+///
+/// # Returns
+///
+/// The log pseudo-determinant; zero (the empty product) if no eigenvalue exceeds `tol`.
+pub fn log_pseudo_determinant(eigenvalues: &Array1<f64>, tol: f64) -> f64 {
+    eigenvalues.iter().filter(|&&v| v > tol).map(|v| v.ln()).sum()
+}
+
+/// Computes the number of spanning trees from the Laplacian spectrum (see `log_spanning_tree_count`).
+///
+/// Returns infinity if the count exceeds the range of `f64`; use `log_spanning_tree_count` for large graphs.
+pub fn spanning_tree_count(eigenvalues: &Array1<f64>) -> f64 {
+    log_spanning_tree_count(eigenvalues).exp()
+}
+
+/// Computes the spectral distance `sqrt(Σ (λ_i - μ_i)^2)` between two spectra.
+///
+/// Both spectra are sorted ascending and the shorter one is padded with zeros at the bottom, so
+/// graphs of different sizes can be compared. For Laplacian spectra the padding is exact: it
+/// matches adding isolated nodes to the smaller graph.
+pub fn spectral_distance(a: &Array1<f64>, b: &Array1<f64>) -> f64 {
+    let sorted_padded = |spectrum: &Array1<f64>, len: usize| {
+        let mut values = vec![0.0; len - spectrum.len()];
+        values.extend(spectrum.iter().copied());
+        values.sort_by(f64::total_cmp);
+        values
+    };
+
+    let len = a.len().max(b.len());
+    sorted_padded(a, len)
+        .iter()
+        .zip(sorted_padded(b, len).iter())
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Computes the graph energy `E = Σ |λ_i|`.
+///
+/// The eigenvalues must be those of the **adjacency** matrix: the energy is the trace norm of `A`,
+/// a descriptor from chemical graph theory. For a Laplacian spectrum (all `λ >= 0`) it would
+/// collapse to `trace(L) = 2m`. The complete graph `K_n` has energy `2(n - 1)`.
+pub fn graph_energy(eigenvalues: &Array1<f64>) -> f64 {
+    eigenvalues.iter().map(|v| v.abs()).sum()
+}
+
+/// Computes the Estrada index `EE = Σ exp(λ_i)`, i.e. `trace(exp(A))`.
+///
+/// The eigenvalues must be those of the **adjacency** matrix, not a Laplacian: `trace(exp(A))`
+/// counts closed walks of every length weighted by `1 / k!`. The result overflows to infinity once
+/// the largest eigenvalue passes about 709; use `log_estrada_index` for such graphs.
+pub fn estrada_index(eigenvalues: &Array1<f64>) -> f64 {
+    eigenvalues.iter().map(|v| v.exp()).sum()
+}
+
+/// Computes `ln EE` (see `estrada_index`) with the log-sum-exp shift, so it stays finite for any
+/// spectrum. Returns negative infinity for an empty spectrum.
+pub fn log_estrada_index(eigenvalues: &Array1<f64>) -> f64 {
+    let max = eigenvalues.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + eigenvalues.iter().map(|v| (v - max).exp()).sum::<f64>().ln()
+}
+
+/// Computes the spectral upper bound on the diameter of Chung, Faber and Manteuffel,
+/// `floor(acosh(n - 1) / acosh((λ_max + λ_2) / (λ_max - λ_2))) + 1`, from a combinatorial
+/// Laplacian spectrum, where `λ_2` is the algebraic connectivity and `λ_max` the largest eigenvalue.
+///
+/// This is a cheap proxy for the diameter of a graph too large for breadth-first search. A small
+/// spectral gap relative to the spread makes the bound loose, so it is most informative for
+/// expander-like graphs. The `n - 1` numerator belongs to the combinatorial Laplacian; Chung's
+/// normalized-Laplacian version replaces it with `vol(G) / min degree`, which the spectrum alone
+/// does not determine, so a normalized spectrum here can undershoot the diameter.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - The eigenvalues of `combinatorial_laplacian`, in any order.
+/// * `n` - The number of nodes.
+///
+/// # Returns
+///
+/// The bound, or infinity if the graph is disconnected (a second zero eigenvalue after
+/// `clamp_spectrum` with `DEFAULT_SPECTRUM_FLOOR`). Graphs whose nonzero eigenvalues all coincide
+/// (within the same floor) are complete and get 1, and graphs with fewer than two nodes get 0.
+pub fn spectral_diameter_bound(eigenvalues: &Array1<f64>, n: usize) -> f64 {
+    if n < 2 || eigenvalues.len() < 2 {
+        return 0.0;
+    }
+    let mut sorted = clamp_spectrum(eigenvalues, DEFAULT_SPECTRUM_FLOOR).to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (gap, largest) = (sorted[1], sorted[sorted.len() - 1]);
+
+    if gap <= 0.0 {
+        return f64::INFINITY;
+    }
+    if largest - gap <= relative_spectrum_floor(eigenvalues, DEFAULT_SPECTRUM_FLOOR) {
+        return 1.0;
+    }
+    let ratio = (n as f64 - 1.0).acosh() / ((largest + gap) / (largest - gap)).acosh();
+    // An exact integer ratio (e.g. the path on 3 nodes) must not round down below it
+    (ratio + 1e-9).floor() + 1.0
+}
+
+/// Computes the Kirchhoff index `Kf = n Σ_{λ_k > 0} 1 / λ_k` from a combinatorial Laplacian
+/// spectrum, which equals the sum of effective resistances over all node pairs.
+///
+/// Every commute time is `vol(G)` times an effective resistance, so `Kf / C(n, 2)` is the average
+/// commute time between two nodes divided by `vol(G)`. The single zero eigenvalue (after
+/// `clamp_spectrum` with `DEFAULT_SPECTRUM_FLOOR`) is skipped; a disconnected graph has more than
+/// one and an infinite resistance between its components, so it gets infinity. The path on 3
+/// nodes, with spectrum `{0, 1, 3}`, has `Kf = 3 (1 + 1/3) = 4`.
+pub fn kirchhoff_index(eigenvalues: &Array1<f64>) -> f64 {
+    let n = eigenvalues.len() as f64;
+    let clamped = clamp_spectrum(eigenvalues, DEFAULT_SPECTRUM_FLOOR);
+    if clamped.iter().filter(|&&v| v == 0.0).count() > 1 {
+        return f64::INFINITY;
+    }
+    n * clamped.iter().filter(|&&v| v > 0.0).map(|v| 1.0 / v).sum::<f64>()
+}
+
+/// Computes the inverse participation ratio `IPR_k = Σ_i v_k[i]^4` of each eigenvector.
+///
+/// For a unit-norm vector the IPR ranges from `1 / n` (spread evenly over all nodes) to 1
+/// (concentrated on a single node), so a high IPR flags a localized eigenvector.
+///
+/// # Arguments
+///
+/// * `eigvecs` - Unit-norm eigenvectors as columns.
+///
+/// # Returns
+///
+/// One IPR per column.
+pub fn inverse_participation_ratios(eigvecs: &Array2<f64>) -> Array1<f64> {
+    eigvecs.map_axis(Axis(0), |column| column.iter().map(|v| v.powi(4)).sum())
+}
+
+
+// Power iteration section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+
+/// Computes the eigenvector centrality of a graph: the principal eigenvector of its adjacency matrix.
+///
+/// Uses power iteration on `A + I`, which has the same eigenvectors as `A` but makes the Perron root
+/// strictly dominant, so bipartite graphs (whose spectrum is symmetric about zero) still converge.
+/// By Perron–Frobenius the result is nonnegative for a nonnegative adjacency matrix.
+///
+/// # Arguments
+///
+/// * `adjacency` - Square, nonnegative, symmetric adjacency matrix.
+/// * `iters` - Maximum number of iterations.
+/// * `tol` - Convergence tolerance on the change of the iterate (Euclidean norm).
+///
+/// # Returns
+///
+/// * `Ok(Array1<f64>)` - The centrality vector, normalized to unit Euclidean length.
+/// * `Err(EigenError)` - If the input is invalid or the iteration did not converge within `iters` steps.
+pub fn eigenvector_centrality(
+    adjacency: &Array2<f64>,
+    iters: usize,
+    tol: f64,
+) -> Result<Array1<f64>, EigenError> {
+    let n = adjacency.nrows();
+    if n == 0 || adjacency.ncols() != n {
+        return Err(EigenError::InvalidInput(format!(
+            "Adjacency matrix must be square and non-empty, got {:?}.",
+            adjacency.dim()
+        )));
+    }
+    if adjacency.iter().any(|&x| x < 0.0) {
+        return Err(EigenError::InvalidInput(
+            "Eigenvector centrality requires nonnegative edge weights.".to_string(),
+        ));
+    }
+
+    let mut x = Array1::<f64>::from_elem(n, 1.0 / (n as f64).sqrt());
+    let mut residual = f64::INFINITY;
+
+    for _ in 0..iters {
+        // y = (A + I) x
+        let mut y = adjacency.dot(&x) + &x;
+        let norm = y.dot(&y).sqrt();
+        if norm == 0.0 {
+            return Err(EigenError::InvalidInput(
+                "Power iteration collapsed to the zero vector.".to_string(),
+            ));
+        }
+        y /= norm;
+
+        let diff = &y - &x;
+        residual = diff.dot(&diff).sqrt();
+        x = y;
+
+        if residual <= tol {
+            return Ok(x);
+        }
     }
 
-    // Extract optimal LWORK and LIWORK from the workspace query
-    let optimal_lwork = work_query[0] as usize;
-    let optimal_liwork = iwork_query[0] as usize;
+    Err(EigenError::NotConverged { iterations: iters, residual })
+}
 
-    // Allocate WORK and IWORK with optimal sizes
-    let mut work = vec![0.0_f64; optimal_lwork];
-    let mut iwork = vec![0_i32; optimal_liwork];
-    let mut eigvals = vec![0.0_f64; n as usize];
-    let mut eigvecs = vec![0.0_f64; (n * n) as usize]; // Flat array for eigenvectors
 
-    // TODO: LAPACK is NOT currently working and all output produced is wrong. Will fix
-    
-    // Perform the actual eigendecomposition with optimal WORK and IWORK
-    unsafe {
-        dsbevd_(
-            &jobz,
-            &uplo,
-            &n,
-            &kd,
-            banded_ptr as *mut f64,
-            &(kd + 1),
-            eigvals.as_mut_ptr(),
-            eigvecs.as_mut_ptr(),
-            &n,
-            work.as_mut_ptr(),
-            &(optimal_lwork as c_int),
-            iwork.as_mut_ptr(),
-            &(optimal_liwork as c_int),
-            &mut info,
-        );
-    }
+/// Maximum number of power iterations in `spectral_radius`.
+const SPECTRAL_RADIUS_MAX_ITERS: usize = 10_000;
 
-    if info != 0 {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("LAPACK dsbevd failed with error code {}", info),
-        ));
+/// Computes the spectral radius `max |λ|` of a symmetric matrix by power iteration, without a full
+/// decomposition. For a nonnegative adjacency matrix this is the Perron root.
+///
+/// Each step multiplies by `A` and estimates the radius as `||A x|| / ||x||` for the unit iterate
+/// `x`, which converges to `max |λ|` even when `λ` and `-λ` are both extreme (bipartite graphs),
+/// where the iterate itself oscillates. Convergence is geometric in `|λ_2| / |λ_1|`.
+///
+/// # Returns
+///
+/// * `Ok(f64)` - The spectral radius, once consecutive estimates differ by at most `tol` relative.
+/// * `Err(EigenError)` - If the matrix is not square and non-empty, or the estimate does not settle
+///   within `SPECTRAL_RADIUS_MAX_ITERS` steps.
+pub fn spectral_radius(adjacency: &Array2<f64>, tol: f64) -> Result<f64, EigenError> {
+    let n = adjacency.nrows();
+    if n == 0 || adjacency.ncols() != n {
+        return Err(EigenError::InvalidInput(format!(
+            "Adjacency matrix must be square and non-empty, got {:?}.",
+            adjacency.dim()
+        )));
     }
 
-    // Convert outputs to ndarray types
-    let eigvals_nd = Array1::from(eigvals);
-    let eigvecs_nd = Array2::from_shape_vec((n as usize, n as usize), eigvecs)
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+    let mut x = Array1::from_iter((0..n).map(|i| 1.0 + i as f64 * 0.37));
+    x /= x.dot(&x).sqrt();
+    let mut estimate = 0.0_f64;
+    let mut change = f64::INFINITY;
+
+    for _ in 0..SPECTRAL_RADIUS_MAX_ITERS {
+        let y = adjacency.dot(&x);
+        let norm = y.dot(&y).sqrt();
+        if norm == 0.0 {
+            // The iterate was annihilated: the start vector lies in the null space, e.g. A = 0
+            return Ok(0.0);
+        }
 
-    Ok((eigvals_nd, eigvecs_nd))
+        change = (norm - estimate).abs();
+        estimate = norm;
+        x = y / norm;
+        if change <= tol * estimate {
+            return Ok(estimate);
+        }
+    }
+
+    Err(EigenError::NotConverged {
+        iterations: SPECTRAL_RADIUS_MAX_ITERS,
+        residual: change,
+    })
 }
 
-/// Converts the adjacency matrix edge list to ndarray::Array2<f64>
-pub fn adjacency_matrix_to_ndarray(
-    edges: &[(u32, u32)],
-    start_node: usize,
-    end_node: usize,
-) -> Array2<f64> {
-    let size = end_node - start_node + 1;
-    let mut adj_array = Array2::<f64>::zeros((size, size));
-    for &(a, b) in edges {
-        let local_a = a as usize - start_node;
-        let local_b = b as usize - start_node;
-        if local_a < size && local_b < size {
-            adj_array[(local_a, local_b)] = 1.0;
-            adj_array[(local_b, local_a)] = 1.0;
+/// Maximum number of inverse iterations in `smallest_magnitude_eigenvalue`.
+const INVERSE_ITERATION_MAX_ITERS: usize = 500;
+
+/// Finds the eigenvalue of a symmetric matrix closest to zero (smallest `|λ|`), which for an
+/// indefinite matrix such as an adjacency is generally not the algebraically smallest one.
+///
+/// Uses shift-invert around zero on the band of the matrix: LAPACK's `dgbtrf` LU-factorizes the
+/// `kd`-banded storage once in `O(n kd^2)`, and inverse iteration `x <- A^{-1} x` (one `dgbtrs`
+/// solve and one `banded_matvec` per step, `O(n kd)` each) converges to the eigenvector of smallest
+/// magnitude at the rate `|λ_1| / |λ_2|`, with the eigenvalue read off as the Rayleigh quotient. A
+/// singular matrix (the factorization has a zero pivot) has 0 as its smallest-magnitude eigenvalue,
+/// which is returned directly.
+///
+/// # Returns
+///
+/// * `Ok(f64)` - The eigenvalue of smallest magnitude, with its sign.
+/// * `Err(EigenError)` - If the matrix is empty or not symmetric, or the iteration does not converge
+///   (e.g. when `λ` and `-λ` are both eigenvalues of smallest magnitude).
+pub fn smallest_magnitude_eigenvalue(matrix: &Array2<f64>) -> Result<f64, EigenError> {
+    let n = matrix.nrows();
+    if n == 0 {
+        return Err(EigenError::InvalidInput("Matrix must be non-empty.".to_string()));
+    }
+    let scale = matrix.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    check_symmetric(matrix, 1e-12 * scale.max(1.0))?;
+
+    let kd = max_band(matrix) as usize;
+    let (Ok(n_int), Ok(kd_int)) = (c_int::try_from(n), c_int::try_from(kd)) else {
+        return Err(EigenError::InvalidInput(format!(
+            "Matrix size {} or bandwidth {} does not fit in a LAPACK integer.",
+            n, kd
+        )));
+    };
+
+    // General band storage for dgbtrf: kl = ku = kd, with kd extra rows on top for the fill-in
+    // of the row interchanges, so A[i][j] sits at row 2 kd + i - j of column j
+    let ldab = 3 * kd + 1;
+    let mut lu = vec![0.0; ldab * n];
+    for j in 0..n {
+        for i in j.saturating_sub(kd)..(j + kd + 1).min(n) {
+            lu[2 * kd + i - j + j * ldab] = matrix[[i, j]];
         }
     }
-    adj_array
-}
+    let ldab_int = ldab as c_int;
+    let mut ipiv = vec![0 as c_int; n];
+    let mut info: c_int = 0;
+    unsafe {
+        dgbtrf_(&n_int, &n_int, &kd_int, &kd_int, lu.as_mut_ptr(), &ldab_int, ipiv.as_mut_ptr(), &mut info);
+    }
+    if info > 0 {
+        return Ok(0.0);
+    }
+    if info < 0 {
+        return Err(EigenError::InvalidInput(format!("LAPACK dgbtrf rejected argument {}.", -info)));
+    }
 
-/// Saves a 2D ndarray::Array2<f64> to a CSV file
-pub fn save_array_to_csv_dsbevd<P: AsRef<Path>>(matrix: &Array2<f64>, csv_path: P) -> io::Result<()> {
-    let mut wtr = WriterBuilder::new()
-        .has_headers(false)
-        .from_path(csv_path)?;
-    for row in matrix.rows() {
-        wtr.serialize(row.to_vec())?;
+    let banded = to_banded_format(matrix, kd as i32);
+    let mut x = Array1::from_shape_fn(n, |i| 1.0 + i as f64 * 0.37);
+    x /= x.dot(&x).sqrt();
+    let tol = 1e-10 * scale.max(f64::MIN_POSITIVE) * (n as f64).sqrt();
+    let mut residual = f64::INFINITY;
+    let trans = b'N' as c_char;
+    let nrhs: c_int = 1;
+
+    for _ in 0..INVERSE_ITERATION_MAX_ITERS {
+        let mut y = x.clone();
+        unsafe {
+            dgbtrs_(
+                &trans,
+                &n_int,
+                &kd_int,
+                &kd_int,
+                &nrhs,
+                lu.as_ptr(),
+                &ldab_int,
+                ipiv.as_ptr(),
+                y.as_mut_ptr(),
+                &n_int,
+                &mut info,
+            );
+        }
+        if info != 0 {
+            return Err(EigenError::InvalidInput(format!("LAPACK dgbtrs rejected argument {}.", -info)));
+        }
+        let norm = y.dot(&y).sqrt();
+        if !norm.is_finite() || norm == 0.0 {
+            return Ok(0.0);
+        }
+        x = y / norm;
+
+        let ax = banded_matvec(&banded, kd, n, &x);
+        let rayleigh = x.dot(&ax);
+        let r = ax - &x * rayleigh;
+        residual = r.dot(&r).sqrt();
+        if residual <= tol {
+            return Ok(rayleigh);
+        }
     }
-    wtr.flush()?;
-    Ok(())
-}
 
-/// Saves a 1D ndarray::Array1<f64> to a CSV file
-pub fn save_vector_to_csv_dsbevd<P: AsRef<Path>>(vector: &Array1<f64>, csv_path: P) -> io::Result<()> {
-    let mut wtr = WriterBuilder::new()
-        .has_headers(false)
-        .from_path(csv_path)?;
-    let row = vector.iter().cloned().collect::<Vec<f64>>();
-    wtr.serialize(row)?;
-    wtr.flush()?;
-    Ok(())
+    Err(EigenError::NotConverged {
+        iterations: INVERSE_ITERATION_MAX_ITERS,
+        residual,
+    })
 }
 
-// SymmetricEigen eigendecomposition section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=
 
-/// Computes eigenvalues and eigenvectors for a given Laplacian matrix with SymmetricEigen
-pub fn compute_eigenvalues_and_vectors_sym(
-    laplacian: &Array2<f64>,
-) -> io::Result<(DVector<f64>, DMatrix<f64>)> {
-    // Convert ndarray::Array2<f64> to nalgebra::DMatrix<f64>
-    let nalgebra_laplacian = ndarray_to_nalgebra_matrix(laplacian)?;
+/// Refines the eigenpair of a symmetric matrix nearest `shift` by shifted inverse iteration, e.g.
+/// the Fiedler pair from an estimate of `λ_2`, without a full decomposition.
+///
+/// `A - σI` is LU-factorized once and `x <- (A - σI)^{-1} x` converges to the eigenvector whose
+/// eigenvalue is nearest `σ`, at the rate `|λ_near - σ| / |λ_next - σ|`, so a good estimate
+/// converges in a handful of steps. If `σ` is an eigenvalue to working precision the shift is
+/// nudged by a relative `1e-10` so the factorization stays usable. The eigenvalue is the Rayleigh
+/// quotient of the final vector. Within a degenerate eigenspace the returned vector is one
+/// member of it, depending on the start vector.
+///
+/// # Returns
+///
+/// * `Ok((f64, Array1<f64>))` - The eigenvalue and unit eigenvector, once `||A x - λ x|| <= tol`.
+/// * `Err(EigenError)` - If the matrix is empty or not symmetric, or the residual is still above
+///   `tol` after `iters` steps (e.g. when `shift` is equidistant from two eigenvalues).
+pub fn inverse_iteration(
+    matrix: &Array2<f64>,
+    shift: f64,
+    iters: usize,
+    tol: f64,
+) -> Result<(f64, Array1<f64>), EigenError> {
+    let n = matrix.nrows();
+    if n == 0 {
+        return Err(EigenError::InvalidInput("Matrix must be non-empty.".to_string()));
+    }
+    let scale = matrix.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    check_symmetric(matrix, 1e-12 * scale.max(1.0))?;
+
+    let a = DMatrix::from_fn(n, n, |i, j| matrix[[i, j]]);
+    let shifted = |sigma: f64| (&a - DMatrix::<f64>::identity(n, n) * sigma).lu();
+    let mut lu = shifted(shift);
+    if !lu.is_invertible() {
+        lu = shifted(shift + 1e-10 * scale.max(shift.abs()).max(f64::MIN_POSITIVE));
+    }
 
-    // Compute eigendecomposition using nalgebra's SymmetricEigen
-    let symmetric_eigen = SymmetricEigen::new(nalgebra_laplacian);
+    let mut x = DVector::from_fn(n, |i, _| 1.0 + i as f64 * 0.37).normalize();
+    let mut residual = f64::INFINITY;
+
+    for _ in 0..iters {
+        let y = lu
+            .solve(&x)
+            .ok_or_else(|| EigenError::InvalidInput("The LU solve failed.".to_string()))?;
+        let norm = y.norm();
+        if !norm.is_finite() || norm == 0.0 {
+            return Err(EigenError::InvalidInput(format!(
+                "The shifted matrix is numerically singular at shift {}.",
+                shift
+            )));
+        }
+        x = y / norm;
 
-    let eigvals = symmetric_eigen.eigenvalues;
-    let eigvecs = symmetric_eigen.eigenvectors;
+        let ax = &a * &x;
+        let rayleigh = x.dot(&ax);
+        residual = (ax - &x * rayleigh).norm();
+        if residual <= tol {
+            return Ok((rayleigh, Array1::from_iter(x.iter().copied())));
+        }
+    }
 
-    Ok((eigvals, eigvecs))
+    Err(EigenError::NotConverged { iterations: iters, residual })
 }
 
-/// Converts an ndarray::Array2<f64> to nalgebra::DMatrix<f64>
-pub fn ndarray_to_nalgebra_matrix(matrix: &Array2<f64>) -> io::Result<DMatrix<f64>> {
-    let (rows, cols) = matrix.dim();
-    let mut nalgebra_matrix = DMatrix::<f64>::zeros(rows, cols);
+// Spectral partitioning section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
 
-    for ((i, j), value) in matrix.indexed_iter() {
-        nalgebra_matrix[(i, j)] = *value;
+/// Finds the sweep cut of the Fiedler vector with the lowest conductance.
+///
+/// Nodes are sorted by Fiedler value (ties broken by index) and every prefix `S` of that order is
+/// scored by `cut(S, S̄) / min(vol(S), vol(S̄))`, where `vol` sums weighted degrees. This is the
+/// standard refinement of thresholding at zero, which is only one of the `n - 1` candidate cuts.
+///
+/// # Arguments
+///
+/// * `adjacency` - Square, nonnegative, symmetric adjacency matrix.
+/// * `fiedler` - The Fiedler vector (eigenvector of the second-smallest Laplacian eigenvalue).
+///
+/// # Returns
+///
+/// The best conductance and the partition, where `true` marks the low-Fiedler side. If no cut has
+/// positive volume on both sides (fewer than two nodes, or no edges), the conductance is infinite
+/// and every node is `false`.
+///
+/// # Panics
+///
+/// Panics if `fiedler` does not have one entry per node.
+pub fn sweep_fiedler_cut(adjacency: &Array2<f64>, fiedler: &Array1<f64>) -> (f64, Vec<bool>) {
+    let n = adjacency.nrows();
+    assert_eq!(fiedler.len(), n, "The Fiedler vector must have one entry per node.");
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| fiedler[a].total_cmp(&fiedler[b]).then(a.cmp(&b)));
+
+    let degrees = adjacency.sum_axis(Axis(1));
+    let total_volume = degrees.sum();
+
+    let mut in_set = vec![false; n];
+    let mut cut = 0.0;
+    let mut volume = 0.0;
+    let mut best = (f64::INFINITY, 0);
+
+    for (size, &u) in order.iter().enumerate().take(n.saturating_sub(1)) {
+        // Edges from u into S stop being cut; the rest of u's edges (except a self-loop) start being cut
+        let into_set: f64 = (0..n).filter(|&v| in_set[v]).map(|v| adjacency[[u, v]]).sum();
+        cut += degrees[u] - adjacency[[u, u]] - 2.0 * into_set;
+        volume += degrees[u];
+        in_set[u] = true;
+
+        let smaller_volume = volume.min(total_volume - volume);
+        if smaller_volume > 0.0 {
+            let conductance = cut / smaller_volume;
+            if conductance < best.0 {
+                best = (conductance, size + 1);
+            }
+        }
     }
 
-    Ok(nalgebra_matrix)
+    let mut partition = vec![false; n];
+    for &u in &order[..best.1] {
+        partition[u] = true;
+    }
+    (best.0, partition)
 }
 
-/// Saves a nalgebra::DMatrix<f64> to a CSV file
-pub fn save_nalgebra_matrix_to_csv<P: AsRef<Path>>(
-    matrix: &DMatrix<f64>,
-    csv_path: P,
-) -> io::Result<()> {
-    let mut wtr = WriterBuilder::new()
-        .has_headers(false)
-        .from_path(csv_path)?;
-    for i in 0..matrix.nrows() {
-        let row = matrix.row(i).iter().cloned().collect::<Vec<f64>>();
-        wtr.serialize(row)?;
+/// Maximum number of Lloyd iterations in `kmeans`.
+const KMEANS_MAX_ITERS: usize = 300;
+
+/// Clusters the rows of `points` into `k` groups with Lloyd's algorithm and k-means++ seeding.
+///
+/// The seeding draws the first center uniformly and every further one with probability
+/// proportional to its squared distance from the nearest chosen center, all from a SplitMix64
+/// stream started at `seed`, so equal seeds give identical results. Lloyd iterations run until the
+/// assignment stops changing (at most `KMEANS_MAX_ITERS`); a cluster that empties keeps its center.
+///
+/// # Returns
+///
+/// The cluster of every point, numbered in order of first appearance (point 0 is in cluster 0).
+///
+/// # Panics
+///
+/// Panics if `k` is zero or larger than the number of points.
+pub fn kmeans(points: &Array2<f64>, k: usize, seed: u64) -> Vec<usize> {
+    let n = points.nrows();
+    assert!(k >= 1 && k <= n, "k must be between 1 and the number of points.");
+
+    let squared_distance = |a: ArrayView1<f64>, b: ArrayView1<f64>| -> f64 {
+        a.iter().zip(b.iter()).map(|(x, y)| (x - y) * (x - y)).sum()
+    };
+    let mut state = seed;
+    let mut uniform = || (splitmix64(&mut state) >> 11) as f64 / (1u64 << 53) as f64;
+
+    // k-means++ seeding
+    let mut centers = Array2::<f64>::zeros((k, points.ncols()));
+    let first = ((uniform() * n as f64) as usize).min(n - 1);
+    centers.row_mut(0).assign(&points.row(first));
+    let mut nearest: Vec<f64> = points.rows().into_iter().map(|p| squared_distance(p, points.row(first))).collect();
+    for c in 1..k {
+        let total: f64 = nearest.iter().sum();
+        let chosen = if total > 0.0 {
+            let target = uniform() * total;
+            let mut cumulative = 0.0;
+            nearest
+                .iter()
+                .position(|&d| {
+                    cumulative += d;
+                    cumulative > target
+                })
+                .unwrap_or(n - 1)
+        } else {
+            // All points coincide with a center already
+            c
+        };
+        centers.row_mut(c).assign(&points.row(chosen));
+        for (i, d) in nearest.iter_mut().enumerate() {
+            *d = d.min(squared_distance(points.row(i), points.row(chosen)));
+        }
     }
-    wtr.flush()?;
-    Ok(())
-}
 
-/// Saves a nalgebra::DVector<f64> to a CSV file
-pub fn save_nalgebra_vector_to_csv<P: AsRef<Path>>(
-    vector: &DVector<f64>,
-    csv_path: P,
-) -> io::Result<()> {
-    let mut wtr = WriterBuilder::new()
-        .has_headers(false)
-        .from_path(csv_path)?;
-    let row = vector.iter().cloned().collect::<Vec<f64>>();
-    wtr.serialize(row)?;
-    wtr.flush()?;
-    Ok(())
+    // Lloyd iterations
+    let mut labels = vec![usize::MAX; n];
+    for _ in 0..KMEANS_MAX_ITERS {
+        let mut changed = false;
+        for (i, label) in labels.iter_mut().enumerate() {
+            let best = (0..k)
+                .map(|c| (squared_distance(points.row(i), centers.row(c)), c))
+                .fold((f64::INFINITY, 0), |best, candidate| if candidate.0 < best.0 { candidate } else { best })
+                .1;
+            if *label != best {
+                *label = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        for c in 0..k {
+            let members: Vec<usize> = (0..n).filter(|&i| labels[i] == c).collect();
+            if !members.is_empty() {
+                let mean = points.select(Axis(0), &members).mean_axis(Axis(0)).expect("members is non-empty");
+                centers.row_mut(c).assign(&mean);
+            }
+        }
+    }
+
+    // Renumber clusters in order of first appearance
+    let mut renumbered = vec![usize::MAX; k];
+    let mut next = 0;
+    labels
+        .into_iter()
+        .map(|label| {
+            if renumbered[label] == usize::MAX {
+                renumbered[label] = next;
+                next += 1;
+            }
+            renumbered[label]
+        })
+        .collect()
 }
 
+/// Partitions a graph into `k` clusters by spectral clustering.
+///
+/// The `k` eigenvectors of the combinatorial Laplacian with the smallest eigenvalues (from
+/// `call_eigendecomp_canonical`, so degenerate bases are reproducible) give every node a point in
+/// `R^k`, and the points are grouped with `kmeans` using `seed`.
+///
+/// # Returns
+///
+/// The cluster of every node, numbered as in `kmeans`, or `InvalidInput` unless `1 <= k <= n`.
+pub fn spectral_clustering(adjacency: &Array2<f64>, k: usize, seed: u64) -> io::Result<Vec<usize>> {
+    let n = adjacency.nrows();
+    if k == 0 || k > n {
+        return Err(EigenError::InvalidInput(format!("Need 1 <= k <= n = {}, got k = {}.", n, k)).into());
+    }
 
-// Compute Normalized Global Eigen-Complexity (NGEC) =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
+    let (_, eigvecs) = call_eigendecomp_canonical(&combinatorial_laplacian(adjacency))?;
+    Ok(kmeans(&eigvecs.slice(s![.., ..k]).to_owned(), k, seed))
+}
 
-/// Computes the Normalized Global Eigen-Complexity (NGEC) based on eigenvalues.
-/// Ignores eigenvalues that are negative within a small epsilon due to floating-point precision.
+/// Ranks the nodes of a graph by their Fiedler-vector component, in ascending order.
 ///
-/// # Arguments
+/// The Fiedler vector is the eigenvector of the second-smallest eigenvalue of the combinatorial
+/// Laplacian, taken from `call_eigendecomp_canonical` so its sign is reproducible. Nodes at either
+/// end of the ranking lie deepest in the two halves of the spectral bisection, while values near
+/// zero mark the boundary. For a disconnected graph the vector only separates components.
 ///
-/// * `eigenvalues` - A reference to an Array1<f64> containing the eigenvalues.
+/// # Returns
+///
+/// `(node, value)` pairs sorted by value (ties broken by node index), or `InvalidInput` if the
+/// graph has fewer than two nodes.
+pub fn rank_by_fiedler(adjacency: &Array2<f64>) -> io::Result<Vec<(usize, f64)>> {
+    rank_by_fiedler_with_laplacian(adjacency, LaplacianKind::Combinatorial)
+}
+
+/// Same as `rank_by_fiedler`, taking the Fiedler vector of the Laplacian variant selected by
+/// `kind`; the normalized Laplacian gives the relaxed normalized cut.
 ///
 /// # Returns
 ///
-/// * `Ok(f64)` - The computed NGEC value.
-/// * `Err(io::Error)` - If the computation fails due to invalid input.
-pub fn compute_ngec(eigenvalues: &Array1<f64>) -> io::Result<f64> {
-    let epsilon = 1e-9; // Small epsilon to account for floating-point precision
-    let m = eigenvalues.len();
-    if m == 0 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Eigenvalues array is empty.",
-        ));
+/// The ranking, or `InvalidInput` if the graph has fewer than two nodes or `kind` is not symmetric.
+pub fn rank_by_fiedler_with_laplacian(adjacency: &Array2<f64>, kind: LaplacianKind) -> io::Result<Vec<(usize, f64)>> {
+    if adjacency.nrows() < 2 {
+        return Err(EigenError::InvalidInput("A Fiedler vector needs at least two nodes.".to_string()).into());
     }
-
-    // Check for eigenvalues significantly below zero (beyond precision tolerance)
-    if eigenvalues.iter().any(|&x| x < -epsilon) {
-        let negative_eigenvalues: Vec<f64> = eigenvalues
-            .iter()
-            .filter(|&&x| x < -epsilon)
-            .cloned()
-            .take(5)
-            .collect();
-    
-        println!("❗ Significant negative eigenvalues found: {:?}", negative_eigenvalues);
-    
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            format!(
-                "Eigenvalues contain significant negative values."
-            ),
-        ));
+    if !kind.is_symmetric() {
+        return Err(EigenError::InvalidInput(format!("The {} Laplacian is not symmetric.", kind)).into());
     }
 
-    // Calculate the sum of eigenvalues, ignoring small negative values due to precision
-    let sum_eigen = eigenvalues
-        .iter()
-        .filter(|&&x| x >= -epsilon)  // Only include eigenvalues >= -epsilon
-        .sum::<f64>();
-    
-    if sum_eigen <= 0.0 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Sum of eigenvalues must be positive.",
-        ));
-    }
+    let (_, eigvecs) = call_eigendecomp_canonical(&build_laplacian(adjacency, kind))?;
+    let mut ranking: Vec<(usize, f64)> = eigvecs.column(1).iter().copied().enumerate().collect();
+    ranking.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    Ok(ranking)
+}
 
-    // Normalize the eigenvalues, ignoring small negative values
-    let normalized_eigen = eigenvalues.mapv(|x| if x >= -epsilon { x / sum_eigen } else { 0.0 });
+/// Counts the trivial (near-zero) Laplacian eigenvalues, one per connected component.
+///
+/// Eigenvalues with `|λ| <= tol` are counted, so a connected graph gives 1 and a graph with `c`
+/// components gives `c`. The eigenvalues do not need to be sorted.
+pub fn count_trivial_modes(eigvals: &Array1<f64>, tol: f64) -> usize {
+    eigvals.iter().filter(|&&value| value.abs() <= tol).count()
+}
 
-    // Compute the entropy
-    // Handle cases where normalized eigenvalues are zero
-    let entropy: f64 = normalized_eigen
-        .iter()
-        .filter(|&&x| x > 0.0)
-        .map(|&x| x * x.ln())
-        .sum::<f64>();
+/// Builds a spectral embedding from Laplacian eigenpairs sorted in ascending order.
+///
+/// The leading `count_trivial_modes(eigvals, tol)` eigenvectors only encode component membership,
+/// so exactly that many are skipped (not just the first one) and the next `dims` eigenvectors
+/// become the coordinates. Row `i` of the result is the embedding of node `i`.
+///
+/// # Arguments
+///
+/// * `eigvals` - Eigenvalues in ascending order, e.g. from `call_eigendecomp`.
+/// * `eigvecs` - Matching eigenvectors as columns.
+/// * `dims` - Number of embedding dimensions; fewer are returned if the spectrum runs out.
+/// * `tol` - Threshold below which an eigenvalue is treated as zero.
+///
+/// # Returns
+///
+/// An `n x dims` matrix of node coordinates.
+pub fn spectral_embedding(eigvals: &Array1<f64>, eigvecs: &Array2<f64>, dims: usize, tol: f64) -> Array2<f64> {
+    let skip = count_trivial_modes(eigvals, tol).min(eigvecs.ncols());
+    let end = (skip + dims).min(eigvecs.ncols());
+    eigvecs.slice(s![.., skip..end]).to_owned()
+}
 
-    // Calculate log(m)
-    let log_m = (m as f64).ln();
-    if log_m == 0.0 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "Logarithm of the number of eigenvalues resulted in zero.",
-        ));
+/// How `scaled_spectral_embedding` scales the eigenvector coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingScaling {
+    /// The raw eigenvectors, as in `spectral_embedding`.
+    None,
+    /// Heat-diffusion coordinates `e^{-λ} v` (diffusion time 1), which damp high frequencies.
+    Diffusion,
+    /// Commute-time coordinates `sqrt(vol / λ) v`, see `commute_time_embedding`.
+    Commute,
+}
+
+/// Builds a `dims`-dimensional spectral embedding from combinatorial Laplacian eigenpairs sorted in
+/// ascending order, skipping the zero modes (those `clamp_spectrum` zeroes at
+/// `DEFAULT_SPECTRUM_FLOOR`) and scaling each
+/// coordinate as selected by `scaling`. Fewer columns are returned if the spectrum runs out.
+pub fn scaled_spectral_embedding(
+    eigvals: &Array1<f64>,
+    eigvecs: &Array2<f64>,
+    dims: usize,
+    scaling: EmbeddingScaling,
+) -> Array2<f64> {
+    if scaling == EmbeddingScaling::Commute {
+        let embedding = commute_time_embedding(eigvals, eigvecs);
+        let end = dims.min(embedding.ncols());
+        return embedding.slice(s![.., ..end]).to_owned();
     }
 
-    // Compute NGEC
-    let ngec = -entropy / log_m;
+    let floor = relative_spectrum_floor(eigvals, DEFAULT_SPECTRUM_FLOOR);
+    let mut embedding = spectral_embedding(eigvals, eigvecs, dims, floor);
+    if scaling == EmbeddingScaling::Diffusion {
+        let skip = count_trivial_modes(eigvals, floor).min(eigvecs.ncols());
+        for (k, mut column) in embedding.columns_mut().into_iter().enumerate() {
+            column *= (-eigvals[skip + k]).exp();
+        }
+    }
+    embedding
+}
 
-    Ok(ngec)
+/// Builds the commute-time embedding `sqrt(vol) L^{+1/2}` from combinatorial Laplacian eigenpairs.
+///
+/// Every eigenvector whose eigenvalue stays positive after `clamp_spectrum` with
+/// `DEFAULT_SPECTRUM_FLOOR` is scaled by `sqrt(vol / λ)`; the zero modes are dropped. The volume `vol = Σ_i d_i` is read off the spectrum as `tr(L) = Σ λ`, so no
+/// adjacency is needed. The squared Euclidean distance between rows `i` and `j` is then the commute
+/// time `vol · R_ij` of a random walk between the nodes, where `R_ij` is the effective resistance.
+///
+/// # Returns
+///
+/// An `n x m` matrix of node coordinates, one column per nontrivial eigenpair in input order.
+pub fn commute_time_embedding(eigvals: &Array1<f64>, eigvecs: &Array2<f64>) -> Array2<f64> {
+    let volume = eigvals.sum();
+    let clamped = clamp_spectrum(eigvals, DEFAULT_SPECTRUM_FLOOR);
+    let nontrivial: Vec<usize> = (0..eigvals.len()).filter(|&k| clamped[k] > 0.0).collect();
+
+    let mut embedding = eigvecs.select(Axis(1), &nontrivial);
+    for (mut column, &k) in embedding.columns_mut().into_iter().zip(nontrivial.iter()) {
+        column *= (volume / eigvals[k]).sqrt();
+    }
+    embedding
 }
 
+/// Computes the spanning-edge centrality `w_ij · R_ij` of each edge from the full combinatorial
+/// Laplacian eigendecomposition, where `R_ij` is the effective resistance between the endpoints.
+///
+/// The centrality is the probability that the edge belongs to a uniformly random spanning tree
+/// (weighted by the product of edge weights), so bridges score exactly 1 and edges with many
+/// alternative routes score near 0; over all edges of a connected graph the scores sum to
+/// `n - 1` (Foster's theorem). Both factors are read off the spectrum: `w_ij = -L_ij =
+/// -Σ_k λ_k v_ik v_jk` and `R_ij = Σ_k (v_ik - v_jk)² / λ_k` over the nonzero modes
+/// (those left positive by `clamp_spectrum` with `DEFAULT_SPECTRUM_FLOOR`), so all `n`
+/// eigenpairs are required.
+///
+/// # Returns
+///
+/// The centrality of every edge, in the order of `edges`. A pair that is not an edge has
+/// `w_ij = 0` and scores (numerically) zero.
+///
+/// # Panics
+///
+/// Panics if an edge refers to a node outside the eigenvectors' rows.
+pub fn spanning_edge_centrality(
+    eigvals: &Array1<f64>,
+    eigvecs: &Array2<f64>,
+    edges: &[(usize, usize)],
+) -> Array1<f64> {
+    let clamped = clamp_spectrum(eigvals, DEFAULT_SPECTRUM_FLOOR);
+    Array1::from_iter(edges.iter().map(|&(i, j)| {
+        let (row_i, row_j) = (eigvecs.row(i), eigvecs.row(j));
+        let mut weight = 0.0;
+        let mut resistance = 0.0;
+        for (k, &lambda) in eigvals.iter().enumerate() {
+            weight -= lambda * row_i[k] * row_j[k];
+            if clamped[k] > 0.0 {
+                let difference = row_i[k] - row_j[k];
+                resistance += difference * difference / lambda;
+            }
+        }
+        weight * resistance
+    }))
+}
 
 // Load and output section =-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-=-
 
@@ -485,9 +3928,8 @@ pub fn print_heatmap_ndarray(matrix: &Array2<f64>) {
     let stdout = StandardStream::stdout(ColorChoice::Always);
     let mut stdout = stdout.lock();
 
-    for i in 0..num_rows {
-        for j in 0..num_cols {
-            let final_value = normalized_values[i][j];
+    for row_values in &normalized_values {
+        for &final_value in row_values {
 
             let color = if final_value == 0.0 {
                 Color::Black
@@ -0,0 +1,343 @@
+// src/extended.rs
+
+//! Module for symmetric eigenvalue computations in extended (double-double) precision.
+//!
+//! Enabled by the `f128` feature. A `DoubleDouble` stores a value as the unevaluated sum of two
+//! f64 values, giving about 106 bits of mantissa (roughly 32 decimal digits) in software. This
+//! separates eigenvalues that collapse onto each other in f64, but it is very slow: every operation
+//! costs 10–20 f64 operations and nothing is delegated to LAPACK, so expect the decomposition to
+//! be two to three orders of magnitude slower than `call_eigendecomp`. Use it on small, pathological
+//! matrices only.
+
+use ndarray::prelude::*;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::eigen::EigenError;
+
+/// Maximum number of QL iterations per eigenvalue before `NotConverged` is reported.
+const MAX_QL_ITERATIONS: usize = 60;
+
+/// A double-double number `hi + lo` with `|lo| <= ulp(hi) / 2`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+impl DoubleDouble {
+    /// Zero.
+    pub const ZERO: DoubleDouble = DoubleDouble { hi: 0.0, lo: 0.0 };
+    /// One.
+    pub const ONE: DoubleDouble = DoubleDouble { hi: 1.0, lo: 0.0 };
+    /// The unit roundoff, 2^-104.
+    pub const EPSILON: f64 = 4.930380657631324e-32;
+
+    /// Creates a value from its two components, renormalizing them.
+    pub fn new(hi: f64, lo: f64) -> Self {
+        quick_two_sum(hi, lo)
+    }
+
+    /// Rounds to the nearest f64.
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    /// Absolute value.
+    pub fn abs(self) -> Self {
+        if self.hi < 0.0 || (self.hi == 0.0 && self.lo < 0.0) {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Square root (zero for non-positive input).
+    pub fn sqrt(self) -> Self {
+        if self.hi <= 0.0 {
+            return Self::ZERO;
+        }
+        // One Newton step (Karp's trick) on the f64 approximation doubles its precision
+        let x = 1.0 / self.hi.sqrt();
+        let ax = self.hi * x;
+        let correction = (self - DoubleDouble::from(ax) * DoubleDouble::from(ax)).hi * x * 0.5;
+        DoubleDouble::from(ax) + DoubleDouble::from(correction)
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(value: f64) -> Self {
+        DoubleDouble { hi: value, lo: 0.0 }
+    }
+}
+
+impl fmt::Display for DoubleDouble {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:e} + {:e}", self.hi, self.lo)
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.hi.partial_cmp(&other.hi) {
+            Some(Ordering::Equal) => self.lo.partial_cmp(&other.lo),
+            ordering => ordering,
+        }
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = DoubleDouble;
+    fn neg(self) -> DoubleDouble {
+        DoubleDouble { hi: -self.hi, lo: -self.lo }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = DoubleDouble;
+    fn add(self, other: DoubleDouble) -> DoubleDouble {
+        let (s, e) = two_sum(self.hi, other.hi);
+        let (t, f) = two_sum(self.lo, other.lo);
+        let ss = quick_two_sum(s, e + t);
+        quick_two_sum(ss.hi, ss.lo + f)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = DoubleDouble;
+    fn sub(self, other: DoubleDouble) -> DoubleDouble {
+        self + (-other)
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = DoubleDouble;
+    fn mul(self, other: DoubleDouble) -> DoubleDouble {
+        let p = self.hi * other.hi;
+        let e = self.hi.mul_add(other.hi, -p) + (self.hi * other.lo + self.lo * other.hi);
+        quick_two_sum(p, e)
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = DoubleDouble;
+    fn div(self, other: DoubleDouble) -> DoubleDouble {
+        // Long division with three f64 quotient digits
+        let q1 = self.hi / other.hi;
+        let r = self - other * DoubleDouble::from(q1);
+        let q2 = r.hi / other.hi;
+        let r = r - other * DoubleDouble::from(q2);
+        let q3 = r.hi / other.hi;
+        quick_two_sum(q1, q2) + DoubleDouble::from(q3)
+    }
+}
+
+/// Error-free sum of two f64 values.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// Error-free sum assuming `|a| >= |b|`.
+fn quick_two_sum(a: f64, b: f64) -> DoubleDouble {
+    let s = a + b;
+    DoubleDouble { hi: s, lo: b - (s - a) }
+}
+
+/// `sqrt(a^2 + b^2)` without destructive overflow or underflow.
+fn pythag(a: DoubleDouble, b: DoubleDouble) -> DoubleDouble {
+    let (abs_a, abs_b) = (a.abs(), b.abs());
+    if abs_a > abs_b {
+        let ratio = abs_b / abs_a;
+        abs_a * (DoubleDouble::ONE + ratio * ratio).sqrt()
+    } else if abs_b.hi == 0.0 {
+        DoubleDouble::ZERO
+    } else {
+        let ratio = abs_a / abs_b;
+        abs_b * (DoubleDouble::ONE + ratio * ratio).sqrt()
+    }
+}
+
+/// Computes the eigenvalues of a symmetric f64 matrix in double-double precision.
+/// See `symmetric_eigenvalues_extended_dd`.
+pub fn symmetric_eigenvalues_extended(matrix: &Array2<f64>) -> Result<Vec<DoubleDouble>, EigenError> {
+    symmetric_eigenvalues_extended_dd(&matrix.mapv(DoubleDouble::from))
+}
+
+/// Computes the eigenvalues of a symmetric matrix entirely in double-double precision.
+///
+/// The matrix is reduced to tridiagonal form with Householder reflections and the tridiagonal
+/// eigenvalues are found with the implicit QL algorithm, both carried out in `DoubleDouble`
+/// arithmetic. Only eigenvalues are computed.
+///
+/// # Returns
+///
+/// * `Ok(Vec<DoubleDouble>)` - The eigenvalues in ascending order.
+/// * `Err(EigenError)` - If the matrix is not square and symmetric, or QL does not converge.
+pub fn symmetric_eigenvalues_extended_dd(
+    matrix: &Array2<DoubleDouble>,
+) -> Result<Vec<DoubleDouble>, EigenError> {
+    let n = matrix.nrows();
+    if matrix.ncols() != n {
+        return Err(EigenError::InvalidInput(format!(
+            "Matrix must be square, got {:?}.",
+            matrix.dim()
+        )));
+    }
+    for ((i, j), value) in matrix.indexed_iter() {
+        if *value != matrix[[j, i]] {
+            return Err(EigenError::InvalidInput(format!(
+                "Matrix is not symmetric at ({}, {}).",
+                i, j
+            )));
+        }
+    }
+
+    let (mut d, mut e) = tridiagonalize(matrix.clone());
+    tridiagonal_ql(&mut d, &mut e)?;
+
+    d.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Ok(d)
+}
+
+/// Householder reduction to tridiagonal form. Returns the diagonal and the subdiagonal
+/// (`e[i]` couples rows `i - 1` and `i`; `e[0]` is zero).
+fn tridiagonalize(mut a: Array2<DoubleDouble>) -> (Vec<DoubleDouble>, Vec<DoubleDouble>) {
+    let n = a.nrows();
+    let zero = DoubleDouble::ZERO;
+    let mut e = vec![zero; n];
+
+    for i in (1..n).rev() {
+        let l = i - 1;
+        if l == 0 {
+            e[i] = a[[i, l]];
+            continue;
+        }
+
+        let mut scale = zero;
+        for k in 0..=l {
+            scale = scale + a[[i, k]].abs();
+        }
+        if scale.hi == 0.0 {
+            e[i] = a[[i, l]];
+            continue;
+        }
+
+        let mut h = zero;
+        for k in 0..=l {
+            a[[i, k]] = a[[i, k]] / scale;
+            h = h + a[[i, k]] * a[[i, k]];
+        }
+        let f = a[[i, l]];
+        let g = if f.hi >= 0.0 { -h.sqrt() } else { h.sqrt() };
+        e[i] = scale * g;
+        h = h - f * g;
+        a[[i, l]] = f - g;
+
+        let mut f = zero;
+        for j in 0..=l {
+            let mut g = zero;
+            for k in 0..=j {
+                g = g + a[[j, k]] * a[[i, k]];
+            }
+            for k in (j + 1)..=l {
+                g = g + a[[k, j]] * a[[i, k]];
+            }
+            e[j] = g / h;
+            f = f + e[j] * a[[i, j]];
+        }
+
+        let hh = f / (h + h);
+        for j in 0..=l {
+            let f = a[[i, j]];
+            let g = e[j] - hh * f;
+            e[j] = g;
+            for k in 0..=j {
+                a[[j, k]] = a[[j, k]] - (f * e[k] + g * a[[i, k]]);
+            }
+        }
+    }
+
+    let d = (0..n).map(|i| a[[i, i]]).collect();
+    (d, e)
+}
+
+/// Implicit QL iteration on a symmetric tridiagonal matrix, overwriting `d` with its eigenvalues.
+fn tridiagonal_ql(d: &mut [DoubleDouble], e: &mut [DoubleDouble]) -> Result<(), EigenError> {
+    let n = d.len();
+    if n == 0 {
+        return Ok(());
+    }
+    let zero = DoubleDouble::ZERO;
+    let two = DoubleDouble::from(2.0);
+
+    // Shift the subdiagonal so e[i] couples d[i] and d[i + 1]
+    for i in 1..n {
+        e[i - 1] = e[i];
+    }
+    e[n - 1] = zero;
+
+    for l in 0..n {
+        let mut iterations = 0;
+        loop {
+            let mut m = l;
+            while m + 1 < n {
+                let dd = d[m].abs() + d[m + 1].abs();
+                if e[m].abs().hi <= DoubleDouble::EPSILON * dd.hi {
+                    break;
+                }
+                m += 1;
+            }
+            if m == l {
+                break;
+            }
+
+            iterations += 1;
+            if iterations > MAX_QL_ITERATIONS {
+                return Err(EigenError::NotConverged {
+                    iterations: MAX_QL_ITERATIONS,
+                    residual: e[l].abs().to_f64(),
+                });
+            }
+
+            let mut g = (d[l + 1] - d[l]) / (two * e[l]);
+            let mut r = pythag(g, DoubleDouble::ONE);
+            let signed_r = if g.hi >= 0.0 { r.abs() } else { -r.abs() };
+            g = d[m] - d[l] + e[l] / (g + signed_r);
+
+            let (mut s, mut c, mut p) = (DoubleDouble::ONE, DoubleDouble::ONE, zero);
+            let mut underflow = false;
+            for i in (l..m).rev() {
+                let f = s * e[i];
+                let b = c * e[i];
+                r = pythag(f, g);
+                e[i + 1] = r;
+                if r.hi == 0.0 {
+                    // Recover from underflow
+                    d[i + 1] = d[i + 1] - p;
+                    e[m] = zero;
+                    underflow = true;
+                    break;
+                }
+                s = f / r;
+                c = g / r;
+                g = d[i + 1] - p;
+                r = (d[i] - g) * s + two * c * b;
+                p = s * r;
+                d[i + 1] = g + p;
+                g = c * r - b;
+            }
+            if underflow {
+                continue;
+            }
+
+            d[l] = d[l] - p;
+            e[l] = g;
+            e[m] = zero;
+        }
+    }
+
+    Ok(())
+}
@@ -4,13 +4,18 @@
 
 use ndarray::prelude::*;
 use std::fs::File;
-use std::io::{self, BufReader, Read};
-use std::path::Path;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use std::cmp::min;
 
-use crate::eigen::{call_eigendecomp, save_array_to_csv_dsbevd, save_vector_to_csv_dsbevd, adjacency_matrix_to_ndarray, compute_ngec, print_heatmap, print_heatmap_ndarray, print_eigenvalues_heatmap};
+use indicatif::ProgressBar;
+
+use crate::convert::{load_gfa, load_rgfa_coordinates};
+use crate::graph::{connected_components, reorder_for_bandwidth, BandwidthReport};
+use crate::laplacian::{build_laplacian, LaplacianKind};
+use crate::eigen::{call_eigendecomp, call_eigendecomp_with_options, EigenOptions, call_eigendecomp_canonical, scaled_spectral_embedding, EmbeddingScaling, save_matrix, save_vector, save_array_to_json_labeled, OutputData, OutputFormat, adjacency_matrix_to_ndarray, compute_ngec_with_floor, compute_eigenvalues_and_vectors_banded_range, load_array_from_npy, csv_max_band, csv_laplacian_to_banded, read_array_from_csv, check_max_band, to_banded_format, rank_by_fiedler_with_laplacian, spectral_distance, print_heatmap, print_heatmap_ndarray, print_eigenvalues_heatmap, DEFAULT_SPECTRUM_FLOOR};
 
 /// Extracts a submatrix for a given node range from the adjacency matrix edge list,
 /// computes the Laplacian, performs eigendecomposition, and saves the results.
@@ -20,13 +25,138 @@ pub fn extract_and_analyze_submatrix<P: AsRef<Path>>(
     end_node: usize,
     output_path: P,
 ) -> io::Result<()> {
+    extract_and_analyze_submatrix_with_progress(
+        edge_list_path,
+        start_node,
+        end_node,
+        output_path,
+        &ExtractOptions::default(),
+        &mut NoProgress,
+    )
+}
+
+/// The phases of `extract_and_analyze_submatrix_with_progress`, in the order they are reported.
+pub const EXTRACT_PHASES: [&str; 6] = ["loading", "laplacian", "decomposition", "saving", "ngec", "heatmaps"];
+
+/// Receives the phases of a subcommand as they start and everything it prints, so a progress
+/// display (e.g. an `indicatif` bar redrawn on a timer) never gets interleaved with the output.
+///
+/// Any `FnMut(&str, f64)` closure is a `Progress` that only records the phases.
+pub trait Progress {
+    /// Called as each phase starts, with its name and the fraction of the work already done, and
+    /// once more with `("done", 1.0)`.
+    fn phase(&mut self, phase: &str, fraction: f64);
+
+    /// Runs `print`, which writes to stdout, with the progress display out of the way.
+    fn suspend(&mut self, print: &mut dyn FnMut()) {
+        print();
+    }
+
+    /// Prints one line to stdout through `suspend`.
+    fn println(&mut self, line: &str) {
+        self.suspend(&mut || println!("{}", line));
+    }
+}
+
+impl<F: FnMut(&str, f64)> Progress for F {
+    fn phase(&mut self, phase: &str, fraction: f64) {
+        self(phase, fraction)
+    }
+}
+
+/// Shows the phase name and percentage on the bar (whose length should be 100), and clears it
+/// while anything is printed.
+impl Progress for ProgressBar {
+    fn phase(&mut self, phase: &str, fraction: f64) {
+        self.set_position((fraction * 100.0).round() as u64);
+        self.set_message(phase.to_string());
+    }
+
+    fn suspend(&mut self, print: &mut dyn FnMut()) {
+        ProgressBar::suspend(self, print);
+    }
+}
+
+/// Ignores the phases and prints straight to stdout; used by the functions without a `progress`
+/// argument.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn phase(&mut self, _phase: &str, _fraction: f64) {}
+}
+
+/// Prints a status line through `progress` unless `quiet` is set.
+fn status(progress: &mut dyn Progress, quiet: bool, line: &str) {
+    if !quiet {
+        progress.println(line);
+    }
+}
+
+/// Options for `extract_and_analyze_submatrix_with_progress` and the other `*_with_options`
+/// subcommands. Each function reads only the fields that apply to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExtractOptions {
+    /// The Laplacian variant to decompose (combinatorial by default).
+    pub kind: LaplacianKind,
+    /// Eigenvalues with `|λ|` at or below this floor, scaled by `max(1, |λ_max|)`, count as zero
+    /// in the NGEC (see `clamp_spectrum`); `DEFAULT_SPECTRUM_FLOOR` by default.
+    pub clamp_floor: f64,
+    /// Format of the written matrices and spectra (CSV by default). `extract` names its files
+    /// `<output>.laplacian.<ext>`, `<output>.eigenvectors.<ext>` and `<output>.eigenvalues.<ext>`.
+    pub format: OutputFormat,
+    /// If set, a Laplacian with a larger `max_band` is rejected before any dense or banded storage
+    /// is allocated (see `check_max_band`). Read by `extract` and `band`; no cap by default.
+    pub max_band: Option<usize>,
+    /// Print only the results (the NGEC of `extract`, the report of `compare`) and warnings, without
+    /// the status lines, solver choice and heatmaps. Off by default.
+    pub quiet: bool,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        ExtractOptions {
+            kind: LaplacianKind::Combinatorial,
+            clamp_floor: DEFAULT_SPECTRUM_FLOOR,
+            format: OutputFormat::Csv,
+            max_band: None,
+            quiet: false,
+        }
+    }
+}
+
+/// Same as `extract_and_analyze_submatrix`, reporting each phase as it starts, with every setting
+/// taken from `options`.
+///
+/// The eigensolvers are all symmetric, so a non-symmetric `options.kind` (random-walk) is rejected
+/// with `InvalidInput` before any file is read.
+///
+/// `progress` receives the phase name (one of `EXTRACT_PHASES`) and the fraction of phases
+/// already completed, then `("done", 1.0)`, and prints every line, heatmaps included. LAPACK gives
+/// no feedback from inside a decomposition, so the fraction only advances between phases.
+pub fn extract_and_analyze_submatrix_with_progress<P: AsRef<Path>>(
+    edge_list_path: P,
+    start_node: usize,
+    end_node: usize,
+    output_path: P,
+    options: &ExtractOptions,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    let ExtractOptions { kind, clamp_floor, format, max_band: band_cap, quiet } = *options;
+    check_laplacian_supported(kind)?;
+
+    let phase = |progress: &mut dyn Progress, index: usize| {
+        progress.phase(EXTRACT_PHASES[index], index as f64 / EXTRACT_PHASES.len() as f64)
+    };
+
     let start_time = Instant::now();
 
     // Load the adjacency matrix from the .gam file
-    println!(
+    phase(progress, 0);
+    status(progress, quiet, &format!(
         "📂 Loading adjacency matrix from {:?}",
         edge_list_path.as_ref()
-    );
+    ));
 
     let adjacency_matrix = Arc::new(Mutex::new(load_adjacency_matrix(
         &edge_list_path,
@@ -34,66 +164,808 @@ pub fn extract_and_analyze_submatrix<P: AsRef<Path>>(
         end_node,
     )?));
 
-    println!("✅ Loaded adjacency matrix.");
+    status(progress, quiet, "✅ Loaded adjacency matrix.");
+
+    // Every Laplacian variant has the sparsity pattern of the adjacency, so the bandwidth is known
+    // from the edges alone and an oversized band is rejected before the dense matrix is formed.
+    let band = adjacency_matrix.lock().unwrap().iter().map(|&(a, b)| a.abs_diff(b) as usize).max().unwrap_or(0);
+    check_max_band(band, band_cap)?;
 
     // Compute Laplacian and eigendecomposition
-    println!("🔬 Computing Laplacian matrix and eigendecomposition...");
+    phase(progress, 1);
+    status(progress, quiet, &format!("🔬 Computing {} Laplacian matrix and eigendecomposition...", kind));
 
     let adj_matrix =
         adjacency_matrix_to_ndarray(&adjacency_matrix.lock().unwrap(), start_node, end_node);
 
-    // Compute degree matrix
-    let degrees = adj_matrix.sum_axis(Axis(1));
-    let degree_matrix = Array2::<f64>::from_diag(&degrees);
-
-    // Compute Laplacian matrix: L = D - A
-    let laplacian = &degree_matrix - &adj_matrix;
+    let laplacian = build_laplacian(&adj_matrix, kind);
 
-    // Save Laplacian matrix to CSV
-    let laplacian_csv_path = output_path.as_ref().with_extension("laplacian.csv");
-    save_array_to_csv_dsbevd(&laplacian, &laplacian_csv_path)?;
-    println!(
+    // Save the Laplacian matrix
+    let laplacian_path = output_path.as_ref().with_extension(format!("laplacian.{}", format.extension()));
+    save_matrix(&laplacian, &laplacian_path, format)?;
+    status(progress, quiet, &format!(
         "✅ Laplacian matrix saved to {}",
-        laplacian_csv_path.display()
-    );
+        laplacian_path.display()
+    ));
 
     // Compute eigenvalues and eigenvectors
-    println!("🔬 Performing eigendecomposition...");
-    let (eigvals, eigvecs) = call_eigendecomp(&laplacian)?;
+    phase(progress, 2);
+    status(progress, quiet, "🔬 Performing eigendecomposition...");
+    // The decomposition stays quiet so that the solver line, too, goes through `progress`
+    let (eigvals, eigvecs, policy) =
+        call_eigendecomp_with_options(&laplacian, &EigenOptions { quiet: true, ..EigenOptions::default() })?;
+    status(progress, quiet, &format!("🧮 Decomposed with {}.", policy));
     
-    // Save eigenvectors to CSV
-    let eigen_csv_path = output_path.as_ref().with_extension("eigenvectors.csv");
-    save_array_to_csv_dsbevd(&eigvecs, &eigen_csv_path)?;
-    println!("✅ Eigenvectors saved to {}", eigen_csv_path.display());
+    // Save the eigenvectors
+    phase(progress, 3);
+    let eigenvectors_path = output_path.as_ref().with_extension(format!("eigenvectors.{}", format.extension()));
+    save_matrix(&eigvecs, &eigenvectors_path, format)?;
+    status(progress, quiet, &format!("✅ Eigenvectors saved to {}", eigenvectors_path.display()));
     
-    // Save eigenvalues to CSV
-    let eigenvalues_csv_path = output_path.as_ref().with_extension("eigenvalues.csv");
-    save_vector_to_csv_dsbevd(&eigvals, &eigenvalues_csv_path)?;
-    println!("✅ Eigenvalues saved to {}", eigenvalues_csv_path.display());
+    // Save the eigenvalues
+    let eigenvalues_path = output_path.as_ref().with_extension(format!("eigenvalues.{}", format.extension()));
+    save_vector(&eigvals, &eigenvalues_path, format)?;
+    status(progress, quiet, &format!("✅ Eigenvalues saved to {}", eigenvalues_path.display()));
     
     // Compute and Print NGEC
-    println!("📊 Computing Normalized Global Eigen-Complexity (NGEC)...");
-    let ngec = compute_ngec(&eigvals)?;
-    println!("✅ NGEC: {:.4}", ngec);
+    phase(progress, 4);
+    status(progress, quiet, "📊 Computing Normalized Global Eigen-Complexity (NGEC)...");
+    let ngec = compute_ngec_with_floor(&eigvals, clamp_floor)?;
+    progress.println(&format!("✅ NGEC: {:.4}", ngec));
     
     // Print heatmaps
-    println!("🎨 Printing heatmaps:");
-    println!("Laplacian Matrix:");
-    print_heatmap(&laplacian.view());
+    phase(progress, 5);
+    if !quiet {
+        progress.suspend(&mut || {
+            println!("🎨 Printing heatmaps:");
+            println!("Laplacian Matrix:");
+            print_heatmap(&laplacian.view());
+
+            println!("Eigenvectors:");
+            let eigenvecs_subset = eigvecs.slice(s![.., 0..min(500, eigvecs.ncols())]); // Display at max first 500
+            print_heatmap_ndarray(&eigenvecs_subset.to_owned());
 
-    println!("Eigenvectors:");
-    let eigenvecs_subset = eigvecs.slice(s![.., 0..min(500, eigvecs.ncols())]); // Display at max first 500
-    print_heatmap_ndarray(&eigenvecs_subset.to_owned());
+            println!("Eigenvalues:");
+            print_eigenvalues_heatmap(&eigvals);
+        });
+    }
 
-    println!("Eigenvalues:");
-    print_eigenvalues_heatmap(&eigvals);
+    progress.phase("done", 1.0);
 
     let duration = start_time.elapsed();
-    println!("⏰ Completed in {:.2?} seconds.", duration);
+    status(progress, quiet, &format!("⏰ Completed in {:.2?} seconds.", duration));
+
+    Ok(())
+}
+
+/// Computes the NGEC of consecutive node windows of `adjacency`, giving a complexity track along a
+/// linearized graph.
+///
+/// Windows cover nodes `start..start + window` for `start = 0, step, 2 * step, ...` as long as the
+/// window fits, so a trailing partial window is dropped. Each window's submatrix is extracted, its
+/// combinatorial Laplacian decomposed, and its NGEC computed with `compute_ngec_with_floor` at
+/// `DEFAULT_SPECTRUM_FLOOR`. A window whose NGEC is undefined (e.g. it contains no edges) gets `NaN`.
+///
+/// # Returns
+///
+/// The `(start, ngec)` pair of every window, in order of `start`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `window` or `step` is zero, and any decomposition error.
+pub fn windowed_ngec(adjacency: &Array2<f64>, window: usize, step: usize) -> io::Result<Vec<(usize, f64)>> {
+    windowed_ngec_with_options(adjacency, window, step, &ExtractOptions::default())
+}
+
+/// Same as `windowed_ngec`, decomposing the Laplacian variant `options.kind` and computing the NGEC
+/// at `options.clamp_floor`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `window` or `step` is zero, and any decomposition error.
+pub fn windowed_ngec_with_options(
+    adjacency: &Array2<f64>,
+    window: usize,
+    step: usize,
+    options: &ExtractOptions,
+) -> io::Result<Vec<(usize, f64)>> {
+    check_window(window, step)?;
+
+    let n = adjacency.nrows();
+    (0..n)
+        .step_by(step)
+        .take_while(|&start| start + window <= n)
+        .map(|start| {
+            let submatrix = adjacency.slice(s![start..start + window, start..start + window]).to_owned();
+            let (eigvals, _) = call_eigendecomp(&build_laplacian(&submatrix, options.kind))?;
+            // Only the NGEC itself may be undefined; a failed decomposition is an error
+            let ngec = compute_ngec_with_floor(&eigvals, options.clamp_floor).unwrap_or(f64::NAN);
+            Ok((start, ngec))
+        })
+        .collect()
+}
+
+/// Checks the window length and step of `windowed_ngec`.
+fn check_window(window: usize, step: usize) -> io::Result<()> {
+    if window == 0 || step == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Window and step must be positive, got {} and {}.", window, step),
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the subgraph induced by the nodes whose tag equals `wanted`, e.g. one chromosome of a
+/// whole-genome GFA.
+///
+/// `names` and `tags` run parallel to the rows of `adjacency`, as the names returned by `load_gfa`
+/// and a per-segment attribute such as the `contig` of `load_rgfa_coordinates`. Edges to nodes
+/// outside the selection are dropped.
+///
+/// # Returns
+///
+/// The induced adjacency matrix and the original indices of its nodes, in ascending order.
+///
+/// # Panics
+///
+/// Panics if `adjacency` is not square or `names` and `tags` do not have one entry per node.
+pub fn extract_by_tag(adjacency: &Array2<f64>, names: &[String], tags: &[String], wanted: &str) -> (Array2<f64>, Vec<usize>) {
+    let n = adjacency.nrows();
+    assert_eq!(adjacency.ncols(), n, "The adjacency matrix must be square.");
+    assert!(
+        names.len() == n && tags.len() == n,
+        "Expected {} names and tags, got {} and {}.",
+        n,
+        names.len(),
+        tags.len()
+    );
+
+    let kept: Vec<usize> = (0..n).filter(|&i| tags[i] == wanted).collect();
+    let submatrix = adjacency.select(Axis(0), &kept).select(Axis(1), &kept);
+    (submatrix, kept)
+}
+
+/// Loads a GFA file, computes its `windowed_ngec` track, and writes it as a bedGraph file
+/// (`chrom\tstart\tend\tngec`, 0-based half-open) for a genome browser.
+///
+/// If every segment carries rGFA `SN`/`SO` tags, nodes are ordered by `(contig, offset)`, each
+/// contig is windowed on its own (so no window spans two contigs), and the records use reference
+/// coordinates; otherwise nodes keep the `load_gfa` order and each record is keyed by node index on
+/// a single sequence named after the file stem. A record covers its window's nodes up to the next
+/// window's first node, so records never overlap even when `step < window`. Windows whose NGEC is
+/// undefined, and records that would be empty because their segments have no length, are omitted.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `window` or `step` is zero before the file is read, and any parse,
+/// decomposition or I/O errors encountered.
+pub fn ngec_track_gfa<P: AsRef<Path>>(gfa_path: P, window: usize, step: usize, output_path: P) -> io::Result<()> {
+    ngec_track_gfa_with_options(gfa_path, window, step, &ExtractOptions::default(), output_path, &mut NoProgress)
+}
+
+/// Same as `ngec_track_gfa`, computing every window's NGEC with `windowed_ngec_with_options`.
+/// `progress` sees a `"loading"` phase, then one `"windows"` phase per contig.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` for a non-symmetric `options.kind` before the file is read, and the
+/// errors of `ngec_track_gfa`.
+pub fn ngec_track_gfa_with_options<P: AsRef<Path>>(
+    gfa_path: P,
+    window: usize,
+    step: usize,
+    options: &ExtractOptions,
+    output_path: P,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    check_window(window, step)?;
+    check_laplacian_supported(options.kind)?;
+    progress.phase("loading", 0.0);
+    status(progress, options.quiet, &format!("📂 Loading GFA from {:?}", gfa_path.as_ref()));
+    let (adjacency, names) = load_gfa(gfa_path.as_ref())?;
+    let coordinates = load_rgfa_coordinates(gfa_path.as_ref())?;
+
+    let rgfa = !names.is_empty() && names.iter().all(|name| coordinates.contains_key(name));
+    let mut order: Vec<usize> = (0..names.len()).collect();
+    if rgfa {
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&coordinates[&names[a]], &coordinates[&names[b]]);
+            (&a.contig, a.offset).cmp(&(&b.contig, b.offset))
+        });
+    }
+    let stem = gfa_path
+        .as_ref()
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "graph".to_string());
+
+    // Windows never span two contigs, so each contig's nodes get a track of their own
+    let mut contigs: Vec<(String, Vec<usize>)> = Vec::new();
+    for &node in &order {
+        let chrom = if rgfa { &coordinates[&names[node]].contig } else { &stem };
+        match contigs.last_mut() {
+            Some((last, nodes)) if last == chrom => nodes.push(node),
+            _ => contigs.push((chrom.clone(), vec![node])),
+        }
+    }
+
+    status(
+        progress,
+        options.quiet,
+        &format!(
+            "🔬 Computing NGEC over windows of {} nodes (step {}) by {}...",
+            window,
+            step,
+            if rgfa { "rGFA coordinates" } else { "node index" }
+        ),
+    );
+    let mut writer = BufWriter::new(File::create(&output_path)?);
+    for (index, (chrom, nodes)) in contigs.iter().enumerate() {
+        progress.phase("windows", index as f64 / contigs.len() as f64);
+        let submatrix = adjacency.select(Axis(0), nodes).select(Axis(1), nodes);
+        let track = windowed_ngec_with_options(&submatrix, window, step, options)?;
+        for &(start, ngec) in track.iter().filter(|(_, ngec)| ngec.is_finite()) {
+            // The record covers the nodes up to the next window's first node
+            let span = start..min(start + step, start + window);
+            let (begin, end) = if rgfa {
+                let first = coordinates[&names[nodes[start]]].offset;
+                let end = span
+                    .map(|i| &coordinates[&names[nodes[i]]])
+                    .map(|coordinate| coordinate.offset + coordinate.length)
+                    .max()
+                    .unwrap_or(first);
+                (first, end)
+            } else {
+                (span.start as u64, span.end as u64)
+            };
+            // Segments without a length would give an empty interval, which bedGraph cannot hold
+            if end > begin {
+                writeln!(writer, "{}\t{}\t{}\t{}", chrom, begin, end, ngec)?;
+            }
+        }
+    }
+    writer.flush()?;
+    progress.phase("done", 1.0);
+    status(progress, options.quiet, &format!("✅ NGEC track saved to {}", output_path.as_ref().display()));
 
     Ok(())
 }
 
+/// Loads a GFA file, ranks its segments by Fiedler value, and writes the ranking as a TSV file
+/// with a `segment\tfiedler` header and one line per segment, lowest value first.
+///
+/// # Arguments
+///
+/// * `gfa_path` - Path to the input GFA file.
+/// * `output_path` - Path to the output TSV file.
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any parse, decomposition, or I/O errors encountered.
+pub fn rank_gfa_by_fiedler<P: AsRef<Path>>(gfa_path: P, output_path: P) -> io::Result<()> {
+    rank_gfa_by_fiedler_with_options(gfa_path, &ExtractOptions::default(), output_path, &mut NoProgress)
+}
+
+/// Same as `rank_gfa_by_fiedler`, ranking by the Fiedler vector of the Laplacian variant
+/// `options.kind` (see `rank_by_fiedler_with_laplacian`). `OutputFormat::Json` writes the ranking
+/// as a labeled one-column matrix (see `save_array_to_json_labeled`) instead of the TSV.
+/// `progress` sees the phases `"loading"`, `"fiedler"` and `"saving"`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` for a non-symmetric `options.kind` or a format that cannot hold the
+/// segment names (`npy`, `mtx`) before the file is read, and the errors of `rank_gfa_by_fiedler`.
+pub fn rank_gfa_by_fiedler_with_options<P: AsRef<Path>>(
+    gfa_path: P,
+    options: &ExtractOptions,
+    output_path: P,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    check_laplacian_supported(options.kind)?;
+    options.format.check_supports(OutputData::LabeledMatrix)?;
+    progress.phase("loading", 0.0);
+    status(progress, options.quiet, &format!("📂 Loading GFA from {:?}", gfa_path.as_ref()));
+    let (adjacency, names) = load_gfa(gfa_path.as_ref())?;
+
+    progress.phase("fiedler", 1.0 / 3.0);
+    status(
+        progress,
+        options.quiet,
+        &format!("🔬 Computing {} Fiedler vector of {} segments...", options.kind, names.len()),
+    );
+    let ranking = rank_by_fiedler_with_laplacian(&adjacency, options.kind)?;
+
+    progress.phase("saving", 2.0 / 3.0);
+
+    if options.format == OutputFormat::Json {
+        let values = Array2::from_shape_fn((ranking.len(), 1), |(i, _)| ranking[i].1);
+        let segments: Vec<String> = ranking.iter().map(|(node, _)| names[*node].clone()).collect();
+        save_array_to_json_labeled(&values, &segments, &["fiedler".to_string()], &output_path)?;
+    } else {
+        let mut writer = BufWriter::new(File::create(&output_path)?);
+        writeln!(writer, "segment\tfiedler")?;
+        for (node, value) in &ranking {
+            writeln!(writer, "{}\t{}", names[*node], value)?;
+        }
+        writer.flush()?;
+    }
+    progress.phase("done", 1.0);
+    status(progress, options.quiet, &format!("✅ Fiedler ranking saved to {}", output_path.as_ref().display()));
+
+    Ok(())
+}
+
+/// Computes a `dims`-dimensional spectral embedding of every GFA segment (see
+/// `scaled_spectral_embedding`) and writes it as a labeled TSV, with a `segment\tdim1\t...` header
+/// and one row per segment, ready for a 2D projector such as t-SNE or UMAP.
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any parse, decomposition, or I/O errors encountered.
+pub fn embed_gfa<P: AsRef<Path>>(gfa_path: P, dims: usize, scaling: EmbeddingScaling, output_path: P) -> io::Result<()> {
+    embed_gfa_with_options(gfa_path, dims, scaling, &ExtractOptions::default(), output_path, &mut NoProgress)
+}
+
+/// Same as `embed_gfa`, embedding with the eigenvectors of the Laplacian variant `options.kind`.
+/// Commute-time scaling assumes the combinatorial Laplacian's spectrum. `OutputFormat::Json`
+/// writes a labeled matrix (see `save_array_to_json_labeled`) instead of the TSV. `progress` sees
+/// the phases `"loading"`, `"decomposition"` and `"saving"`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` for a non-symmetric `options.kind` or a format that cannot hold the
+/// segment names (`npy`, `mtx`) before the file is read, and the errors of `embed_gfa`.
+pub fn embed_gfa_with_options<P: AsRef<Path>>(
+    gfa_path: P,
+    dims: usize,
+    scaling: EmbeddingScaling,
+    options: &ExtractOptions,
+    output_path: P,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    check_laplacian_supported(options.kind)?;
+    options.format.check_supports(OutputData::LabeledMatrix)?;
+    progress.phase("loading", 0.0);
+    status(progress, options.quiet, &format!("📂 Loading GFA from {:?}", gfa_path.as_ref()));
+    let (adjacency, names) = load_gfa(gfa_path.as_ref())?;
+
+    progress.phase("decomposition", 1.0 / 3.0);
+    status(
+        progress,
+        options.quiet,
+        &format!("🔬 Computing a {}-dimensional {:?} embedding of {} segments...", dims, scaling, names.len()),
+    );
+    let laplacian = build_laplacian(&adjacency, options.kind);
+    let (eigvals, eigvecs) = call_eigendecomp_canonical(&laplacian)?;
+    let embedding = scaled_spectral_embedding(&eigvals, &eigvecs, dims, scaling);
+    if embedding.ncols() < dims {
+        progress.println(&format!("❗ Only {} nontrivial eigenvectors are available.", embedding.ncols()));
+    }
+
+    progress.phase("saving", 2.0 / 3.0);
+
+    let header: Vec<String> = (1..=embedding.ncols()).map(|d| format!("dim{}", d)).collect();
+    if options.format == OutputFormat::Json {
+        save_array_to_json_labeled(&embedding, &names, &header, &output_path)?;
+    } else {
+        let mut writer = BufWriter::new(File::create(&output_path)?);
+        writeln!(writer, "segment\t{}", header.join("\t"))?;
+        for (name, row) in names.iter().zip(embedding.rows()) {
+            let values: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+            writeln!(writer, "{}\t{}", name, values.join("\t"))?;
+        }
+        writer.flush()?;
+    }
+    progress.phase("done", 1.0);
+    status(progress, options.quiet, &format!("✅ Embedding saved to {}", output_path.as_ref().display()));
+
+    Ok(())
+}
+
+/// Computes the pairwise `spectral_distance` between the combinatorial Laplacian spectra of every
+/// GFA file matching `pattern`, and writes the distance matrix as a labeled TSV file.
+///
+/// The pattern may use `*` and `?` in its last component (e.g. `samples/*.gfa`). Files are taken
+/// in sorted order and labeled by their stem; the TSV has a header row and a label column, so
+/// cell `(i, j)` is the distance between graphs `i` and `j`. Graphs of different sizes are
+/// compared by zero-padding the smaller spectrum (see `spectral_distance`).
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any parse, decomposition, or I/O errors encountered, or
+/// `NotFound` if no file matches.
+pub fn write_spectral_distances<P: AsRef<Path>>(pattern: &str, output_path: P) -> io::Result<()> {
+    write_spectral_distances_with_options(pattern, &ExtractOptions::default(), output_path, &mut NoProgress)
+}
+
+/// Same as `write_spectral_distances`, comparing the spectra of the Laplacian variant
+/// `options.kind` and writing the labeled TSV for `OutputFormat::Csv` or a labeled JSON object
+/// (see `save_array_to_json_labeled`) for `OutputFormat::Json`. `progress` sees one `"spectra"`
+/// phase per file, then `"saving"`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` for a non-symmetric `options.kind` or for `npy` and `mtx`, which cannot
+/// hold the labels, before any file is read.
+pub fn write_spectral_distances_with_options<P: AsRef<Path>>(
+    pattern: &str,
+    options: &ExtractOptions,
+    output_path: P,
+    progress: &mut dyn Progress,
+) -> io::Result<()> {
+    let ExtractOptions { kind, format, quiet, .. } = *options;
+    check_laplacian_supported(kind)?;
+    format.check_supports(OutputData::LabeledMatrix)?;
+    let paths = expand_glob(pattern)?;
+    if paths.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No files match {}", pattern),
+        ));
+    }
+
+    let mut labels = Vec::with_capacity(paths.len());
+    let mut spectra = Vec::with_capacity(paths.len());
+    // Saving counts as one more step after the spectra
+    let steps = (paths.len() + 1) as f64;
+    for (index, path) in paths.iter().enumerate() {
+        progress.phase("spectra", index as f64 / steps);
+        status(progress, quiet, &format!("🔬 Computing spectrum of {}", path.display()));
+        let (adjacency, _) = load_gfa(path)?;
+        let (eigvals, _) = call_eigendecomp(&build_laplacian(&adjacency, kind))?;
+        labels.push(path.file_stem().unwrap_or_default().to_string_lossy().into_owned());
+        spectra.push(eigvals);
+    }
+
+    progress.phase("saving", paths.len() as f64 / steps);
+    if format == OutputFormat::Json {
+        let distances = Array2::from_shape_fn((spectra.len(), spectra.len()), |(i, j)| {
+            spectral_distance(&spectra[i], &spectra[j])
+        });
+        save_array_to_json_labeled(&distances, &labels, &labels, &output_path)?;
+    } else {
+        let mut writer = BufWriter::new(File::create(&output_path)?);
+        writeln!(writer, "\t{}", labels.join("\t"))?;
+        for (i, label) in labels.iter().enumerate() {
+            let row: Vec<String> = spectra.iter().map(|other| spectral_distance(&spectra[i], other).to_string()).collect();
+            writeln!(writer, "{}\t{}", label, row.join("\t"))?;
+        }
+        writer.flush()?;
+    }
+    progress.phase("done", 1.0);
+    status(progress, quiet, &format!("✅ Spectral distances saved to {}", output_path.as_ref().display()));
+
+    Ok(())
+}
+
+/// How a sample graph differs from a reference graph, as reported by `compare_gfa`. Every
+/// difference is sample minus reference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphComparison {
+    /// `spectral_distance` between the Laplacian spectra (zero-padded if the sizes differ).
+    pub spectral_distance: f64,
+    /// Difference in NGEC (see `compute_ngec_with_floor`, at `DEFAULT_SPECTRUM_FLOOR` unless
+    /// `compare_gfa_with_options` sets another floor).
+    pub ngec_difference: f64,
+    /// Difference in the number of connected components.
+    pub component_difference: i64,
+    /// Difference in algebraic connectivity, the second-smallest Laplacian eigenvalue.
+    pub algebraic_connectivity_difference: f64,
+}
+
+/// Compares two GFA graphs through their combinatorial Laplacian spectra and prints a report.
+///
+/// Graphs of different sizes are compared as `spectral_distance` documents: the smaller spectrum
+/// is padded with zeros, which matches adding isolated nodes to the smaller graph. The NGEC and
+/// algebraic connectivity are computed on each graph's own spectrum.
+///
+/// # Arguments
+///
+/// * `reference_path` - Path to the reference GFA file.
+/// * `sample_path` - Path to the sample GFA file.
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any parse or decomposition errors encountered, or
+/// `InvalidInput` if either graph has fewer than two segments (its NGEC is undefined).
+pub fn compare_gfa<P: AsRef<Path>>(reference_path: P, sample_path: P) -> io::Result<GraphComparison> {
+    compare_gfa_with_options(reference_path, sample_path, &ExtractOptions::default(), &mut NoProgress)
+}
+
+/// Same as `compare_gfa`, comparing the spectra of the Laplacian variant `options.kind` and
+/// computing each NGEC at `options.clamp_floor`. `progress` sees the phases `"reference"` and
+/// `"sample"`; the report is printed even with `options.quiet`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` for a non-symmetric `options.kind` before either file is read, and the
+/// errors of `compare_gfa`.
+pub fn compare_gfa_with_options<P: AsRef<Path>>(
+    reference_path: P,
+    sample_path: P,
+    options: &ExtractOptions,
+    progress: &mut dyn Progress,
+) -> io::Result<GraphComparison> {
+    check_laplacian_supported(options.kind)?;
+    let summarize = |path: &Path, progress: &mut dyn Progress| -> io::Result<(Array1<f64>, f64, usize, f64)> {
+        status(progress, options.quiet, &format!("🔬 Computing spectrum of {}", path.display()));
+        let (adjacency, _) = load_gfa(path)?;
+        let (eigvals, _) = call_eigendecomp(&build_laplacian(&adjacency, options.kind))?;
+        let ngec = compute_ngec_with_floor(&eigvals, options.clamp_floor)?;
+        let components = connected_components(&adjacency).len();
+        let connectivity = eigvals.get(1).copied().unwrap_or(0.0).max(0.0);
+        Ok((eigvals, ngec, components, connectivity))
+    };
+    progress.phase("reference", 0.0);
+    let (reference, reference_ngec, reference_components, reference_connectivity) =
+        summarize(reference_path.as_ref(), progress)?;
+    progress.phase("sample", 0.5);
+    let (sample, sample_ngec, sample_components, sample_connectivity) =
+        summarize(sample_path.as_ref(), progress)?;
+    progress.phase("done", 1.0);
+
+    let comparison = GraphComparison {
+        spectral_distance: spectral_distance(&reference, &sample),
+        ngec_difference: sample_ngec - reference_ngec,
+        component_difference: sample_components as i64 - reference_components as i64,
+        algebraic_connectivity_difference: sample_connectivity - reference_connectivity,
+    };
+
+    progress.println(&format!(
+        "📊 {} ({} nodes) vs {} ({} nodes), sample minus reference:",
+        reference_path.as_ref().display(),
+        reference.len(),
+        sample_path.as_ref().display(),
+        sample.len()
+    ));
+    progress.println(&format!("spectral_distance\t{}", comparison.spectral_distance));
+    progress.println(&format!("ngec_difference\t{}", comparison.ngec_difference));
+    progress.println(&format!("component_difference\t{}", comparison.component_difference));
+    progress.println(&format!(
+        "algebraic_connectivity_difference\t{}",
+        comparison.algebraic_connectivity_difference
+    ));
+
+    Ok(comparison)
+}
+
+/// Lists the files matching a pattern with `*` and `?` wildcards in its last component, sorted.
+fn expand_glob(pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let pattern_path = Path::new(pattern);
+    let file_pattern = pattern_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let directory = match pattern_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(&directory)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_file() && wildcard_match(&file_pattern, &name) {
+            paths.push(directory.join(name));
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Matches `name` against a pattern where `*` is any run of characters and `?` any one character.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // matches[j] is whether the pattern prefix seen so far matches name[..j]
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for &p in &pattern {
+        if p == '*' {
+            for j in 1..=name.len() {
+                matches[j] = matches[j] || matches[j - 1];
+            }
+        } else {
+            for j in (1..=name.len()).rev() {
+                matches[j] = matches[j - 1] && (p == '?' || p == name[j - 1]);
+            }
+            matches[0] = false;
+        }
+    }
+    matches[name.len()]
+}
+
+/// Loads a cached banded matrix (a `.npy` file of `to_banded_format` output, see
+/// `save_array_to_npy`), computes only the eigenpairs `il..=iu` (1-based), and saves the
+/// eigenvalues to `output_path` and the eigenvectors next to it with the extension
+/// `eigenvectors.csv`.
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any file, range, or decomposition errors encountered.
+pub fn eigen_range_from_banded<P: AsRef<Path>>(banded_path: P, il: usize, iu: usize, output_path: P) -> io::Result<()> {
+    eigen_range_from_banded_with_options(banded_path, il, iu, &ExtractOptions::default(), output_path)
+}
+
+/// Same as `eigen_range_from_banded`, writing both outputs in `options.format`; the eigenvectors
+/// go next to `output_path` as `.eigenvectors.<ext>`.
+pub fn eigen_range_from_banded_with_options<P: AsRef<Path>>(
+    banded_path: P,
+    il: usize,
+    iu: usize,
+    options: &ExtractOptions,
+    output_path: P,
+) -> io::Result<()> {
+    let format = options.format;
+    println!("📂 Loading banded matrix from {:?}", banded_path.as_ref());
+    let banded = load_array_from_npy(&banded_path)?;
+
+    println!(
+        "🔬 Computing eigenpairs {}..={} of n = {}, kd = {}...",
+        il,
+        iu,
+        banded.ncols(),
+        banded.nrows().saturating_sub(1)
+    );
+    let (eigvals, eigvecs) = compute_eigenvalues_and_vectors_banded_range(&banded, il, iu)?;
+
+    save_vector(&eigvals, &output_path, format)?;
+    println!("✅ Eigenvalues saved to {}", output_path.as_ref().display());
+    let eigenvectors_path = output_path.as_ref().with_extension(format!("eigenvectors.{}", format.extension()));
+    save_matrix(&eigvecs, &eigenvectors_path, format)?;
+    println!("✅ Eigenvectors saved to {}", eigenvectors_path.display());
+
+    Ok(())
+}
+
+/// Streams a square CSV adjacency into its banded combinatorial Laplacian and saves it as `.npy`
+/// for `eigen_range_from_banded`. With `kd = None` a first pass over the file finds `max_band`.
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any file or CSV errors, or `InvalidData` if a nonzero entry lies
+/// outside an explicit `kd`.
+pub fn band_csv_to_npy<P: AsRef<Path>>(csv_path: P, kd: Option<usize>, output_path: P) -> io::Result<()> {
+    let options = ExtractOptions { format: OutputFormat::Npy, ..ExtractOptions::default() };
+    band_csv_with_options(csv_path, kd, &options, output_path)
+}
+
+/// Same as `band_csv_to_npy`, saving the banded Laplacian `options.kind` in `options.format`.
+/// Only `.npy` output feeds `eigen_range_from_banded`; the other formats are for inspection
+/// elsewhere. The random-walk Laplacian is not symmetric and is rejected (see
+/// `check_laplacian_supported`).
+///
+/// With `options.max_band` set, a bandwidth (explicit or found by the first pass) above the cap
+/// is rejected with `InvalidInput` before the banded storage is allocated (see `check_max_band`).
+pub fn band_csv_with_options<P: AsRef<Path>>(
+    csv_path: P,
+    kd: Option<usize>,
+    options: &ExtractOptions,
+    output_path: P,
+) -> io::Result<()> {
+    let ExtractOptions { kind, format, max_band, .. } = *options;
+    check_laplacian_supported(kind)?;
+    let kd = match kd {
+        Some(kd) => kd,
+        None => {
+            println!("🔍 Scanning {:?} for the bandwidth...", csv_path.as_ref());
+            csv_max_band(&csv_path)?
+        }
+    };
+
+    check_max_band(kd, max_band)?;
+
+    println!("📐 Building the banded Laplacian with kd = {}...", kd);
+    let banded = csv_laplacian_to_banded(&csv_path, kd, kind)?;
+    save_matrix(&banded, &output_path, format)?;
+    println!(
+        "✅ Banded Laplacian ({} x {}) saved to {}",
+        banded.nrows(),
+        banded.ncols(),
+        output_path.as_ref().display()
+    );
+
+    Ok(())
+}
+
+/// Same as `band_csv_with_options`, but first tries a reverse Cuthill–McKee reordering and reports the
+/// bandwidth before and after (see `reorder_for_bandwidth`).
+///
+/// Reordering needs the whole matrix, so the CSV is read densely rather than streamed. The
+/// permutation is applied only if it strictly shrinks the bandwidth; it is then written next to
+/// the output as `.order.csv`, one original node index per line in the new order. With `kd = None`
+/// the bandwidth of the matrix actually saved is used, and `options` is read as in
+/// `band_csv_with_options`.
+///
+/// # Returns
+///
+/// The `BandwidthReport`, whether or not the reordering was applied.
+///
+/// # Errors
+///
+/// Returns an `io::Result` with any file or CSV errors, or `InvalidData` if the matrix is not
+/// square or a nonzero entry lies outside an explicit `kd`.
+pub fn band_csv_reordered<P: AsRef<Path>>(
+    csv_path: P,
+    kd: Option<usize>,
+    options: &ExtractOptions,
+    output_path: P,
+) -> io::Result<BandwidthReport> {
+    let ExtractOptions { kind, format, max_band, .. } = *options;
+    check_laplacian_supported(kind)?;
+    println!("📂 Loading {:?} to reorder it...", csv_path.as_ref());
+    let adjacency = read_array_from_csv(&csv_path)?;
+    if adjacency.nrows() != adjacency.ncols() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Adjacency matrix must be square, got {:?}.", adjacency.dim()),
+        ));
+    }
+
+    let (reordered, order, report) = reorder_for_bandwidth(&adjacency);
+    println!(
+        "📐 original_band = {}, reordered_band = {} ({:.1}% reduction)",
+        report.original_band,
+        report.reordered_band,
+        report.reduction_percent()
+    );
+
+    let adjacency = if report.improved() {
+        let order_path = output_path.as_ref().with_extension("order.csv");
+        let mut writer = BufWriter::new(File::create(&order_path)?);
+        for node in &order {
+            writeln!(writer, "{}", node)?;
+        }
+        writer.flush()?;
+        println!("✅ Node order saved to {}", order_path.display());
+        reordered
+    } else {
+        println!("⏭️  Reordering does not reduce the bandwidth; keeping the original order.");
+        adjacency
+    };
+
+    let band = crate::eigen::max_band(&adjacency) as usize;
+    let kd = kd.unwrap_or(band);
+    if kd < band {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("kd = {} is smaller than the bandwidth {} of the matrix.", kd, band),
+        ));
+    }
+
+    check_max_band(kd, max_band)?;
+
+    println!("📐 Building the banded Laplacian with kd = {}...", kd);
+    let laplacian = build_laplacian(&adjacency, kind);
+    let banded = to_banded_format(&laplacian, kd as i32);
+    save_matrix(&banded, &output_path, format)?;
+    println!(
+        "✅ Banded Laplacian ({} x {}) saved to {}",
+        banded.nrows(),
+        banded.ncols(),
+        output_path.as_ref().display()
+    );
+
+    Ok(report)
+}
+
+/// Checks that the Laplacian variant can be decomposed by the symmetric eigensolvers.
+pub fn check_laplacian_supported(kind: LaplacianKind) -> io::Result<()> {
+    if kind.is_symmetric() {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "The {} Laplacian is not symmetric and cannot be used with the symmetric eigensolver; \
+             use --laplacian normalized instead, which has the same eigenvalues.",
+            kind
+        ),
+    ))
+}
+
 /// Loads the adjacency matrix from a binary edge list file (.gam)
 pub fn load_adjacency_matrix<P: AsRef<Path>>(
     path: P,
@@ -105,7 +977,7 @@ pub fn load_adjacency_matrix<P: AsRef<Path>>(
     let mut buffer = [0u8; 8];
     let mut edges = Vec::new();
 
-    while let Ok(_) = reader.read_exact(&mut buffer) {
+    while reader.read_exact(&mut buffer).is_ok() {
         let from = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
         let to = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]);
 
@@ -0,0 +1,904 @@
+// src/graph.rs
+
+//! Module for building and representing graph adjacency structures.
+
+use ndarray::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::eigen::max_band;
+use crate::laplacian::degrees;
+
+/// How `AdjacencyBuilder` and the GFA loaders combine the weights of an edge that is added more
+/// than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateEdgePolicy {
+    /// Keep the most recently added weight. This matches `adjacency_matrix_to_ndarray`.
+    #[default]
+    Overwrite,
+    /// Keep the first weight and ignore later ones, e.g. for unweighted graphs.
+    First,
+    /// Add the weights together, e.g. for coverage-weighted graphs or to count parallel links.
+    Sum,
+    /// Keep the largest weight.
+    Max,
+    /// Reject the duplicate with an `InvalidData` error.
+    Error,
+}
+
+impl DuplicateEdgePolicy {
+    /// Combines the weight already stored for an edge with a newly added one, or returns `None`
+    /// under `Error`.
+    pub fn combine(self, existing: f64, new: f64) -> Option<f64> {
+        match self {
+            DuplicateEdgePolicy::Overwrite => Some(new),
+            DuplicateEdgePolicy::First => Some(existing),
+            DuplicateEdgePolicy::Sum => Some(existing + new),
+            DuplicateEdgePolicy::Max => Some(existing.max(new)),
+            DuplicateEdgePolicy::Error => None,
+        }
+    }
+}
+
+/// Builds an undirected adjacency matrix one edge at a time.
+///
+/// The node set grows as new IDs appear: the built matrix has `max id + 1` rows. An edge `(u, v)`
+/// and its reverse `(v, u)` are the same undirected edge, so adding both counts as a duplicate.
+#[derive(Debug, Clone, Default)]
+pub struct AdjacencyBuilder {
+    policy: DuplicateEdgePolicy,
+    num_nodes: usize,
+    /// Edge weights keyed by `(min(u, v), max(u, v))`.
+    weights: HashMap<(usize, usize), f64>,
+}
+
+impl AdjacencyBuilder {
+    /// Creates an empty builder with the default `Overwrite` policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty builder with the given duplicate-edge policy.
+    pub fn with_policy(policy: DuplicateEdgePolicy) -> Self {
+        AdjacencyBuilder {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Adds the undirected edge `(u, v)` with weight `w`, growing the node set if needed. An edge
+    /// already present is resolved with the builder's `DuplicateEdgePolicy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if the edge is a duplicate under `DuplicateEdgePolicy::Error`; the
+    /// builder is left unchanged.
+    pub fn add_edge(&mut self, u: usize, v: usize, w: f64) -> io::Result<()> {
+        let key = (u.min(v), u.max(v));
+        let weight = match self.weights.get(&key) {
+            Some(&existing) => self.policy.combine(existing, w).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Duplicate edge ({}, {}).", u, v),
+                )
+            })?,
+            None => w,
+        };
+
+        self.num_nodes = self.num_nodes.max(u.max(v) + 1);
+        self.weights.insert(key, weight);
+        Ok(())
+    }
+
+    /// Number of nodes seen so far (largest ID plus one).
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    /// Number of distinct undirected edges (self-loops included).
+    pub fn num_edges(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Builds the dense symmetric adjacency matrix.
+    pub fn build(&self) -> Array2<f64> {
+        let mut adjacency = Array2::<f64>::zeros((self.num_nodes, self.num_nodes));
+        for (&(u, v), &w) in &self.weights {
+            adjacency[[u, v]] = w;
+            adjacency[[v, u]] = w;
+        }
+        adjacency
+    }
+
+    /// Builds the symmetric adjacency matrix in compressed sparse row form.
+    pub fn build_sparse(&self) -> SparseAdjacency {
+        let mut entries: Vec<(usize, usize, f64)> = Vec::with_capacity(2 * self.weights.len());
+        for (&(u, v), &w) in &self.weights {
+            entries.push((u, v, w));
+            if u != v {
+                entries.push((v, u, w));
+            }
+        }
+        entries.sort_by_key(|&(u, v, _)| (u, v));
+
+        let mut indptr = vec![0; self.num_nodes + 1];
+        for &(u, _, _) in &entries {
+            indptr[u + 1] += 1;
+        }
+        for i in 0..self.num_nodes {
+            indptr[i + 1] += indptr[i];
+        }
+
+        SparseAdjacency {
+            num_nodes: self.num_nodes,
+            indptr,
+            indices: entries.iter().map(|&(_, v, _)| v).collect(),
+            values: entries.iter().map(|&(_, _, w)| w).collect(),
+        }
+    }
+
+    /// Streams the edges of a binary edge list file (.gam) into a new builder with weight 1 each,
+    /// without holding the edge list in memory.
+    ///
+    /// `.gam` files store every undirected edge in both directions, so a record that mirrors an
+    /// earlier unpaired one (`(v, u)` after `(u, v)`, or a self-loop written twice) is the other half of the same edge and is skipped;
+    /// only records that genuinely repeat an edge go through `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any I/O error, and `InvalidData` for a repeated edge under `DuplicateEdgePolicy::Error`.
+    pub fn from_edge_list<P: AsRef<Path>>(path: P, policy: DuplicateEdgePolicy) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut builder = Self::with_policy(policy);
+        let mut buffer = [0u8; 8];
+        let mut unmirrored = HashSet::new();
+
+        while reader.read_exact(&mut buffer).is_ok() {
+            let from = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as usize;
+            let to = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+            if unmirrored.remove(&(to, from)) {
+                continue;
+            }
+            unmirrored.insert((from, to));
+            builder.add_edge(from, to, 1.0)?;
+        }
+
+        Ok(builder)
+    }
+}
+
+/// Magic bytes identifying a file written by `SparseAdjacency::save`.
+const SPARSE_MAGIC: &[u8; 4] = b"GCSR";
+/// Version of the layout written by `SparseAdjacency::save`.
+const SPARSE_VERSION: u8 = 1;
+
+/// A square adjacency matrix in compressed sparse row (CSR) form.
+///
+/// Row `i` holds the column indices `indices[indptr[i]..indptr[i + 1]]` in ascending order,
+/// with the matching weights in `values`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseAdjacency {
+    pub num_nodes: usize,
+    pub indptr: Vec<usize>,
+    pub indices: Vec<usize>,
+    pub values: Vec<f64>,
+}
+
+impl SparseAdjacency {
+    /// Converts a dense square matrix, storing only its nonzero entries.
+    pub fn from_dense(matrix: &Array2<f64>) -> Self {
+        let num_nodes = matrix.nrows();
+        let mut indptr = Vec::with_capacity(num_nodes + 1);
+        let mut indices = Vec::new();
+        let mut values = Vec::new();
+
+        indptr.push(0);
+        for row in matrix.rows() {
+            for (j, &w) in row.iter().enumerate() {
+                if w != 0.0 {
+                    indices.push(j);
+                    values.push(w);
+                }
+            }
+            indptr.push(indices.len());
+        }
+
+        SparseAdjacency {
+            num_nodes,
+            indptr,
+            indices,
+            values,
+        }
+    }
+
+    /// Number of stored (nonzero) entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the weight of entry `(i, j)`, or zero if it is not stored.
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        let range = self.indptr[i]..self.indptr[i + 1];
+        match self.indices[range.clone()].binary_search(&j) {
+            Ok(offset) => self.values[range.start + offset],
+            Err(_) => 0.0,
+        }
+    }
+
+    /// Iterates over the `(column, weight)` pairs stored in row `i`.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let range = self.indptr[i]..self.indptr[i + 1];
+        self.indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+
+    /// Computes the matrix-vector product `A x`.
+    pub fn matvec(&self, x: &Array1<f64>) -> Array1<f64> {
+        Array1::from_iter((0..self.num_nodes).map(|i| self.row(i).map(|(j, w)| w * x[j]).sum()))
+    }
+
+    /// Converts to a dense matrix.
+    pub fn to_dense(&self) -> Array2<f64> {
+        let mut dense = Array2::<f64>::zeros((self.num_nodes, self.num_nodes));
+        for i in 0..self.num_nodes {
+            for (j, w) in self.row(i) {
+                dense[[i, j]] = w;
+            }
+        }
+        dense
+    }
+
+    /// Writes the matrix to a binary file without node names. See `save_with_names`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.save_with_names(path, &[])
+    }
+
+    /// Writes the matrix and its node names to a compact binary file (little-endian, like the
+    /// `.gam` format), so a parsed graph can be cached between runs.
+    ///
+    /// Layout: magic `GCSR`, a version byte, the node, entry and name counts (u64 each), then
+    /// `indptr` and `indices` as u64, `values` as f64, and each name as a u32 byte length
+    /// followed by its UTF-8 bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `names` is neither empty nor one per node, and any I/O error.
+    pub fn save_with_names<P: AsRef<Path>>(&self, path: P, names: &[String]) -> io::Result<()> {
+        if !names.is_empty() && names.len() != self.num_nodes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Expected {} node names, got {}.", self.num_nodes, names.len()),
+            ));
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(SPARSE_MAGIC)?;
+        writer.write_all(&[SPARSE_VERSION])?;
+        for count in [self.num_nodes, self.nnz(), names.len()] {
+            writer.write_all(&(count as u64).to_le_bytes())?;
+        }
+        for &value in self.indptr.iter().chain(self.indices.iter()) {
+            writer.write_all(&(value as u64).to_le_bytes())?;
+        }
+        for value in &self.values {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        for name in names {
+            writer.write_all(&(name.len() as u32).to_le_bytes())?;
+            writer.write_all(name.as_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Reads a matrix written by `save` or `save_with_names`, discarding any node names.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::load_with_names(path).map(|(adjacency, _)| adjacency)
+    }
+
+    /// Reads a matrix and its node names (empty if none were saved).
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` for a file without the `GCSR` magic, an unknown version, counts or
+    /// name lengths that do not fit in the file (checked before anything is allocated), or CSR
+    /// arrays that are inconsistent (row pointers that decrease or do not end at the entry count,
+    /// column indices out of range or not ascending within a row), and any I/O error.
+    pub fn load_with_names<P: AsRef<Path>>(path: P) -> io::Result<(Self, Vec<String>)> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut header = [0u8; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != SPARSE_MAGIC {
+            return Err(invalid("Not a sparse adjacency file.".to_string()));
+        }
+        if header[4] != SPARSE_VERSION {
+            return Err(invalid(format!("Unsupported sparse adjacency version {}.", header[4])));
+        }
+
+        let mut word = [0u8; 8];
+        let mut read_u64 = |reader: &mut BufReader<File>| -> io::Result<u64> {
+            reader.read_exact(&mut word)?;
+            Ok(u64::from_le_bytes(word))
+        };
+        let num_nodes = read_u64(&mut reader)?;
+        let nnz = read_u64(&mut reader)?;
+        let num_names = read_u64(&mut reader)?;
+
+        // Header, the three arrays of 8-byte words, then at least a 4-byte length per name
+        let arrays_end = num_nodes
+            .checked_add(1)
+            .and_then(|words| words.checked_add(nnz.checked_mul(2)?))
+            .and_then(|words| words.checked_mul(8))
+            .and_then(|bytes| bytes.checked_add(29));
+        let min_len = arrays_end.and_then(|end| end.checked_add(num_names.checked_mul(4)?));
+        if min_len.is_none_or(|min_len| min_len > file_len) {
+            return Err(invalid(format!(
+                "Header counts ({} nodes, {} entries, {} names) do not fit in a file of {} bytes.",
+                num_nodes, nnz, num_names, file_len
+            )));
+        }
+        let mut remaining = file_len - arrays_end.unwrap_or(file_len);
+        let (num_nodes, nnz, num_names) = (num_nodes as usize, nnz as usize, num_names as usize);
+
+        let mut read_words = |reader: &mut BufReader<File>, len: usize| -> io::Result<Vec<u64>> {
+            (0..len).map(|_| read_u64(reader)).collect()
+        };
+        let indptr: Vec<usize> = read_words(&mut reader, num_nodes + 1)?.into_iter().map(|v| v as usize).collect();
+        let indices: Vec<usize> = read_words(&mut reader, nnz)?.into_iter().map(|v| v as usize).collect();
+        let values: Vec<f64> = read_words(&mut reader, nnz)?.into_iter().map(f64::from_bits).collect();
+
+        if indptr[0] != 0 || indptr[num_nodes] != nnz || indptr.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(invalid("Row pointers are inconsistent.".to_string()));
+        }
+        if let Some(&column) = indices.iter().find(|&&column| column >= num_nodes) {
+            return Err(invalid(format!("Column index {} is out of range for {} nodes.", column, num_nodes)));
+        }
+        let unsorted = |i: &usize| indices[indptr[*i]..indptr[*i + 1]].windows(2).any(|pair| pair[0] >= pair[1]);
+        if let Some(row) = (0..num_nodes).find(unsorted) {
+            return Err(invalid(format!("Column indices of row {} are not strictly ascending.", row)));
+        }
+        if num_names != 0 && num_names != num_nodes {
+            return Err(invalid(format!("Expected {} node names, found {}.", num_nodes, num_names)));
+        }
+
+        let mut names = Vec::with_capacity(num_names);
+        for _ in 0..num_names {
+            let mut length = [0u8; 4];
+            reader.read_exact(&mut length)?;
+            let length = u32::from_le_bytes(length) as u64;
+            remaining = remaining.saturating_sub(4);
+            if length > remaining {
+                return Err(invalid(format!("Name length {} runs past the end of the file.", length)));
+            }
+            remaining -= length;
+            let mut bytes = vec![0u8; length as usize];
+            reader.read_exact(&mut bytes)?;
+            names.push(String::from_utf8(bytes).map_err(|error| invalid(error.to_string()))?);
+        }
+
+        let adjacency = SparseAdjacency {
+            num_nodes,
+            indptr,
+            indices,
+            values,
+        };
+        Ok((adjacency, names))
+    }
+}
+
+/// How `knn_graph_weighted` and `threshold_graph_weighted` map the distance `d` of a linked pair to an edge weight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightKernel {
+    /// Every edge has weight 1.
+    Binary,
+    /// The heat kernel `exp(-d^2 / (2 sigma^2))`.
+    Gaussian { sigma: f64 },
+    /// The inverse distance `1 / d`. Coincident points (`d = 0`) get an infinite weight, so
+    /// deduplicate the input first.
+    Inverse,
+    /// The linear falloff `cutoff - d`, which `threshold_graph` uses with the threshold as cutoff.
+    /// Pairs at or beyond `cutoff` get a non-positive weight.
+    Linear { cutoff: f64 },
+}
+
+impl WeightKernel {
+    /// Returns the edge weight for a pair at distance `distance`.
+    pub fn weight(&self, distance: f64) -> f64 {
+        match *self {
+            WeightKernel::Binary => 1.0,
+            WeightKernel::Gaussian { sigma } => (-distance * distance / (2.0 * sigma * sigma)).exp(),
+            WeightKernel::Inverse => 1.0 / distance,
+            WeightKernel::Linear { cutoff } => cutoff - distance,
+        }
+    }
+}
+
+/// Builds the symmetric k-nearest-neighbor graph of a point cloud with binary weights.
+/// See `knn_graph_weighted`.
+pub fn knn_graph(points: &Array2<f64>, k: usize) -> Array2<f64> {
+    knn_graph_weighted(points, k, WeightKernel::Binary)
+}
+
+/// Builds the symmetric k-nearest-neighbor graph of a point cloud.
+///
+/// Each row of `points` is one node. Distances are Euclidean, a point is never its own neighbor,
+/// and ties are broken by the lower index. The graph is symmetrized by union: `i` and `j` are
+/// linked if either is among the other's `k` nearest neighbors, so every node has degree at least
+/// `min(k, n - 1)`. The result can be passed straight to the `laplacian` builders.
+pub fn knn_graph_weighted(points: &Array2<f64>, k: usize, kernel: WeightKernel) -> Array2<f64> {
+    let n = points.nrows();
+    let mut adjacency = Array2::<f64>::zeros((n, n));
+
+    for i in 0..n {
+        let mut distances: Vec<(f64, usize)> = (0..n)
+            .filter(|&j| j != i)
+            .map(|j| {
+                let diff = &points.row(i) - &points.row(j);
+                (diff.dot(&diff).sqrt(), j)
+            })
+            .collect();
+        distances.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        for &(distance, j) in distances.iter().take(k) {
+            let weight = kernel.weight(distance);
+            adjacency[[i, j]] = weight;
+            adjacency[[j, i]] = weight;
+        }
+    }
+
+    adjacency
+}
+
+/// Builds the symmetric adjacency of all pairs closer than `threshold` in a pairwise distance matrix.
+///
+/// Edges have weight 1, or `threshold - d` when `weighted` is set, so closer pairs are linked more
+/// strongly. See `threshold_graph_weighted` for other kernels.
+///
+/// # Panics
+///
+/// Panics if `distances` is not square.
+pub fn threshold_graph(distances: &Array2<f64>, threshold: f64, weighted: bool) -> Array2<f64> {
+    let kernel = if weighted {
+        WeightKernel::Linear { cutoff: threshold }
+    } else {
+        WeightKernel::Binary
+    };
+    threshold_graph_weighted(distances, threshold, kernel)
+}
+
+/// Builds the symmetric adjacency of all pairs closer than `threshold` in a pairwise distance matrix.
+///
+/// Nodes `i != j` are linked when `distances[[i, j]] < threshold`; the diagonal is ignored, so there
+/// are no self-loops. Only the upper triangle is read, so `distances` is assumed symmetric. Edge
+/// weights come from `kernel`; `WeightKernel::Binary` gives the unweighted graph.
+///
+/// # Panics
+///
+/// Panics if `distances` is not square.
+pub fn threshold_graph_weighted(distances: &Array2<f64>, threshold: f64, kernel: WeightKernel) -> Array2<f64> {
+    let n = distances.nrows();
+    assert_eq!(n, distances.ncols(), "Distance matrix must be square.");
+
+    let mut adjacency = Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let distance = distances[[i, j]];
+            if distance < threshold {
+                let weight = kernel.weight(distance);
+                adjacency[[i, j]] = weight;
+                adjacency[[j, i]] = weight;
+            }
+        }
+    }
+
+    adjacency
+}
+
+/// Coarsens a graph by merging heavy-edge matched node pairs, the first level of a multilevel method.
+///
+/// Nodes are visited in index order; each unmatched node is paired with the unmatched neighbor it
+/// shares the heaviest edge with (ties go to the lower index), and nodes without an unmatched
+/// neighbor stay on their own. Coarse weights sum the fine weights between the merged groups, and
+/// the edge inside a merged pair becomes a self-loop, so the total weight `sum(A)` is preserved.
+///
+/// # Returns
+///
+/// The coarse adjacency and, for every fine node, the index of the coarse node it was merged into.
+/// Coarse nodes are numbered in order of their lowest fine node. A graph with a perfect matching
+/// halves in size.
+///
+/// # Panics
+///
+/// Panics if `adjacency` is not square.
+pub fn coarsen(adjacency: &Array2<f64>) -> (Array2<f64>, Vec<usize>) {
+    let n = adjacency.nrows();
+    assert_eq!(n, adjacency.ncols(), "Adjacency matrix must be square.");
+
+    let mut mapping = vec![usize::MAX; n];
+    let mut num_coarse = 0;
+    for u in 0..n {
+        if mapping[u] != usize::MAX {
+            continue;
+        }
+        let partner = (0..n)
+            .filter(|&v| v != u && mapping[v] == usize::MAX && adjacency[[u, v]] > 0.0)
+            .fold(None, |best: Option<usize>, v| match best {
+                Some(b) if adjacency[[u, b]] >= adjacency[[u, v]] => Some(b),
+                _ => Some(v),
+            });
+
+        mapping[u] = num_coarse;
+        if let Some(v) = partner {
+            mapping[v] = num_coarse;
+        }
+        num_coarse += 1;
+    }
+
+    let mut coarse = Array2::<f64>::zeros((num_coarse, num_coarse));
+    for ((i, j), &w) in adjacency.indexed_iter() {
+        coarse[[mapping[i], mapping[j]]] += w;
+    }
+
+    (coarse, mapping)
+}
+
+/// Returns the nodes with no edge to any other node, in ascending order.
+///
+/// A self-loop does not connect a node to the rest of the graph, so it is ignored. Each isolated
+/// node adds a spurious zero eigenvalue to the Laplacian spectrum and a zero row to the normalized
+/// Laplacian.
+pub fn isolated_nodes(adjacency: &Array2<f64>) -> Vec<usize> {
+    adjacency
+        .rows()
+        .into_iter()
+        .enumerate()
+        .filter(|(i, row)| row.iter().enumerate().all(|(j, &w)| j == *i || w == 0.0))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Removes the `isolated_nodes` from an adjacency matrix.
+///
+/// # Returns
+///
+/// The reduced adjacency and, for each of its rows, the index of that node in the original matrix.
+pub fn drop_isolated(adjacency: &Array2<f64>) -> (Array2<f64>, Vec<usize>) {
+    let isolated = isolated_nodes(adjacency);
+    let kept: Vec<usize> = (0..adjacency.nrows()).filter(|i| isolated.binary_search(i).is_err()).collect();
+    let reduced = adjacency.select(Axis(0), &kept).select(Axis(1), &kept);
+    (reduced, kept)
+}
+
+/// Builds the line graph `L(G)`, whose nodes are the edges of `G`.
+///
+/// Every nonzero entry `(i, j)` with `i < j` is one edge of `G` (self-loops are skipped), and two
+/// edges are linked with weight 1 when they share an endpoint. The result can go straight into
+/// the `laplacian` builders for edge-centric spectral analysis.
+///
+/// # Returns
+///
+/// The line graph's adjacency and, for each of its nodes, the `(i, j)` edge of `G` it stands for,
+/// in lexicographic order.
+pub fn line_graph_adjacency(adjacency: &Array2<f64>) -> (Array2<f64>, Vec<(usize, usize)>) {
+    let n = adjacency.nrows();
+    let mut edges = Vec::new();
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if adjacency[[i, j]] != 0.0 {
+                edges.push((i, j));
+            }
+        }
+    }
+
+    let m = edges.len();
+    let mut line_graph = Array2::<f64>::zeros((m, m));
+    for a in 0..m {
+        for b in (a + 1)..m {
+            let ((u1, v1), (u2, v2)) = (edges[a], edges[b]);
+            if u1 == u2 || u1 == v2 || v1 == u2 || v1 == v2 {
+                line_graph[[a, b]] = 1.0;
+                line_graph[[b, a]] = 1.0;
+            }
+        }
+    }
+
+    (line_graph, edges)
+}
+
+/// Finds the connected components of a symmetric matrix's off-diagonal sparsity pattern.
+///
+/// Works on adjacency matrices and on any Laplacian built from them (self-loops and the diagonal
+/// are ignored). Components are listed in order of their lowest node, each with its nodes ascending.
+pub fn connected_components(matrix: &Array2<f64>) -> Vec<Vec<usize>> {
+    let n = matrix.nrows();
+    let mut visited = vec![false; n];
+    let mut components = Vec::new();
+
+    for start in 0..n {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut component = vec![start];
+        let mut stack = vec![start];
+        while let Some(u) = stack.pop() {
+            for v in 0..n {
+                if v != u && !visited[v] && matrix[[u, v]] != 0.0 {
+                    visited[v] = true;
+                    component.push(v);
+                    stack.push(v);
+                }
+            }
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+
+    components
+}
+
+/// Computes the degree assortativity: the Pearson correlation of the degrees at either end of an edge.
+///
+/// Degrees are the weighted row sums (`laplacian::degrees`), and every nonzero entry `(i, j)` with
+/// `i < j` counts once as an edge in both orientations (self-loops are skipped). Values near 1 mean
+/// hubs link to hubs, values near -1 that hubs link to leaves, as in a star.
+///
+/// # Returns
+///
+/// The assortativity coefficient in `[-1, 1]`, or NaN if the graph has no edges or every edge joins
+/// nodes of equal degree (e.g. a regular graph), where the correlation is undefined.
+pub fn degree_assortativity(adjacency: &Array2<f64>) -> f64 {
+    let degree = degrees(adjacency);
+    let n = adjacency.nrows();
+
+    let (mut edges, mut sum, mut sum_products, mut sum_squares) = (0usize, 0.0, 0.0, 0.0);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if adjacency[[i, j]] != 0.0 {
+                let (x, y) = (degree[i], degree[j]);
+                edges += 1;
+                sum += (x + y) / 2.0;
+                sum_products += x * y;
+                sum_squares += (x * x + y * y) / 2.0;
+            }
+        }
+    }
+
+    let m = edges as f64;
+    let mean = sum / m;
+    let variance = sum_squares / m - mean * mean;
+    if edges == 0 || variance.abs() <= f64::EPSILON * sum_squares / m {
+        return f64::NAN;
+    }
+    (sum_products / m - mean * mean) / variance
+}
+
+/// Bandwidths of an adjacency matrix before and after `reorder_for_bandwidth`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandwidthReport {
+    /// `max_band` of the matrix in its original node order.
+    pub original_band: usize,
+    /// `max_band` after reverse Cuthill–McKee reordering.
+    pub reordered_band: usize,
+}
+
+impl BandwidthReport {
+    /// Percentage by which reordering shrank the bandwidth (negative if it grew).
+    pub fn reduction_percent(&self) -> f64 {
+        if self.original_band == 0 {
+            return 0.0;
+        }
+        100.0 * (self.original_band as f64 - self.reordered_band as f64) / self.original_band as f64
+    }
+
+    /// Whether the reordered matrix has a strictly smaller bandwidth, i.e. reordering is worth applying.
+    pub fn improved(&self) -> bool {
+        self.reordered_band < self.original_band
+    }
+}
+
+/// Computes the reverse Cuthill–McKee ordering of a symmetric adjacency matrix.
+///
+/// Each connected component is traversed breadth-first from its lowest-degree node, visiting
+/// neighbours in order of increasing degree (ties by index), and the concatenated order is
+/// reversed. Nonzero entries then cluster near the diagonal, which shrinks the `kd` the banded
+/// solvers need.
+///
+/// # Returns
+///
+/// The permutation as `order[new] = old`, suitable for `Array2::select`.
+///
+/// # Panics
+///
+/// Panics if `adjacency` is not square.
+pub fn reverse_cuthill_mckee(adjacency: &Array2<f64>) -> Vec<usize> {
+    let n = adjacency.nrows();
+    assert_eq!(n, adjacency.ncols(), "Adjacency matrix must be square.");
+
+    let neighbours: Vec<Vec<usize>> = (0..n)
+        .map(|u| (0..n).filter(|&v| v != u && adjacency[[u, v]] != 0.0).collect())
+        .collect();
+    let degree = |u: usize| neighbours[u].len();
+
+    let mut by_degree: Vec<usize> = (0..n).collect();
+    by_degree.sort_by_key(|&u| (degree(u), u));
+
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    for &root in &by_degree {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let mut head = order.len();
+        order.push(root);
+        while head < order.len() {
+            let u = order[head];
+            head += 1;
+            let mut next: Vec<usize> = neighbours[u].iter().copied().filter(|&v| !visited[v]).collect();
+            next.sort_by_key(|&v| (degree(v), v));
+            for v in next {
+                visited[v] = true;
+                order.push(v);
+            }
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+/// Applies `reverse_cuthill_mckee` to `adjacency` and reports the bandwidth before and after.
+///
+/// # Returns
+///
+/// The permuted matrix, the permutation (`order[new] = old`), and the `BandwidthReport`. Check
+/// `BandwidthReport::improved` before using the permuted matrix: on graphs that are already
+/// banded, such as a path in its natural order, reordering cannot help.
+pub fn reorder_for_bandwidth(adjacency: &Array2<f64>) -> (Array2<f64>, Vec<usize>, BandwidthReport) {
+    let order = reverse_cuthill_mckee(adjacency);
+    let reordered = adjacency.select(Axis(0), &order).select(Axis(1), &order);
+    let report = BandwidthReport {
+        original_band: max_band(adjacency) as usize,
+        reordered_band: max_band(&reordered) as usize,
+    };
+    (reordered, order, report)
+}
+
+/// An adjacency matrix that stays on disk: every product streams the `.gam` edge list once.
+///
+/// The file is the binary edge list written by `convert_gfa_to_edge_list`: consecutive records of
+/// two little-endian u32 node IDs `(from, to)`. Every record adds 1 to `A[from][to]`, so an
+/// undirected graph must list both directions (as converted files do) and repeated records count
+/// repeatedly. Only `O(n)` memory is used (the degree vector and the vectors themselves), but each
+/// product costs a full sequential read of the file, so iterative methods are I/O bound; keep the
+/// file on local fast storage.
+#[derive(Debug, Clone)]
+pub struct StreamingAdjacency {
+    path: PathBuf,
+    num_nodes: usize,
+    degrees: Array1<f64>,
+}
+
+impl StreamingAdjacency {
+    /// Scans the file once to find the node count (largest ID plus one) and the degrees.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if the file ends in a partial record, and any I/O error from reading it.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut degrees: Vec<f64> = Vec::new();
+        for_each_record(&path, |from, to| {
+            let needed = from.max(to) + 1;
+            if needed > degrees.len() {
+                degrees.resize(needed, 0.0);
+            }
+            degrees[from] += 1.0;
+            Ok(())
+        })?;
+        let num_nodes = degrees.len();
+
+        Ok(StreamingAdjacency {
+            path,
+            num_nodes,
+            degrees: Array1::from(degrees),
+        })
+    }
+
+    /// Number of nodes.
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    /// Out-degree of every node (number of records starting there).
+    pub fn degrees(&self) -> &Array1<f64> {
+        &self.degrees
+    }
+
+    /// Computes `A x` with one pass over the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `x` does not have one entry per node, `InvalidData` if the file
+    /// now holds a partial record or a node ID past the count found by `open` (it changed since),
+    /// and any I/O error from reading it.
+    pub fn matvec(&self, x: &Array1<f64>) -> io::Result<Array1<f64>> {
+        if x.len() != self.num_nodes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Expected a vector of length {}, got {}.", self.num_nodes, x.len()),
+            ));
+        }
+
+        let mut y = Array1::<f64>::zeros(self.num_nodes);
+        let n = self.num_nodes;
+        for_each_record(&self.path, |from, to| {
+            if from >= n || to >= n {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Record ({}, {}) is past the {} nodes found when the file was opened.", from, to, n),
+                ));
+            }
+            y[from] += x[to];
+            Ok(())
+        })?;
+        Ok(y)
+    }
+
+    /// Computes the combinatorial Laplacian product `L x = D x - A x` with one pass over the file.
+    ///
+    /// # Errors
+    ///
+    /// The same as `matvec`.
+    pub fn laplacian_matvec(&self, x: &Array1<f64>) -> io::Result<Array1<f64>> {
+        let ax = self.matvec(x)?;
+        Ok(&self.degrees * x - ax)
+    }
+}
+
+/// Calls `f(from, to)` for every record of a binary edge list file, stopping at the first error
+/// from `f`.
+///
+/// The end of the file is only accepted at a record boundary: a trailing partial record is
+/// `InvalidData`, and every other read error is returned as is.
+fn for_each_record<F: FnMut(usize, usize) -> io::Result<()>>(path: &Path, mut f: F) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = [0u8; 8];
+    let mut record = 0usize;
+
+    loop {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        match filled {
+            0 => return Ok(()),
+            8 => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Record {} is truncated to {} of 8 bytes.", record, filled),
+                ))
+            }
+        }
+
+        let from = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+        let to = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]);
+        f(from as usize, to as usize)?;
+        record += 1;
+    }
+}
@@ -0,0 +1,398 @@
+// src/lanczos.rs
+
+//! Module for iterative (Lanczos) eigensolvers that only need matrix-vector products.
+
+use ndarray::prelude::*;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::chebyshev::splitmix64;
+use crate::eigen::{compute_eigenvalues_and_vectors_sym, EigenError};
+
+/// Magic bytes identifying a Lanczos checkpoint file.
+const CHECKPOINT_MAGIC: &[u8; 4] = b"LNCZ";
+/// Version of the checkpoint layout written by `LanczosState::save`.
+const CHECKPOINT_VERSION: u32 = 1;
+/// Seed of the random start block of `block_lanczos`.
+const BLOCK_LANCZOS_SEED: u64 = 0xB10C_C0DE_1A2C_2057;
+
+/// The resumable state of a Lanczos run.
+///
+/// Plain Lanczos only needs the tridiagonal coefficients and the last two Lanczos vectors to
+/// continue, so this is everything a checkpoint has to hold. Resuming from a saved state
+/// reproduces an uninterrupted run exactly, since the values are stored bit for bit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanczosState {
+    /// Diagonal of the tridiagonal matrix `T` (one entry per completed iteration).
+    pub alphas: Vec<f64>,
+    /// Off-diagonal of `T`; `betas[j]` couples Lanczos vectors `j` and `j + 1`.
+    pub betas: Vec<f64>,
+    /// The previous Lanczos vector `v_{j-1}` (zero before the first iteration).
+    v_prev: Array1<f64>,
+    /// The current Lanczos vector `v_j`.
+    v_curr: Array1<f64>,
+}
+
+impl LanczosState {
+    /// Starts a new Lanczos run from the given (not necessarily normalized) start vector.
+    pub fn new(start: &Array1<f64>) -> Result<Self, EigenError> {
+        let norm = start.dot(start).sqrt();
+        if start.is_empty() || norm == 0.0 || !norm.is_finite() {
+            return Err(EigenError::InvalidInput(
+                "Lanczos start vector must be non-empty, finite and nonzero.".to_string(),
+            ));
+        }
+
+        Ok(LanczosState {
+            alphas: Vec::new(),
+            betas: Vec::new(),
+            v_prev: Array1::zeros(start.len()),
+            v_curr: start / norm,
+        })
+    }
+
+    /// Dimension of the operator this run works on.
+    pub fn dim(&self) -> usize {
+        self.v_curr.len()
+    }
+
+    /// Number of completed Lanczos iterations.
+    pub fn iterations(&self) -> usize {
+        self.alphas.len()
+    }
+
+    /// Whether the Krylov space is exhausted (an invariant subspace was found), so no further step is possible.
+    pub fn is_exhausted(&self) -> bool {
+        self.betas.last() == Some(&0.0) || self.iterations() >= self.dim()
+    }
+
+    /// Performs one Lanczos iteration with the given matrix-vector product.
+    ///
+    /// Returns `false` without changing the state if the run is already exhausted.
+    pub fn step<F: Fn(&Array1<f64>) -> Array1<f64>>(&mut self, matvec: &F) -> bool {
+        self.try_step(&|x: &Array1<f64>| Ok(matvec(x)))
+            .expect("An infallible matrix-vector product cannot fail.")
+    }
+
+    /// Performs one Lanczos iteration with a fallible matrix-vector product, such as
+    /// `StreamingAdjacency::laplacian_matvec`, which reads the graph from disk.
+    ///
+    /// Returns `Ok(false)` without changing the state if the run is already exhausted. If the
+    /// product fails the error is returned and the state is left as it was.
+    pub fn try_step<F: Fn(&Array1<f64>) -> io::Result<Array1<f64>>>(&mut self, matvec: &F) -> io::Result<bool> {
+        if self.is_exhausted() {
+            return Ok(false);
+        }
+
+        // w = A v_j - beta_{j-1} v_{j-1}
+        let mut w = matvec(&self.v_curr)?;
+        if let Some(&beta_prev) = self.betas.last() {
+            w.scaled_add(-beta_prev, &self.v_prev);
+        }
+
+        let alpha = w.dot(&self.v_curr);
+        w.scaled_add(-alpha, &self.v_curr);
+        let beta = w.dot(&w).sqrt();
+
+        self.alphas.push(alpha);
+        self.betas.push(beta);
+
+        let v_next = if beta > 0.0 { w / beta } else { Array1::zeros(self.dim()) };
+        self.v_prev = std::mem::replace(&mut self.v_curr, v_next);
+
+        Ok(true)
+    }
+
+    /// Returns the Ritz values (eigenvalues of the current tridiagonal `T`) in ascending order.
+    pub fn ritz_values(&self) -> io::Result<Array1<f64>> {
+        let k = self.iterations();
+        let mut t = Array2::<f64>::zeros((k, k));
+        for i in 0..k {
+            t[[i, i]] = self.alphas[i];
+            if i + 1 < k {
+                t[[i, i + 1]] = self.betas[i];
+                t[[i + 1, i]] = self.betas[i];
+            }
+        }
+
+        let (eigvals, _) = compute_eigenvalues_and_vectors_sym(&t)?;
+        Ok(Array1::from(eigvals.as_slice().to_vec()))
+    }
+
+    /// Writes the state to a binary checkpoint file (little-endian, like the `.gam` format).
+    ///
+    /// Layout: magic `LNCZ`, version (u32), dimension (u64), iteration count (u64),
+    /// then the alphas, betas, previous vector and current vector as f64 values.
+    ///
+    /// The state is written to a temporary file in the same directory, synced to disk and then
+    /// renamed over `path`, so a job preempted mid-save still finds the previous checkpoint intact.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut writer = BufWriter::new(tempfile::NamedTempFile::new_in(directory)?);
+
+        writer.write_all(CHECKPOINT_MAGIC)?;
+        writer.write_all(&CHECKPOINT_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.dim() as u64).to_le_bytes())?;
+        writer.write_all(&(self.iterations() as u64).to_le_bytes())?;
+
+        let values = self
+            .alphas
+            .iter()
+            .chain(self.betas.iter())
+            .chain(self.v_prev.iter())
+            .chain(self.v_curr.iter());
+        for value in values {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        let file = writer.into_inner().map_err(|error| error.into_error())?;
+        file.as_file().sync_all()?;
+        file.persist(path).map_err(|error| error.error)?;
+        Ok(())
+    }
+
+    /// Restores a state previously written by `save`, ready to continue iterating.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` for a file without the `LNCZ` magic, an unknown version, or a header
+    /// whose counts do not match the file length (more iterations than the dimension, or a
+    /// truncated payload), checked before anything is allocated.
+    pub fn resume_from<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != CHECKPOINT_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Not a Lanczos checkpoint file.",
+            ));
+        }
+
+        let mut word = [0u8; 4];
+        reader.read_exact(&mut word)?;
+        let version = u32::from_le_bytes(word);
+        if version != CHECKPOINT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported Lanczos checkpoint version {}.", version),
+            ));
+        }
+
+        let n = read_u64(&mut reader)?;
+        let k = read_u64(&mut reader)?;
+
+        // Magic, version, n and k, then 2k coefficients and two n-vectors of f64
+        let expected_len = k
+            .checked_add(n)
+            .and_then(|count| count.checked_mul(16))
+            .and_then(|payload| payload.checked_add(24));
+        if k > n || expected_len != Some(file_len) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Lanczos checkpoint header (n = {}, iterations = {}) does not match the file length of {} bytes.",
+                    n, k, file_len
+                ),
+            ));
+        }
+        let (n, k) = (n as usize, k as usize);
+
+        let alphas = read_f64s(&mut reader, k)?;
+        let betas = read_f64s(&mut reader, k)?;
+        let v_prev = Array1::from(read_f64s(&mut reader, n)?);
+        let v_curr = Array1::from(read_f64s(&mut reader, n)?);
+
+        Ok(LanczosState {
+            alphas,
+            betas,
+            v_prev,
+            v_curr,
+        })
+    }
+}
+
+/// Runs Lanczos iterations until `max_iters` have completed (counting iterations done before a resume)
+/// or the Krylov space is exhausted, saving a checkpoint to `checkpoint_path` every `every` iterations.
+///
+/// A final checkpoint is always written when the loop ends, so an interrupted job can pick up with
+/// `LanczosState::resume_from`. Pass `every = 0` to only write the final checkpoint.
+pub fn lanczos_checkpointed<F, P>(
+    matvec: F,
+    state: &mut LanczosState,
+    max_iters: usize,
+    checkpoint_path: P,
+    every: usize,
+) -> io::Result<()>
+where
+    F: Fn(&Array1<f64>) -> Array1<f64>,
+    P: AsRef<Path>,
+{
+    while state.iterations() < max_iters && state.step(&matvec) {
+        if every > 0 && state.iterations().is_multiple_of(every) {
+            state.save(&checkpoint_path)?;
+        }
+    }
+
+    state.save(&checkpoint_path)
+}
+
+/// Runs up to `max_iters` Lanczos iterations on an `n`-dimensional operator and records the Ritz
+/// values after every iteration, to judge whether `max_iters` was enough for convergence.
+///
+/// The run starts from a fixed, non-symmetric vector (`1 + 0.37 i`), so repeated calls give the
+/// same history. Computing the history costs a dense `k x k` eigendecomposition at every step `k`,
+/// so this is meant as a diagnostic rather than for production runs.
+///
+/// # Returns
+///
+/// The final Ritz values in ascending order and, for each completed iteration `k`, the `k` Ritz
+/// values after it. The history is shorter than `max_iters` if the Krylov space was exhausted.
+pub fn lanczos_with_history<F: Fn(&Array1<f64>) -> Array1<f64>>(
+    matvec: F,
+    n: usize,
+    max_iters: usize,
+) -> io::Result<(Array1<f64>, Vec<Array1<f64>>)> {
+    let start = Array1::from_iter((0..n).map(|i| 1.0 + i as f64 * 0.37));
+    let mut state = LanczosState::new(&start)?;
+
+    let mut history = Vec::with_capacity(max_iters.min(n));
+    while state.iterations() < max_iters && state.step(&matvec) {
+        history.push(state.ritz_values()?);
+    }
+
+    let final_values = history.last().cloned().unwrap_or_else(|| Array1::zeros(0));
+    Ok((final_values, history))
+}
+
+/// Computes the `k` smallest eigenpairs of a symmetric `n x n` operator with block Lanczos, given
+/// only its product with a block of vectors (e.g. `banded_matmat`).
+///
+/// Each of the `iters` steps multiplies a block of `block` orthonormal vectors and extends the
+/// basis with the part of the product orthogonal to everything found so far (full
+/// reorthogonalization, two passes). Columns that vanish are dropped, and the run stops early once
+/// nothing new is left, i.e. an invariant subspace was found. The Ritz pairs of the projected
+/// matrix `V^T A V` are the result. Because every step explores `block` directions at once, a
+/// `g`-fold degenerate eigenvalue is resolved with all `g` vectors as long as `block >= g`, where
+/// single-vector Lanczos only ever sees one vector of the eigenspace.
+///
+/// The start block is drawn from a fixed seed, so repeated calls give the same result.
+///
+/// # Arguments
+///
+/// * `matmat` - Computes `A X` for an `n x b` block `X` (`b <= block`).
+/// * `n` - Dimension of the operator.
+/// * `block` - Number of vectors per block; at least the multiplicity of the wanted eigenvalues.
+/// * `iters` - Number of block products; the basis has at most `block * iters` vectors.
+/// * `k` - Number of eigenpairs to return.
+///
+/// # Returns
+///
+/// The `min(k, basis size)` smallest Ritz values in ascending order and the matching Ritz
+/// vectors as columns of an `n x k` matrix.
+///
+/// # Panics
+///
+/// Panics if `block` or `iters` is zero, `block > n`, or `matmat` returns a block of the wrong
+/// shape or with non-finite entries.
+pub fn block_lanczos(
+    matmat: impl Fn(&Array2<f64>) -> Array2<f64>,
+    n: usize,
+    block: usize,
+    iters: usize,
+    k: usize,
+) -> (Array1<f64>, Array2<f64>) {
+    assert!(block > 0 && iters > 0, "block and iters must be positive.");
+    assert!(block <= n, "The block size cannot exceed the dimension.");
+
+    let mut state = BLOCK_LANCZOS_SEED;
+    let start = Array2::from_shape_fn((n, block), |_| {
+        (splitmix64(&mut state) >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    });
+
+    // Orthonormal basis V and its image A V, one column per basis vector
+    let mut basis: Vec<Array1<f64>> = Vec::with_capacity(block * iters);
+    let mut image: Vec<Array1<f64>> = Vec::with_capacity(block * iters);
+    let mut current = orthonormalize_against(&basis, start);
+
+    for _ in 0..iters {
+        if current.is_empty() {
+            break;
+        }
+        let block_matrix = Array2::from_shape_fn((n, current.len()), |(i, j)| current[j][i]);
+        let product = matmat(&block_matrix);
+        assert_eq!(product.dim(), block_matrix.dim(), "matmat must return an n x b block.");
+
+        basis.append(&mut current);
+        image.extend(product.columns().into_iter().map(|column| column.to_owned()));
+        current = orthonormalize_against(&basis, product);
+    }
+
+    // Rayleigh-Ritz on T = V^T A V, symmetrized against rounding
+    let m = basis.len();
+    let mut projected = Array2::<f64>::zeros((m, m));
+    for i in 0..m {
+        for j in 0..m {
+            projected[[i, j]] = 0.5 * (basis[i].dot(&image[j]) + basis[j].dot(&image[i]));
+        }
+    }
+    let (ritz_values, ritz_vectors) =
+        compute_eigenvalues_and_vectors_sym(&projected).expect("The projected matrix must be finite.");
+
+    let count = k.min(m);
+    let values = Array1::from_iter(ritz_values.iter().take(count).copied());
+    let mut vectors = Array2::<f64>::zeros((n, count));
+    for c in 0..count {
+        let mut column = vectors.column_mut(c);
+        for (j, v) in basis.iter().enumerate() {
+            column.scaled_add(ritz_vectors[(j, c)], v);
+        }
+    }
+    (values, vectors)
+}
+
+/// Orthonormalizes the columns of `block` against `basis` and each other with two passes of
+/// modified Gram-Schmidt, dropping columns that are (numerically) in the span already.
+fn orthonormalize_against(basis: &[Array1<f64>], block: Array2<f64>) -> Vec<Array1<f64>> {
+    let mut accepted: Vec<Array1<f64>> = Vec::with_capacity(block.ncols());
+    for column in block.columns() {
+        let mut v = column.to_owned();
+        let original_norm = v.dot(&v).sqrt();
+        assert!(original_norm.is_finite(), "matmat returned non-finite values.");
+        for _ in 0..2 {
+            for q in basis.iter().chain(accepted.iter()) {
+                let coefficient = q.dot(&v);
+                v.scaled_add(-coefficient, q);
+            }
+        }
+        let norm = v.dot(&v).sqrt();
+        if norm > 1e-10 * original_norm && norm > 0.0 {
+            accepted.push(v / norm);
+        }
+    }
+    accepted
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+fn read_f64s<R: Read>(reader: &mut R, count: usize) -> io::Result<Vec<f64>> {
+    let mut values = Vec::with_capacity(count);
+    let mut buffer = [0u8; 8];
+    for _ in 0..count {
+        reader.read_exact(&mut buffer)?;
+        values.push(f64::from_le_bytes(buffer));
+    }
+    Ok(values)
+}
@@ -0,0 +1,351 @@
+// src/laplacian.rs
+
+//! Module for building the Laplacian variants of an adjacency matrix.
+
+use ndarray::prelude::*;
+use std::fmt;
+use std::io;
+
+use crate::eigen::{call_eigendecomp, compute_ngec, DEFAULT_SPECTRUM_FLOOR};
+use crate::graph::{connected_components, SparseAdjacency};
+
+/// The supported Laplacian variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaplacianKind {
+    /// `L = D - A`
+    Combinatorial,
+    /// `L = I - D^{-1/2} A D^{-1/2}`
+    Normalized,
+    /// `L = I - D^{-1} A` (not symmetric)
+    RandomWalk,
+    /// `Q = D + A`
+    Signless,
+}
+
+impl LaplacianKind {
+    /// Whether this variant is symmetric and can therefore be passed to the symmetric eigensolvers.
+    pub fn is_symmetric(self) -> bool {
+        !matches!(self, LaplacianKind::RandomWalk)
+    }
+}
+
+impl fmt::Display for LaplacianKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LaplacianKind::Combinatorial => "combinatorial",
+            LaplacianKind::Normalized => "normalized",
+            LaplacianKind::RandomWalk => "random-walk",
+            LaplacianKind::Signless => "signless",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Computes the weighted degree (row sum) of every node.
+pub fn degrees(adjacency: &Array2<f64>) -> Array1<f64> {
+    adjacency.sum_axis(Axis(1))
+}
+
+/// Builds the diagonal degree matrix `D`, with the weighted degrees on the diagonal.
+pub fn degree_matrix(adjacency: &Array2<f64>) -> Array2<f64> {
+    Array2::<f64>::from_diag(&degrees(adjacency))
+}
+
+/// Builds `D^{-1/2}`, the diagonal matrix of inverse square-rooted degrees.
+///
+/// Isolated nodes get a zero on the diagonal rather than infinity, matching the convention of
+/// `normalized_laplacian`, so `D^{-1/2} A D^{-1/2}` stays finite.
+pub fn degree_matrix_inv_sqrt(adjacency: &Array2<f64>) -> Array2<f64> {
+    let inv_sqrt = degrees(adjacency).mapv(|d| if d > 0.0 { 1.0 / d.sqrt() } else { 0.0 });
+    Array2::<f64>::from_diag(&inv_sqrt)
+}
+
+/// Builds the combinatorial Laplacian `L = D - A`.
+pub fn combinatorial_laplacian(adjacency: &Array2<f64>) -> Array2<f64> {
+    &degree_matrix(adjacency) - adjacency
+}
+
+/// Builds the symmetric normalized Laplacian `L = I - D^{-1/2} A D^{-1/2}`.
+///
+/// Isolated nodes (degree zero) get an all-zero row and column, following Chung's convention,
+/// so each connected component still contributes exactly one zero eigenvalue.
+pub fn normalized_laplacian(adjacency: &Array2<f64>) -> Array2<f64> {
+    let degrees = degrees(adjacency);
+    let inv_sqrt = degrees.mapv(|d| if d > 0.0 { 1.0 / d.sqrt() } else { 0.0 });
+
+    let mut laplacian = Array2::<f64>::zeros(adjacency.dim());
+    for ((i, j), value) in laplacian.indexed_iter_mut() {
+        let identity = if i == j && degrees[i] > 0.0 { 1.0 } else { 0.0 };
+        *value = identity - inv_sqrt[i] * adjacency[[i, j]] * inv_sqrt[j];
+    }
+    laplacian
+}
+
+/// Builds the random-walk Laplacian `L = I - D^{-1} A`.
+///
+/// This matrix is not symmetric, but it is similar to the normalized Laplacian and shares its
+/// eigenvalues. Isolated nodes get an all-zero row, as in `normalized_laplacian`.
+pub fn random_walk_laplacian(adjacency: &Array2<f64>) -> Array2<f64> {
+    let degrees = degrees(adjacency);
+
+    let mut laplacian = Array2::<f64>::zeros(adjacency.dim());
+    for ((i, j), value) in laplacian.indexed_iter_mut() {
+        if degrees[i] > 0.0 {
+            let identity = if i == j { 1.0 } else { 0.0 };
+            *value = identity - adjacency[[i, j]] / degrees[i];
+        }
+    }
+    laplacian
+}
+
+/// Builds the signless Laplacian `Q = D + A`.
+pub fn signless_laplacian(adjacency: &Array2<f64>) -> Array2<f64> {
+    &degree_matrix(adjacency) + adjacency
+}
+
+/// Builds the signed Laplacian `L = D̄ - A` for graphs with negative edge weights, where
+/// `D̄_ii = sum_j |A_ij|`.
+///
+/// With only nonnegative weights this equals `combinatorial_laplacian`. With negative weights the
+/// combinatorial Laplacian (which sums the signed weights) can have negative eigenvalues, while this
+/// one stays positive semidefinite: `x^T L x = sum_{i<j} |A_ij| (x_i - sgn(A_ij) x_j)^2`. It has a
+/// zero eigenvalue per component only if that component is balanced (its nodes split into two
+/// camps with positive edges inside and negative edges across).
+pub fn signed_laplacian(adjacency: &Array2<f64>) -> Array2<f64> {
+    let absolute_degrees = adjacency.mapv(f64::abs).sum_axis(Axis(1));
+    &Array2::<f64>::from_diag(&absolute_degrees) - adjacency
+}
+
+/// Builds the combinatorial Laplacian of a bipartite graph from its `m x n` biadjacency `B`
+/// (rows are one side, e.g. reads, and columns the other, e.g. segments).
+///
+/// The full adjacency is the `(m + n) x (m + n)` block matrix `[[0, B], [B^T, 0]]`, so the first
+/// `m` nodes are the rows of `B` and the last `n` its columns. The result is symmetric and has the
+/// row-side degrees `B 1` and column-side degrees `B^T 1` on its diagonal.
+pub fn bipartite_laplacian(biadjacency: &Array2<f64>) -> Array2<f64> {
+    let (m, n) = biadjacency.dim();
+    let mut adjacency = Array2::<f64>::zeros((m + n, m + n));
+    adjacency.slice_mut(s![..m, m..]).assign(biadjacency);
+    adjacency.slice_mut(s![m.., ..m]).assign(&biadjacency.t());
+    combinatorial_laplacian(&adjacency)
+}
+
+/// Tests whether a normalized Laplacian spectrum is symmetric about 1, i.e. whether every
+/// eigenvalue `λ` is matched by `2 - λ` to within `tol`.
+///
+/// A graph is bipartite exactly when the normalized spectrum of each of its components has this
+/// symmetry, so it serves as a spectral-only bipartiteness heuristic. Isolated nodes contribute a
+/// zero eigenvalue without a partner at 2 (see `normalized_laplacian`), so drop them first. A
+/// disconnected graph mixing bipartite and non-bipartite components may also fail the check.
+///
+/// # Arguments
+///
+/// * `eigenvalues` - The eigenvalues of `normalized_laplacian`, in any order.
+/// * `tol` - The largest accepted `|λ_i + λ_{n-1-i} - 2|` after sorting.
+pub fn check_bipartite_spectral_symmetry(eigenvalues: &Array1<f64>, tol: f64) -> bool {
+    let mut sorted = eigenvalues.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    (0..n / 2 + n % 2).all(|i| (sorted[i] + sorted[n - 1 - i] - 2.0).abs() <= tol)
+}
+
+/// Builds Newman's modularity matrix `B = A - k k^T / (2m)`, where `k` are the weighted degrees
+/// and `2m = sum(k)`.
+///
+/// Unlike the Laplacians, `B` is dense: the rank-one correction fills in every entry, so a banded
+/// ordering of `A` does not carry over and `call_eigendecomp` takes the dsyevd path
+/// (`SolverPolicy::Dense`). Every row of `B` sums to zero, and its leading eigenvector gives the
+/// Newman spectral split. A graph without edges gives the zero matrix.
+pub fn modularity_matrix(adjacency: &Array2<f64>) -> Array2<f64> {
+    let degrees = degrees(adjacency);
+    let total = degrees.sum();
+    if total == 0.0 {
+        return Array2::<f64>::zeros(adjacency.dim());
+    }
+
+    let mut modularity = adjacency.clone();
+    for ((i, j), value) in modularity.indexed_iter_mut() {
+        *value -= degrees[i] * degrees[j] / total;
+    }
+    modularity
+}
+
+/// Splits a graph into two communities by the sign of the leading eigenvector of its
+/// `modularity_matrix` (Newman's spectral bisection).
+///
+/// A split is only made if it increases modularity, i.e. if the leading eigenvalue of `B` is
+/// positive (above `DEFAULT_SPECTRUM_FLOOR`); otherwise the graph is indivisible and every node
+/// stays in one community. The eigenvector's sign is fixed so that node 0 is always `true`, and
+/// nodes with a zero component join node 0's side.
+///
+/// # Returns
+///
+/// For every node, which of the two communities it belongs to.
+pub fn newman_split(adjacency: &Array2<f64>) -> io::Result<Vec<bool>> {
+    let n = adjacency.nrows();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+
+    let (eigvals, eigvecs) = call_eigendecomp(&modularity_matrix(adjacency))?;
+    if eigvals[n - 1] <= DEFAULT_SPECTRUM_FLOOR {
+        return Ok(vec![true; n]);
+    }
+
+    let leading = eigvecs.column(n - 1);
+    let sign = if leading[0] < 0.0 { -1.0 } else { 1.0 };
+    Ok(leading.iter().map(|&u| sign * u >= 0.0).collect())
+}
+
+/// Builds the Laplacian variant selected by `kind`.
+pub fn build_laplacian(adjacency: &Array2<f64>, kind: LaplacianKind) -> Array2<f64> {
+    match kind {
+        LaplacianKind::Combinatorial => combinatorial_laplacian(adjacency),
+        LaplacianKind::Normalized => normalized_laplacian(adjacency),
+        LaplacianKind::RandomWalk => random_walk_laplacian(adjacency),
+        LaplacianKind::Signless => signless_laplacian(adjacency),
+    }
+}
+
+/// The combinatorial Laplacian `L = D - A` as a matrix-free operator over a sparse adjacency matrix,
+/// for methods that only need products `L x` on graphs too large to store densely.
+#[derive(Debug, Clone)]
+pub struct LaplacianOperator {
+    adjacency: SparseAdjacency,
+    degrees: Array1<f64>,
+}
+
+impl LaplacianOperator {
+    /// Wraps a sparse symmetric adjacency matrix.
+    pub fn new(adjacency: SparseAdjacency) -> Self {
+        let degrees = Array1::from_iter((0..adjacency.num_nodes).map(|i| adjacency.row(i).map(|(_, w)| w).sum()));
+        LaplacianOperator { adjacency, degrees }
+    }
+
+    /// Builds the operator from a dense adjacency matrix.
+    pub fn from_dense(adjacency: &Array2<f64>) -> Self {
+        Self::new(SparseAdjacency::from_dense(adjacency))
+    }
+
+    /// Number of nodes.
+    pub fn dim(&self) -> usize {
+        self.adjacency.num_nodes
+    }
+
+    /// Weighted degree of every node.
+    pub fn degrees(&self) -> &Array1<f64> {
+        &self.degrees
+    }
+
+    /// Gershgorin bound `2 * max degree` on the largest eigenvalue.
+    pub fn lambda_max_bound(&self) -> f64 {
+        2.0 * self.degrees.iter().fold(0.0_f64, |acc, &d| acc.max(d))
+    }
+
+    /// Computes `L x = D x - A x`.
+    pub fn apply(&self, x: &Array1<f64>) -> Array1<f64> {
+        &self.degrees * x - self.adjacency.matvec(x)
+    }
+}
+
+/// NGEC of one graph under each symmetric Laplacian variant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NgecComparison {
+    pub combinatorial: f64,
+    pub normalized: f64,
+    pub signless: f64,
+}
+
+/// Builds the combinatorial, normalized and signless Laplacians of `adjacency`, decomposes each,
+/// and returns their NGEC values together. The random-walk variant shares the normalized
+/// Laplacian's spectrum, so it would repeat `normalized`.
+pub fn ngec_across_normalizations(adjacency: &Array2<f64>) -> io::Result<NgecComparison> {
+    let ngec_of = |kind: LaplacianKind| -> io::Result<f64> {
+        let (eigvals, _) = call_eigendecomp(&build_laplacian(adjacency, kind))?;
+        compute_ngec(&eigvals)
+    };
+
+    Ok(NgecComparison {
+        combinatorial: ngec_of(LaplacianKind::Combinatorial)?,
+        normalized: ngec_of(LaplacianKind::Normalized)?,
+        signless: ngec_of(LaplacianKind::Signless)?,
+    })
+}
+
+/// Computes the NGEC of every connected component separately, instead of one value that mixes the
+/// spectra of unrelated components.
+///
+/// Components come in the order of `graph::connected_components`; each one's combinatorial
+/// Laplacian is decomposed on its own and scored with `compute_ngec`. An isolated node, whose NGEC
+/// is undefined, gets `NaN`.
+///
+/// # Returns
+///
+/// The `(size, ngec)` pair of every component.
+///
+/// # Errors
+///
+/// Returns any decomposition or NGEC error of a component with two or more nodes.
+pub fn ngec_per_component(adjacency: &Array2<f64>) -> io::Result<Vec<(usize, f64)>> {
+    connected_components(adjacency)
+        .into_iter()
+        .map(|nodes| {
+            if nodes.len() == 1 {
+                return Ok((1, f64::NAN));
+            }
+            let submatrix = adjacency.select(Axis(0), &nodes).select(Axis(1), &nodes);
+            let (eigvals, _) = call_eigendecomp(&combinatorial_laplacian(&submatrix))?;
+            Ok((nodes.len(), compute_ngec(&eigvals)?))
+        })
+        .collect()
+}
+
+/// Computes the combinatorial and normalized Laplacian eigenvalues of `adjacency` together.
+///
+/// The degrees are computed once and the normalized Laplacian is derived from the combinatorial
+/// one as `D^{-1/2} L D^{-1/2}` (which also gives isolated nodes their zero row, as in
+/// `normalized_laplacian`). Both matrices have the sparsity pattern of `A` and hence the same
+/// bandwidth, so they take the same solver path; the two decompositions themselves cannot share work.
+///
+/// # Returns
+///
+/// The combinatorial and normalized eigenvalues, each in ascending order.
+pub fn dual_spectra(adjacency: &Array2<f64>) -> io::Result<(Array1<f64>, Array1<f64>)> {
+    let degrees = degrees(adjacency);
+    let combinatorial = &Array2::<f64>::from_diag(&degrees) - adjacency;
+
+    let inv_sqrt = degrees.mapv(|d| if d > 0.0 { 1.0 / d.sqrt() } else { 0.0 });
+    let mut normalized = combinatorial.clone();
+    for ((i, j), value) in normalized.indexed_iter_mut() {
+        *value *= inv_sqrt[i] * inv_sqrt[j];
+    }
+
+    let (combinatorial_vals, _) = call_eigendecomp(&combinatorial)?;
+    let (normalized_vals, _) = call_eigendecomp(&normalized)?;
+    Ok((combinatorial_vals, normalized_vals))
+}
+
+/// Closed-form combinatorial Laplacian eigenvalues of the path graph `P_n`, in ascending order:
+/// `2 - 2 cos(π k / n)` for `k = 0..n`.
+///
+/// Together with `cycle_graph_spectrum` and `complete_graph_spectrum` this is an exact oracle for
+/// checking the numerical solvers at sizes where hardcoded fixtures are impractical.
+pub fn path_graph_spectrum(n: usize) -> Array1<f64> {
+    Array1::from_iter((0..n).map(|k| 2.0 - 2.0 * (std::f64::consts::PI * k as f64 / n as f64).cos()))
+}
+
+/// Closed-form combinatorial Laplacian eigenvalues of the cycle graph `C_n` (`n >= 3`), in
+/// ascending order: `2 - 2 cos(2π k / n)` for `k = 0..n`, each nonzero value except `4` appearing twice.
+pub fn cycle_graph_spectrum(n: usize) -> Array1<f64> {
+    let mut values: Vec<f64> = (0..n)
+        .map(|k| 2.0 - 2.0 * (2.0 * std::f64::consts::PI * k as f64 / n as f64).cos())
+        .collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Array1::from(values)
+}
+
+/// Closed-form combinatorial Laplacian eigenvalues of the complete graph `K_n`, in ascending
+/// order: `0` once and `n` with multiplicity `n - 1`.
+pub fn complete_graph_spectrum(n: usize) -> Array1<f64> {
+    Array1::from_iter((0..n).map(|k| if k == 0 { 0.0 } else { n as f64 }))
+}
@@ -1,3 +1,13 @@
+pub mod backend;
+pub mod chebyshev;
 pub mod convert;
 pub mod extract;
+pub mod graph;
 pub mod eigen;
+#[cfg(feature = "f128")]
+pub mod extended;
+pub mod laplacian;
+pub mod lanczos;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+mod trace;
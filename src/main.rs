@@ -1,8 +1,12 @@
 // src/main.rs
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use indicatif::{ProgressBar, ProgressStyle};
 use std::io;
+use std::time::Duration;
 
+use graphome::eigen::{EmbeddingScaling, OutputFormat, DEFAULT_SPECTRUM_FLOOR};
+use graphome::laplacian::LaplacianKind;
 use graphome::{convert, extract};
 
 /// Graphome: GFA to Adjacency Matrix Converter and Analyzer
@@ -46,9 +50,289 @@ enum Commands {
         /// Output .gam file
         #[arg(short, long)]
         output: String,
+
+        /// Laplacian variant to decompose
+        #[arg(long, value_enum, default_value_t = LaplacianArg::Combinatorial)]
+        laplacian: LaplacianArg,
+
+        /// Eigenvalues with |λ| at or below this floor, times max(1, |λ_max|), count as zero in the NGEC
+        #[arg(long, default_value_t = DEFAULT_SPECTRUM_FLOOR)]
+        clamp_floor: f64,
+
+        /// Format of the Laplacian, eigenvector and eigenvalue files
+        #[arg(long, value_enum, default_value_t = FormatArg::Csv)]
+        format: FormatArg,
+
+        /// Fail before saving or decomposing if the Laplacian's bandwidth exceeds N
+        #[arg(long, value_name = "N")]
+        max_band: Option<usize>,
+
+        /// Hide the progress bar, status lines and heatmaps, printing only the NGEC (for scripting)
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Rank GFA segments by their Fiedler-vector component
+    FiedlerRank {
+        /// Path to the input GFA file
+        #[arg(long)]
+        gfa: String,
+
+        /// Path to the output TSV file
+        #[arg(long, default_value = "fiedler_ranking.tsv")]
+        out: String,
+
+        /// Output format (csv writes a labeled TSV; npy and mtx cannot hold the labels)
+        #[arg(long, value_enum, default_value_t = FormatArg::Csv)]
+        format: FormatArg,
+
+        /// Laplacian variant to decompose
+        #[arg(long, value_enum, default_value_t = LaplacianArg::Combinatorial)]
+        laplacian: LaplacianArg,
+        /// Hide the progress bar and status lines
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Compute pairwise spectral distances between GFA files
+    SpectralDist {
+        /// Pattern selecting the input GFA files, e.g. "samples/*.gfa"
+        #[arg(long)]
+        glob: String,
+
+        /// Path to the output TSV file
+        #[arg(long, default_value = "spectral_distances.tsv")]
+        out: String,
+
+        /// Output format (csv writes a labeled TSV; npy and mtx cannot hold the labels)
+        #[arg(long, value_enum, default_value_t = FormatArg::Csv)]
+        format: FormatArg,
+
+        /// Laplacian variant to decompose
+        #[arg(long, value_enum, default_value_t = LaplacianArg::Combinatorial)]
+        laplacian: LaplacianArg,
+        /// Hide the progress bar and status lines
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Compare a sample GFA against a reference and print their spectral differences
+    Compare {
+        /// Path to the reference GFA file
+        #[arg(long)]
+        a: String,
+
+        /// Path to the sample GFA file
+        #[arg(long)]
+        b: String,
+
+        /// Laplacian variant to decompose
+        #[arg(long, value_enum, default_value_t = LaplacianArg::Combinatorial)]
+        laplacian: LaplacianArg,
+
+        /// Eigenvalues with |λ| at or below this floor, times max(1, |λ_max|), count as zero in the NGEC
+        #[arg(long, default_value_t = DEFAULT_SPECTRUM_FLOOR)]
+        clamp_floor: f64,
+        /// Hide the progress bar and status lines, printing only the report
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Compute a range of eigenpairs from a cached banded matrix (.npy)
+    Eigen {
+        /// Path to the banded matrix saved with `save_array_to_npy`
+        #[arg(long)]
+        banded: String,
+
+        /// Index of the first eigenpair (1-based, ascending)
+        #[arg(long)]
+        il: usize,
+
+        /// Index of the last eigenpair (inclusive)
+        #[arg(long)]
+        iu: usize,
+
+        /// Output file for the eigenvalues; eigenvectors go next to it as .eigenvectors.<ext>
+        #[arg(long, default_value = "eigenvalues.csv")]
+        out: String,
+
+        /// Format of the eigenvalue and eigenvector files
+        #[arg(long, value_enum, default_value_t = FormatArg::Csv)]
+        format: FormatArg,
+    },
+
+    /// Stream a CSV adjacency into its banded Laplacian (.npy) without a dense matrix
+    Band {
+        /// Path to the square CSV adjacency matrix (no header)
+        #[arg(long)]
+        csv: String,
+
+        /// Bandwidth to store, or "auto" to compute max_band in a first pass
+        #[arg(long, default_value = "auto")]
+        kd: String,
+
+        /// Try a reverse Cuthill-McKee reordering first and report the bandwidth before and after
+        #[arg(long)]
+        reorder: bool,
+
+        /// Format of the banded Laplacian; only npy can be read back by `eigen`
+        #[arg(long, value_enum, default_value_t = FormatArg::Npy)]
+        format: FormatArg,
+
+        /// Fail before allocating the band if its bandwidth exceeds N
+        #[arg(long, value_name = "N")]
+        max_band: Option<usize>,
+
+        /// Laplacian variant to band
+        #[arg(long, value_enum, default_value_t = LaplacianArg::Combinatorial)]
+        laplacian: LaplacianArg,
+
+        /// Path to the output .npy file
+        #[arg(long, default_value = "lap.banded.npy")]
+        out: String,
+    },
+
+    /// Write a spectral embedding of every GFA segment as a labeled TSV
+    Embed {
+        /// Path to the GFA file
+        #[arg(long)]
+        gfa: String,
+
+        /// Number of embedding dimensions
+        #[arg(long, default_value_t = 2)]
+        dims: usize,
+
+        /// Scaling of the eigenvector coordinates
+        #[arg(long, value_enum, default_value_t = ScalingArg::None)]
+        scaling: ScalingArg,
+
+        /// Path to the output TSV file
+        #[arg(long, default_value = "embed.tsv")]
+        out: String,
+
+        /// Output format (csv writes a labeled TSV; npy and mtx cannot hold the labels)
+        #[arg(long, value_enum, default_value_t = FormatArg::Csv)]
+        format: FormatArg,
+
+        /// Laplacian variant to decompose
+        #[arg(long, value_enum, default_value_t = LaplacianArg::Combinatorial)]
+        laplacian: LaplacianArg,
+        /// Hide the progress bar and status lines
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Write the NGEC of sliding node windows as a bedGraph track
+    NgecTrack {
+        /// Path to the GFA file (rGFA SN/SO tags give reference coordinates)
+        #[arg(long)]
+        gfa: String,
+
+        /// Number of nodes per window
+        #[arg(long)]
+        window: usize,
+
+        /// Number of nodes between consecutive window starts
+        #[arg(long)]
+        step: usize,
+
+        /// Path to the output bedGraph file
+        #[arg(long, default_value = "track.bedgraph")]
+        out: String,
+
+        /// Laplacian variant to decompose
+        #[arg(long, value_enum, default_value_t = LaplacianArg::Combinatorial)]
+        laplacian: LaplacianArg,
+
+        /// Eigenvalues with |λ| at or below this floor, times max(1, |λ_max|), count as zero in the NGEC
+        #[arg(long, default_value_t = DEFAULT_SPECTRUM_FLOOR)]
+        clamp_floor: f64,
+        /// Hide the progress bar and status lines
+        #[arg(short, long)]
+        quiet: bool,
     },
 }
 
+/// The `--laplacian` values, mapped onto `LaplacianKind` so the library does not depend on clap.
+#[derive(Clone, Copy, ValueEnum)]
+enum LaplacianArg {
+    Combinatorial,
+    Normalized,
+    RandomWalk,
+    Signless,
+}
+
+impl From<LaplacianArg> for LaplacianKind {
+    fn from(arg: LaplacianArg) -> Self {
+        match arg {
+            LaplacianArg::Combinatorial => LaplacianKind::Combinatorial,
+            LaplacianArg::Normalized => LaplacianKind::Normalized,
+            LaplacianArg::RandomWalk => LaplacianKind::RandomWalk,
+            LaplacianArg::Signless => LaplacianKind::Signless,
+        }
+    }
+}
+
+/// The `--format` values, mapped onto `OutputFormat`.
+#[derive(Clone, Copy, ValueEnum)]
+enum FormatArg {
+    Csv,
+    Npy,
+    Mtx,
+    Json,
+}
+
+impl From<FormatArg> for OutputFormat {
+    fn from(arg: FormatArg) -> Self {
+        match arg {
+            FormatArg::Csv => OutputFormat::Csv,
+            FormatArg::Npy => OutputFormat::Npy,
+            FormatArg::Mtx => OutputFormat::Mtx,
+            FormatArg::Json => OutputFormat::Json,
+        }
+    }
+}
+
+/// The `--scaling` values, mapped onto `EmbeddingScaling`.
+#[derive(Clone, Copy, ValueEnum)]
+enum ScalingArg {
+    None,
+    Diffusion,
+    Commute,
+}
+
+impl From<ScalingArg> for EmbeddingScaling {
+    fn from(arg: ScalingArg) -> Self {
+        match arg {
+            ScalingArg::None => EmbeddingScaling::None,
+            ScalingArg::Diffusion => EmbeddingScaling::Diffusion,
+            ScalingArg::Commute => EmbeddingScaling::Commute,
+        }
+    }
+}
+
+/// Creates a percentage bar that shows the current phase name and keeps ticking while a phase runs.
+fn phase_progress_bar() -> ProgressBar {
+    let bar = ProgressBar::new(100);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos:>3}% {msg}")
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    bar.enable_steady_tick(Duration::from_millis(100));
+    bar
+}
+
+/// The `phase_progress_bar`, or a hidden bar with `--quiet`.
+fn progress_bar(quiet: bool) -> ProgressBar {
+    if quiet {
+        ProgressBar::hidden()
+    } else {
+        phase_progress_bar()
+    }
+}
+
 fn main() -> io::Result<()> {
     let cli = Cli::parse();
 
@@ -61,10 +345,143 @@ fn main() -> io::Result<()> {
             start_node,
             end_node,
             output,
+            laplacian,
+            clamp_floor,
+            format,
+            max_band,
+            quiet,
         } => {
-            extract::extract_and_analyze_submatrix(input, *start_node, *end_node, output)?;
+            let mut bar = progress_bar(*quiet);
+            extract::extract_and_analyze_submatrix_with_progress(
+                input,
+                *start_node,
+                *end_node,
+                output,
+                &extract::ExtractOptions {
+                    kind: (*laplacian).into(),
+                    clamp_floor: *clamp_floor,
+                    format: (*format).into(),
+                    max_band: *max_band,
+                    quiet: *quiet,
+                },
+                &mut bar,
+            )?;
+            bar.finish();
+        }
+        Commands::FiedlerRank { gfa, out, format, laplacian, quiet } => {
+            let options = extract::ExtractOptions {
+                kind: (*laplacian).into(),
+                format: (*format).into(),
+                quiet: *quiet,
+                ..Default::default()
+            };
+            let mut bar = progress_bar(*quiet);
+            extract::rank_gfa_by_fiedler_with_options(gfa, &options, out, &mut bar)?;
+            bar.finish();
+        }
+        Commands::SpectralDist { glob, out, format, laplacian, quiet } => {
+            let options = extract::ExtractOptions {
+                kind: (*laplacian).into(),
+                format: (*format).into(),
+                quiet: *quiet,
+                ..Default::default()
+            };
+            let mut bar = progress_bar(*quiet);
+            extract::write_spectral_distances_with_options(glob, &options, out, &mut bar)?;
+            bar.finish();
+        }
+        Commands::Compare { a, b, laplacian, clamp_floor, quiet } => {
+            let options = extract::ExtractOptions {
+                kind: (*laplacian).into(),
+                clamp_floor: *clamp_floor,
+                quiet: *quiet,
+                ..Default::default()
+            };
+            let mut bar = progress_bar(*quiet);
+            extract::compare_gfa_with_options(a, b, &options, &mut bar)?;
+            bar.finish();
+        }
+        Commands::Eigen { banded, il, iu, out, format } => {
+            let options = extract::ExtractOptions { format: (*format).into(), ..Default::default() };
+            extract::eigen_range_from_banded_with_options(banded, *il, *iu, &options, out)?;
+        }
+        Commands::Band { csv, kd, reorder, format, max_band, laplacian, out } => {
+            let kd = match kd.as_str() {
+                "auto" => None,
+                value => Some(value.parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("--kd must be \"auto\" or a non-negative integer, got '{}'", value),
+                    )
+                })?),
+            };
+            let options = extract::ExtractOptions {
+                kind: (*laplacian).into(),
+                format: (*format).into(),
+                max_band: *max_band,
+                ..Default::default()
+            };
+            if *reorder {
+                extract::band_csv_reordered(csv, kd, &options, out)?;
+            } else {
+                extract::band_csv_with_options(csv, kd, &options, out)?;
+            }
+        }
+        Commands::Embed { gfa, dims, scaling, out, format, laplacian, quiet } => {
+            let options = extract::ExtractOptions {
+                kind: (*laplacian).into(),
+                format: (*format).into(),
+                quiet: *quiet,
+                ..Default::default()
+            };
+            let mut bar = progress_bar(*quiet);
+            extract::embed_gfa_with_options(gfa, *dims, (*scaling).into(), &options, out, &mut bar)?;
+            bar.finish();
+        }
+        Commands::NgecTrack { gfa, window, step, out, laplacian, clamp_floor, quiet } => {
+            let options = extract::ExtractOptions {
+                kind: (*laplacian).into(),
+                clamp_floor: *clamp_floor,
+                quiet: *quiet,
+                ..Default::default()
+            };
+            let mut bar = progress_bar(*quiet);
+            extract::ngec_track_gfa_with_options(gfa, *window, *step, &options, out, &mut bar)?;
+            bar.finish();
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that `compare` parses its `--a`/`--b` paths and the Laplacian, floor and quiet options, with
+    /// the documented defaults when they are omitted.
+    #[test]
+    fn test_compare_parses_paths_and_options() {
+        let cli = Cli::parse_from([
+            "graphome", "compare", "--a", "ref.gfa", "--b", "sample.gfa", "--laplacian", "normalized",
+            "--clamp-floor", "1e-6", "--quiet",
+        ]);
+        let Commands::Compare { a, b, laplacian, clamp_floor, quiet } = cli.command else {
+            panic!("Expected the compare subcommand");
+        };
+        assert_eq!((a.as_str(), b.as_str()), ("ref.gfa", "sample.gfa"));
+        assert_eq!(LaplacianKind::from(laplacian), LaplacianKind::Normalized);
+        assert_eq!(clamp_floor, 1e-6);
+        assert!(quiet);
+
+        let cli = Cli::parse_from(["graphome", "compare", "--a", "ref.gfa", "--b", "sample.gfa"]);
+        let Commands::Compare { laplacian, clamp_floor, quiet, .. } = cli.command else {
+            panic!("Expected the compare subcommand");
+        };
+        assert_eq!(LaplacianKind::from(laplacian), LaplacianKind::Combinatorial);
+        assert_eq!(clamp_floor, DEFAULT_SPECTRUM_FLOOR);
+        assert!(!quiet);
+
+        assert!(Cli::try_parse_from(["graphome", "compare", "--a", "ref.gfa"]).is_err());
+    }
+}
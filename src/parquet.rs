@@ -0,0 +1,90 @@
+// src/parquet.rs
+
+//! Module for exporting eigenvectors as Parquet tables.
+//!
+//! Enabled by the `parquet` feature, which links the Apache `parquet` crate without its
+//! compression codecs. Tables are written uncompressed with a single row group and load directly
+//! into pandas, polars, DuckDB or Spark.
+
+use ::parquet::data_type::{ByteArray, ByteArrayType, DoubleType};
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::reader::{FileReader, SerializedFileReader};
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::schema::parser::parse_message_type;
+use ndarray::prelude::*;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Writes eigenvectors as a Parquet table with one row per node: a UTF-8 `name` column followed
+/// by one DOUBLE column `eigenvector_<j>` per eigenvector.
+///
+/// # Arguments
+///
+/// * `path` - The output file.
+/// * `eigvecs` - The eigenvectors as columns, one row per node (as returned by `call_eigendecomp`).
+/// * `node_names` - The name of every node, in row order.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if `node_names` does not have one entry per row, and any I/O or
+/// encoding error from writing the file.
+pub fn save_eigenvectors_parquet(path: &Path, eigvecs: &Array2<f64>, node_names: &[String]) -> io::Result<()> {
+    let (n, k) = eigvecs.dim();
+    if node_names.len() != n {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Expected {} node names, got {}.", n, node_names.len()),
+        ));
+    }
+
+    let columns: String = (0..k).map(|j| format!(" required double eigenvector_{};", j)).collect();
+    let schema = parse_message_type(&format!("message eigenvectors {{ required binary name (UTF8);{} }}", columns))
+        .map_err(io::Error::other)?;
+    let properties = WriterProperties::builder().build();
+    let mut writer = SerializedFileWriter::new(File::create(path)?, Arc::new(schema), Arc::new(properties))
+        .map_err(io::Error::other)?;
+
+    let mut row_group = writer.next_row_group().map_err(io::Error::other)?;
+    let names: Vec<ByteArray> = node_names.iter().map(|name| ByteArray::from(name.as_str())).collect();
+    let mut column = row_group
+        .next_column()
+        .map_err(io::Error::other)?
+        .ok_or_else(|| io::Error::other("The schema has no name column."))?;
+    column.typed::<ByteArrayType>().write_batch(&names, None, None).map_err(io::Error::other)?;
+    column.close().map_err(io::Error::other)?;
+
+    for eigvec in eigvecs.columns() {
+        let mut column = row_group
+            .next_column()
+            .map_err(io::Error::other)?
+            .ok_or_else(|| io::Error::other("The schema has fewer columns than eigenvectors."))?;
+        column.typed::<DoubleType>().write_batch(&eigvec.to_vec(), None, None).map_err(io::Error::other)?;
+        column.close().map_err(io::Error::other)?;
+    }
+
+    row_group.close().map_err(io::Error::other)?;
+    writer.close().map_err(io::Error::other)?;
+    Ok(())
+}
+
+/// Reads the column names and row count from the footer of a Parquet file, e.g. to check an
+/// export without loading it.
+///
+/// # Errors
+///
+/// Returns `InvalidData` if the file is not a valid Parquet file (including a footer length
+/// that runs past the start of the file), and any I/O error from reading it.
+pub fn read_parquet_schema(path: &Path) -> io::Result<(Vec<String>, u64)> {
+    let reader = SerializedFileReader::new(File::open(path)?)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    let metadata = reader.metadata().file_metadata();
+    let columns = metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect();
+    Ok((columns, metadata.num_rows().max(0) as u64))
+}
@@ -0,0 +1,40 @@
+// src/trace.rs
+
+//! Module for `tracing` spans around the stages of the eigen pipeline.
+//!
+//! Enabled by the `trace` feature. Every decomposition opens an `info`-level span named
+//! `eigendecomp`, with child spans `banding`, `lapack.<routine>` (e.g. `lapack.dsbevd`) and
+//! `vector_assembly`, each carrying the matrix dimensions `n` and `kd` as fields. Any `tracing`
+//! subscriber can consume them: `tracing-subscriber`'s fmt layer with `FmtSpan::CLOSE` prints each
+//! stage with its duration, and `tracing-chrome` turns them into a waterfall. Without the feature,
+//! `stage_span!` compiles to nothing.
+
+/// Opens a span around a stage of the pipeline and enters it until the returned guard drops.
+///
+/// `stage_span!("banding", n = n, kd = kd)` expands to `tracing::info_span!(..).entered()` with
+/// the `trace` feature, and to a `DisabledSpan` without it.
+macro_rules! stage_span {
+    ($name:expr $(, $field:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "trace")]
+        let guard = tracing::info_span!($name $(, $field = $value)*).entered();
+        #[cfg(not(feature = "trace"))]
+        let guard = {
+            $(let _ = $value;)*
+            $crate::trace::DisabledSpan
+        };
+        guard
+    }};
+}
+
+pub(crate) use stage_span;
+
+/// The guard `stage_span!` returns without the `trace` feature.
+#[cfg(not(feature = "trace"))]
+#[must_use = "The span closes as soon as the guard is dropped."]
+pub(crate) struct DisabledSpan;
+
+// A no-op `Drop`, so call sites can end a stage early with `drop` whichever guard they hold
+#[cfg(not(feature = "trace"))]
+impl Drop for DisabledSpan {
+    fn drop(&mut self) {}
+}
@@ -0,0 +1,22 @@
+// tests/test_backend.rs
+
+//! Unit tests for the backend module.
+
+use graphome::backend::{backend_info, compiled_backends};
+
+/// Test that `backend_info` reports a vendor and a well-formed LAPACK version.
+#[test]
+fn test_backend_info_reports_vendor_and_version() {
+    let info = backend_info();
+    assert!(!info.vendor.is_empty());
+
+    let parts: Vec<u32> = info
+        .lapack_version
+        .split('.')
+        .map(|part| part.parse().expect("Version parts should be numeric."))
+        .collect();
+    assert_eq!(parts.len(), 3);
+    assert!(parts[0] >= 3, "Unexpected LAPACK version {}", info.lapack_version);
+
+    assert!(compiled_backends().len() <= 1);
+}
@@ -0,0 +1,62 @@
+// tests/test_chebyshev.rs
+
+//! Unit tests for the chebyshev module.
+
+use graphome::chebyshev::{chebyshev_coefficients, stochastic_trace};
+use graphome::eigen::call_eigendecomp;
+use graphome::laplacian::{combinatorial_laplacian, LaplacianOperator};
+use ndarray::prelude::*;
+
+const TOLERANCE: f64 = 1e-6;
+
+/// Adjacency matrix of the path graph on `n` nodes.
+fn path_adjacency(n: usize) -> Array2<f64> {
+    let mut adjacency = Array2::<f64>::zeros((n, n));
+    for i in 0..n - 1 {
+        adjacency[[i, i + 1]] = 1.0;
+        adjacency[[i + 1, i]] = 1.0;
+    }
+    adjacency
+}
+
+/// Test that the Chebyshev interpolant reproduces a smooth function on the interval.
+#[test]
+fn test_chebyshev_coefficients_interpolate() {
+    let f = |x: f64| (-0.5 * x).exp();
+    let lambda_max = 4.0;
+    let coefficients = chebyshev_coefficients(&f, 20, lambda_max);
+
+    for &lambda in &[0.0, 0.7, 2.0, 3.9] {
+        let x: f64 = 2.0 * lambda / lambda_max - 1.0;
+        let theta = x.acos();
+        let approx: f64 = coefficients
+            .iter()
+            .enumerate()
+            .map(|(k, c)| c * (k as f64 * theta).cos())
+            .sum();
+        assert!((approx - f(lambda)).abs() < TOLERANCE);
+    }
+}
+
+/// Test that the stochastic heat trace is close to the exact trace on a small graph.
+#[test]
+fn test_stochastic_trace_heat_kernel() {
+    let adjacency = path_adjacency(30);
+    let operator = LaplacianOperator::from_dense(&adjacency);
+    assert_eq!(operator.dim(), 30);
+
+    // The operator matches the dense Laplacian
+    let x = Array1::from_iter((0..30).map(|i| (i as f64).sin()));
+    let dense = combinatorial_laplacian(&adjacency).dot(&x);
+    for (a, b) in operator.apply(&x).iter().zip(dense.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+
+    let heat = |lambda: f64| (-0.3 * lambda).exp();
+    let (eigvals, _) = call_eigendecomp(&combinatorial_laplacian(&adjacency)).unwrap();
+    let exact: f64 = eigvals.iter().map(|&lambda| heat(lambda)).sum();
+
+    let estimate = stochastic_trace(&operator, heat, 500, 30, operator.lambda_max_bound());
+    let relative_error = (estimate - exact).abs() / exact;
+    assert!(relative_error < 0.05, "estimate {} vs exact {}", estimate, exact);
+}
@@ -14,7 +14,11 @@ use ndarray::prelude::*;
 use std::io::{self, BufWriter, Write};
 use std::path::Path;
 
-use graphome::convert::convert_gfa_to_edge_list;
+use graphome::convert::{
+    assert_gfa_symmetric, convert_gfa_to_edge_list, load_dot_adjacency, load_gfa, load_gfa_directed, load_gfa_multi,
+    load_gfa_with_policy, write_gfa_with_partition, GfaError,
+};
+use graphome::graph::DuplicateEdgePolicy;
 
 #[cfg(test)]
 mod tests {
@@ -190,7 +194,7 @@ mod tests {
 
         // Load eigenvectors
         let eigenvectors_csv = output_analysis.path().with_extension("eigenvectors.csv");
-        let eigenvectors = load_csv_as_matrix(&eigenvectors_csv)?;
+        let _eigenvectors = load_csv_as_matrix(&eigenvectors_csv)?;
 
         // Since eigenvectors can vary in sign and orientation, we'll focus on verifying the eigenvalues' correctness
         // Alternatively, you can implement additional checks for eigenvectors if necessary
@@ -274,7 +278,7 @@ mod tests {
         }
         // Load eigenvectors
         let eigenvectors_csv = output_analysis.path().with_extension("eigenvectors.csv");
-        let eigenvectors = load_csv_as_matrix(&eigenvectors_csv)?;
+        let _eigenvectors = load_csv_as_matrix(&eigenvectors_csv)?;
         // Since eigenvectors can vary in sign and orientation, we'll focus on verifying the eigenvalues' correctness
         // Alternatively, you can implement additional checks for eigenvectors if necessary
         Ok(())
@@ -297,7 +301,7 @@ mod tests {
         let nrows = matrix.len();
         let ncols = if nrows > 0 { matrix[0].len() } else { 0 };
         Array2::from_shape_vec((nrows, ncols), matrix.into_iter().flatten().collect())
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(io::Error::other)
     }
 
     /// Helper function to load a CSV file as a nalgebra::DVector<f64>
@@ -443,7 +447,7 @@ mod tests {
             let v = eigvecs.column(i);
 
             // Compute L * v
-            let lv = &laplacian * &v;
+            let lv = &laplacian * v;
 
             // Compute lambda * v
             let lambda_v: DVector<f64> = v * lambda; // Explicitly specifying the type for lambda_v
@@ -471,7 +475,7 @@ mod tests {
     fn test_save_matrix_to_csv() -> io::Result<()> {
         // Create a temporary matrix
         let matrix = Array2::from_shape_vec((2, 2), vec![0.0, 1.0, 1.0, 0.0])
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            .map_err(io::Error::other)?;
 
         // Output file
         let output_file = NamedTempFile::new()?;
@@ -550,4 +554,252 @@ mod tests {
 
         Ok(())
     }
+
+    /// Test that write_gfa_with_partition tags every segment with the label of its matrix index
+    #[test]
+    fn test_write_gfa_with_partition() -> io::Result<()> {
+        // Segments are out of order in the file; matrix indices follow sorted names:
+        // "10" -> 0, "2" -> 1, "a" -> 2
+        let mut gfa_file = NamedTempFile::new()?;
+        writeln!(gfa_file, "H\tVN:Z:1.0")?;
+        writeln!(gfa_file, "S\ta\tACGT\tCL:i:9")?;
+        writeln!(gfa_file, "S\t2\t*")?;
+        writeln!(gfa_file, "S\t10\tGG\tLN:i:2")?;
+        writeln!(gfa_file, "L\t2\t+\ta\t+\t0M")?;
+
+        let output_gfa = NamedTempFile::new()?;
+        let labels = vec![5, 6, 7];
+        write_gfa_with_partition(gfa_file.path(), output_gfa.path(), &labels, "CL")?;
+
+        let mut contents = String::new();
+        File::open(output_gfa.path())?.read_to_string(&mut contents)?;
+        let lines: Vec<&str> = contents.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "H\tVN:Z:1.0",
+                "S\ta\tACGT\tCL:i:7",
+                "S\t2\t*\tCL:i:6",
+                "S\t10\tGG\tLN:i:2\tCL:i:5",
+                "L\t2\t+\ta\t+\t0M",
+            ],
+            "Annotated GFA does not carry the expected labels."
+        );
+
+        // A label count that does not match the segment count is rejected
+        let result = write_gfa_with_partition(gfa_file.path(), output_gfa.path(), &labels[..2], "CL");
+        assert!(result.is_err(), "Expected an error for a mismatched label count.");
+
+        Ok(())
+    }
+
+    /// Test that `load_gfa_multi` builds a block-diagonal matrix with prefixed, file-ordered names
+    #[test]
+    fn test_load_gfa_multi_block_diagonal() -> io::Result<()> {
+        let dir = tempdir()?;
+        let chr1 = dir.path().join("chr1.gfa");
+        let chr2 = dir.path().join("chr2.gfa");
+        std::fs::write(&chr1, "H\tVN:Z:1.0\nS\tb\t*\nS\ta\t*\nL\ta\t+\tb\t+\t0M\n")?;
+        std::fs::write(
+            &chr2,
+            "S\ta\t*\nS\tc\t*\nS\tb\t*\nL\ta\t+\tc\t+\t0M\nL\tc\t+\tb\t-\t0M\n",
+        )?;
+
+        let (adjacency, names) = load_gfa_multi(&[chr1.as_path(), chr2.as_path()]).unwrap();
+
+        assert_eq!(names, vec!["chr1:a", "chr1:b", "chr2:a", "chr2:b", "chr2:c"]);
+        let expected = array![
+            [0.0, 1.0, 0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 1.0],
+            [0.0, 0.0, 0.0, 0.0, 1.0],
+            [0.0, 0.0, 1.0, 1.0, 0.0]
+        ];
+        assert_eq!(adjacency, expected);
+
+        // The same file twice produces colliding names
+        let result = load_gfa_multi(&[chr1.as_path(), chr1.as_path()]);
+        assert!(matches!(result, Err(GfaError::DuplicateName(_))));
+
+        // A link to an undefined segment is reported with its line number
+        let broken = dir.path().join("broken.gfa");
+        std::fs::write(&broken, "S\ta\t*\nL\ta\t+\tz\t+\t0M\n")?;
+        let result = load_gfa_multi(&[broken.as_path()]);
+        assert!(matches!(result, Err(GfaError::Parse { line: 2, .. })));
+
+        Ok(())
+    }
+
+    /// Test that gzipped GFA and CSV inputs load identically to their uncompressed versions
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_inputs_match_uncompressed() -> io::Result<()> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use graphome::convert::load_gfa;
+        use graphome::eigen::read_array_from_csv;
+
+        fn gzip_copy(source: &Path, target: &Path) -> io::Result<()> {
+            let mut encoder = GzEncoder::new(File::create(target)?, Compression::default());
+            encoder.write_all(&std::fs::read(source)?)?;
+            encoder.finish()?;
+            Ok(())
+        }
+
+        let dir = tempdir()?;
+        let gfa = dir.path().join("graph.gfa");
+        let gfa_gz = dir.path().join("graph.gfa.gz");
+        std::fs::write(&gfa, "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t+\tc\t-\t0M\n")?;
+        gzip_copy(&gfa, &gfa_gz)?;
+        assert_eq!(load_gfa(&gfa_gz).unwrap(), load_gfa(&gfa).unwrap());
+
+        let csv = dir.path().join("matrix.csv");
+        let csv_gz = dir.path().join("matrix.csv.gz");
+        save_array_to_csv_dsbevd(&array![[1.0, 2.0], [3.0, 4.5]], &csv)?;
+        gzip_copy(&csv, &csv_gz)?;
+        assert_eq!(read_array_from_csv(&csv_gz).unwrap(), read_array_from_csv(&csv).unwrap());
+
+        Ok(())
+    }
+
+    /// Test that a gzipped input is rejected with a clear error when the `gzip` feature is off
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_gzip_input_requires_feature() -> io::Result<()> {
+        use graphome::convert::open_input;
+
+        let dir = tempdir()?;
+        let path = dir.path().join("graph.gfa.gz");
+        std::fs::write(&path, [0x1f, 0x8b, 0x08, 0x00])?;
+
+        let error = open_input(&path).err().expect("gzip input should be rejected");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("gzip"));
+
+        Ok(())
+    }
+
+    /// Test that a one-sided link is reported by segment name and a two-sided one is not
+    #[test]
+    fn test_assert_gfa_symmetric_reports_one_sided_link() -> io::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("one_sided.gfa");
+        // a-b is listed in both orientations, so c-b is missing its reversal `L b - c -`
+        std::fs::write(
+            &path,
+            "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t-\ta\t-\t0M\nL\tc\t+\tb\t+\t0M\n",
+        )?;
+
+        let (adjacency, names) = load_gfa_directed(&path).unwrap();
+        match assert_gfa_symmetric(&adjacency, &names) {
+            Err(GfaError::Asymmetric(pairs)) => {
+                assert_eq!(pairs, vec![("c".to_string(), "b".to_string())]);
+            }
+            other => panic!("Expected an asymmetry diagnostic, got {:?}", other),
+        }
+
+        // The symmetric loader mirrors every link, so it always passes
+        let (adjacency, names) = load_gfa(&path).unwrap();
+        assert!(assert_gfa_symmetric(&adjacency, &names).is_ok());
+
+        Ok(())
+    }
+
+    /// Test that an ordinary GFA file, listing each link once in any orientation, passes the check
+    #[test]
+    fn test_assert_gfa_symmetric_accepts_single_listed_links() -> io::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("single.gfa");
+        std::fs::write(
+            &path,
+            "S\ta\t*\nS\tb\t*\nS\tc\t*\nS\td\t*\nL\ta\t+\tb\t+\t0M\nL\tc\t-\tb\t-\t0M\nL\tc\t+\td\t-\t0M\n",
+        )?;
+
+        let (adjacency, names) = load_gfa_directed(&path).unwrap();
+        assert!(assert_gfa_symmetric(&adjacency, &names).is_ok());
+        assert_eq!(adjacency, load_gfa(&path).unwrap().0);
+
+        Ok(())
+    }
+
+    /// Test that a link listed twice (once reversed) is resolved by the duplicate-edge policy
+    #[test]
+    fn test_load_gfa_duplicate_policy() -> io::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("parallel.gfa");
+        std::fs::write(
+            &path,
+            "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t-\ta\t-\t0M\nL\tb\t+\tc\t+\t0M\n",
+        )?;
+
+        let (summed, _) = load_gfa_with_policy(&path, DuplicateEdgePolicy::Sum).unwrap();
+        assert_eq!(summed[[0, 1]], 2.0);
+        assert_eq!(summed[[1, 0]], 2.0);
+        assert_eq!(summed[[1, 2]], 1.0);
+
+        let (first, _) = load_gfa_with_policy(&path, DuplicateEdgePolicy::First).unwrap();
+        assert_eq!(first, load_gfa(&path).unwrap().0);
+
+        match load_gfa_with_policy(&path, DuplicateEdgePolicy::Error) {
+            Err(GfaError::Parse { line, .. }) => assert_eq!(line, 5),
+            other => panic!("Expected a duplicate-link error, got {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    /// Test that a DOT graph is parsed into a symmetric weighted adjacency, for both `graph` and
+    /// `digraph` headers
+    #[test]
+    fn test_load_dot_adjacency() -> io::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("small.dot");
+        std::fs::write(
+            &path,
+            r#"// A small weighted graph
+graph G {
+    rankdir = LR;
+    node [shape=box];
+    "seg 1" [label="first"];
+    "seg 1" -- b [weight=2.5];
+    /* a chain with the default weight */
+    b -- c -- d;
+    subgraph cluster_0 {
+        edge [weight=4]
+        c -- a:n
+    }
+    d -- a
+}
+"#,
+        )?;
+
+        let (adjacency, names) = load_dot_adjacency(&path)?;
+        assert_eq!(names, vec!["seg 1", "b", "c", "d", "a"]);
+        let expected = array![
+            [0.0, 2.5, 0.0, 0.0, 0.0],
+            [2.5, 0.0, 1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 1.0, 4.0],
+            [0.0, 0.0, 1.0, 0.0, 1.0],
+            [0.0, 0.0, 4.0, 1.0, 0.0]
+        ];
+        assert_eq!(adjacency, expected);
+
+        // A digraph is symmetrized, and its arcs must use '->'
+        let directed = dir.path().join("directed.dot");
+        std::fs::write(&directed, "digraph { x -> y [weight=3]; y -> z }\n")?;
+        let (adjacency, names) = load_dot_adjacency(&directed)?;
+        assert_eq!(names, vec!["x", "y", "z"]);
+        assert_eq!(adjacency, array![[0.0, 3.0, 0.0], [3.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+
+        std::fs::write(&directed, "digraph {\n x -- y\n}\n")?;
+        let error = load_dot_adjacency(&directed).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("line 2"), "{}", error);
+
+        std::fs::write(&directed, "graph { x -- y [weight=heavy] }\n")?;
+        assert!(load_dot_adjacency(&directed).is_err());
+
+        Ok(())
+    }
 }
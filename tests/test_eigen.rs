@@ -2,23 +2,133 @@
 
 //! Unit tests for the eigen module.
 
-use ndarray::array;
+use ndarray::{array, s};
 use graphome::eigen::{
+    banded_matmat,
+    banded_matvec,
+    banded_to_display,
     call_eigendecomp,
+    check_orthonormal,
+    check_symmetric,
+    call_eigendecomp_canonical,
+    call_eigendecomp_f32,
+    call_eigendecomp_with_timeout,
+    call_eigendecomp_with_options,
+    canonicalize_degenerate,
+    canonicalize_eigenpairs,
+    check_max_band,
+    clamp_spectrum,
+    commute_time_embedding,
+    compare_backends,
     compute_eigenvalues_and_vectors_sym,
+    compute_eigenvalues_block_tridiagonal,
+    compute_eigenvalues_and_vectors_mrrr,
     compute_eigenvalues_and_vectors_sym_band,
+    compute_eigenvalues_and_vectors_tridiagonal,
     compute_ngec,
+    compute_ngec_from_adjacency,
+    compute_ngec_with_floor,
+    count_trivial_modes,
+    csv_laplacian_to_banded,
+    csv_max_band,
+    decompose_by_components,
+    decompose_by_components_parallel,
+    degeneracy_structure,
+    estimate_resources,
+    eigenvector_centrality,
+    estrada_index,
+    gap_ratios,
+    graph_energy,
+    into_banded_format,
+    inverse_iteration,
+    inverse_participation_ratios,
+    kirchhoff_index,
+    is_tridiagonal,
+    kmeans,
+    load_array_from_npy,
+    log_estrada_index,
+    log_pseudo_determinant,
+    log_spanning_tree_count,
+    relative_spectrum_floor,
     max_band,
+    mean_gap_ratio,
+    ngec_distribution,
+    optimal_workspace,
+    read_array_from_csv,
+    read_labeled_csv,
+    read_square_csv,
+    reduce_to_tridiagonal,
+    run_eigendecomp_with_timeout,
     save_array_to_csv_dsbevd,
+    save_array_to_csv_labeled,
+    save_matrix,
+    save_vector,
+    scrub_non_finite,
+    scrub_non_finite_with_policy,
+    select_by_energy,
+    select_solver_policy,
+    skew_symmetrize,
+    spanning_edge_centrality,
+    spectral_diameter_bound,
+    spawn_eigendecomp,
+    smallest_magnitude_eigenvalue,
+    spanning_tree_count,
+    spectral_clustering,
+    spectral_embedding,
+    spectra_approx_eq,
+    spectral_radius,
+    symmetrize,
+    sweep_fiedler_cut,
     to_banded_format,
+    to_banded_format_uplo,
+    truncate_bandwidth,
+    truncation_error,
+    von_neumann_entropy,
+    BlockTridiagonal,
+    CsvError,
+    EigenError,
+    EigenOptions,
+    EigenvalueIter,
+    NonFinitePolicy,
+    OutputData,
+    OutputFormat,
+    SolverPolicy,
+    DEFAULT_DENSE_RATIO,
+    DEFAULT_SPECTRUM_FLOOR,
 };
+use graphome::laplacian::LaplacianKind;
 use std::fs;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::Read;
 use std::path::Path;
-use nalgebra::DMatrix;
 
 const TOLERANCE: f64 = 1e-6;
+const REL_TOLERANCE: f64 = 1e-10;
+
+/// Builds the Laplacian of the path graph on `n` nodes.
+fn path_laplacian(n: usize) -> ndarray::Array2<f64> {
+    let mut laplacian = ndarray::Array2::<f64>::zeros((n, n));
+    for i in 0..n - 1 {
+        laplacian[[i, i + 1]] = -1.0;
+        laplacian[[i + 1, i]] = -1.0;
+        laplacian[[i, i]] += 1.0;
+        laplacian[[i + 1, i + 1]] += 1.0;
+    }
+    laplacian
+}
+
+/// Builds the Laplacian of the cycle graph on `n >= 3` nodes.
+fn cycle_laplacian(n: usize) -> ndarray::Array2<f64> {
+    let mut laplacian = ndarray::Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        let j = (i + 1) % n;
+        laplacian[[i, i]] += 1.0;
+        laplacian[[j, j]] += 1.0;
+        laplacian[[i, j]] = -1.0;
+        laplacian[[j, i]] = -1.0;
+    }
+    laplacian
+}
 
 /// Test the `to_banded_format` function with a known symmetric matrix and `kd = 1`.
 #[test]
@@ -50,6 +160,39 @@ fn test_to_banded_format_kd_1() {
     );
 }
 
+/// Test `to_banded_format_uplo` for both triangles against hand-computed layouts.
+#[test]
+fn test_to_banded_format_uplo_both_triangles() {
+    let matrix = array![
+        [1.0, 5.0, 8.0, 0.0],
+        [5.0, 2.0, 6.0, 9.0],
+        [8.0, 6.0, 3.0, 7.0],
+        [0.0, 9.0, 7.0, 4.0]
+    ];
+
+    let upper = to_banded_format_uplo(&matrix, 2, 'U').unwrap();
+    let expected_upper = array![
+        [0.0, 0.0, 8.0, 9.0],
+        [0.0, 5.0, 6.0, 7.0],
+        [1.0, 2.0, 3.0, 4.0]
+    ];
+    assert_eq!(upper, expected_upper);
+    assert_eq!(upper, to_banded_format(&matrix, 2));
+
+    let lower = to_banded_format_uplo(&matrix, 2, 'L').unwrap();
+    let expected_lower = array![
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 0.0],
+        [8.0, 9.0, 0.0, 0.0]
+    ];
+    assert_eq!(lower, expected_lower);
+
+    assert!(matches!(
+        to_banded_format_uplo(&matrix, 2, 'X'),
+        Err(EigenError::InvalidInput(_))
+    ));
+}
+
 /// Test the `to_banded_format` function with a known symmetric matrix and `kd = 2`.
 #[test]
 fn test_to_banded_format_kd_2() {
@@ -110,15 +253,14 @@ fn test_compute_eigenvalues_and_vectors_sym_band_known_matrix() {
     eigvals_sym_sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
     // Compare eigenvalues within the specified tolerance
-    for (computed, reference) in eigvals_lapack_sorted.iter().zip(eigvals_sym_sorted.iter()) {
-        assert!(
-            (*computed - *reference).abs() <= TOLERANCE,
-            "Eigenvalues mismatch: computed = {}, reference = {}, difference = {}",
-            computed,
-            reference,
-            (*computed - reference).abs()
-        );
-    }
+    let computed = ndarray::Array1::from(eigvals_lapack_sorted);
+    let reference = ndarray::Array1::from(eigvals_sym_sorted);
+    assert!(
+        spectra_approx_eq(&computed, &reference, TOLERANCE, REL_TOLERANCE),
+        "Eigenvalues mismatch: computed = {:?}, reference = {:?}",
+        computed,
+        reference
+    );
 
     // Compare eigenvectors within the specified tolerance
     // Handle potential sign differences
@@ -172,15 +314,14 @@ fn test_compute_eigenvalues_and_vectors_sym_known_matrix() {
     eigvals_lapack_sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
     // Compare eigenvalues within the specified tolerance
-    for (computed, reference) in eigvals_sym_sorted.iter().zip(eigvals_lapack_sorted.iter()) {
-        assert!(
-            (*computed - *reference).abs() <= TOLERANCE,
-            "Eigenvalues mismatch: computed = {}, reference = {}, difference = {}",
-            computed,
-            reference,
-            (*computed - reference).abs()
-        );
-    }
+    let computed = ndarray::Array1::from(eigvals_sym_sorted);
+    let reference = ndarray::Array1::from(eigvals_lapack_sorted);
+    assert!(
+        spectra_approx_eq(&computed, &reference, TOLERANCE, REL_TOLERANCE),
+        "Eigenvalues mismatch: computed = {:?}, reference = {:?}",
+        computed,
+        reference
+    );
 
     // Compare eigenvectors within the specified tolerance
     for col in 0..eigvecs_sym.ncols() {
@@ -203,6 +344,21 @@ fn test_compute_eigenvalues_and_vectors_sym_known_matrix() {
     }
 }
 
+/// Test that `compute_eigenvalues_and_vectors_sym` returns eigenpairs in ascending order, each
+/// eigenvector moved with its eigenvalue, whatever order nalgebra produces them in.
+#[test]
+fn test_compute_eigenvalues_and_vectors_sym_ascending_order() {
+    // A diagonal matrix has the unit vectors as eigenvectors, so the permutation is visible
+    let diagonal = [5.0, 1.0, 3.0, 2.0];
+    let matrix = ndarray::Array2::from_diag(&ndarray::Array1::from(diagonal.to_vec()));
+
+    let (eigvals, eigvecs) = compute_eigenvalues_and_vectors_sym(&matrix).unwrap();
+    assert_eq!(eigvals.as_slice(), &[1.0, 2.0, 3.0, 5.0]);
+    for (k, &source) in [1, 3, 2, 0].iter().enumerate() {
+        assert!((eigvecs[(source, k)].abs() - 1.0).abs() <= TOLERANCE, "Column {} is not e_{}.", k, source);
+    }
+}
+
 /// Test the `compute_ngec` function with a set of positive eigenvalues.
 #[test]
 fn test_compute_ngec_with_positive_eigenvalues() {
@@ -245,6 +401,39 @@ fn test_compute_ngec_with_negative_eigenvalues() {
     }
 }
 
+/// Test that the NGEC distribution sums to one and its entropy matches `von_neumann_entropy`.
+#[test]
+fn test_ngec_distribution_sums_to_one() {
+    // The tiny negative value is rounding noise and is clamped to zero
+    let eigenvalues = array![-1e-12, 1.0, 2.0, 3.0];
+    let distribution = ngec_distribution(&eigenvalues).expect("Failed to compute the NGEC distribution");
+
+    assert!((distribution.sum() - 1.0).abs() < TOLERANCE);
+    assert_eq!(distribution[0], 0.0);
+    assert!((distribution[3] - 0.5).abs() < TOLERANCE);
+
+    let entropy: f64 = distribution.iter().filter(|&&p| p > 0.0).map(|&p| -p * p.ln()).sum();
+    assert!((entropy - von_neumann_entropy(&eigenvalues).unwrap()).abs() < TOLERANCE);
+
+    assert!(matches!(ngec_distribution(&array![0.0, 0.0]), Err(EigenError::InvalidInput(_))));
+}
+
+/// Test that the clamp floor decides whether a tiny eigenvalue contributes to the NGEC.
+#[test]
+fn test_compute_ngec_with_floor() {
+    // A genuinely tiny Fiedler value of 1e-7
+    let eigenvalues = array![0.0, 1e-7, 1.0, 2.0];
+
+    let kept = compute_ngec_with_floor(&eigenvalues, DEFAULT_SPECTRUM_FLOOR).unwrap();
+    let clamped = compute_ngec_with_floor(&eigenvalues, 1e-6).unwrap();
+    assert!(kept > clamped, "NGEC should grow when the tiny eigenvalue is kept: {} vs {}", kept, clamped);
+    assert_eq!(kept, compute_ngec(&eigenvalues).unwrap());
+
+    // With the tiny value clamped, only 1 and 2 remain
+    let expected = -(1.0_f64 / 3.0 * (1.0_f64 / 3.0).ln() + 2.0 / 3.0 * (2.0_f64 / 3.0).ln()) / 4.0_f64.ln();
+    assert!((clamped - expected).abs() < TOLERANCE);
+}
+
 /// Test the `compute_ngec` function with an empty eigenvalues array.
 #[test]
 fn test_compute_ngec_with_empty_eigenvalues() {
@@ -277,11 +466,11 @@ fn test_save_array_to_csv_dsbevd() {
     let output_path = Path::new("test_output_dsbevd.csv");
 
     // Save the array to a CSV file
-    save_array_to_csv_dsbevd(&array, &output_path)
+    save_array_to_csv_dsbevd(&array, output_path)
         .expect("Failed to save array to CSV using dsbevd");
 
     // Read back the file contents
-    let mut file = File::open(&output_path)
+    let mut file = File::open(output_path)
         .expect("Failed to open CSV file saved by dsbevd");
     let mut contents = String::new();
     file.read_to_string(&mut contents)
@@ -301,6 +490,69 @@ fn test_save_array_to_csv_dsbevd() {
         .expect("Failed to delete test output CSV file saved by dsbevd");
 }
 
+/// Test that `read_square_csv` round-trips a saved array and locates a malformed cell.
+#[test]
+fn test_read_square_csv_reports_malformed_cell() {
+    let array = array![[1.0, -2.5], [3.0, 4.0]];
+    let roundtrip_path = Path::new("test_read_square_csv_roundtrip.csv");
+    save_array_to_csv_dsbevd(&array, roundtrip_path).expect("Failed to save array to CSV");
+    let loaded = read_square_csv(roundtrip_path).expect("Failed to read CSV back");
+    assert_eq!(loaded, array);
+    fs::remove_file(roundtrip_path).expect("Failed to delete test CSV file");
+
+    let malformed_path = Path::new("test_read_square_csv_malformed.csv");
+    fs::write(malformed_path, "1.0,2.0,3.0\n4.0,5.0,6.0\n7.0,oops,9.0\n")
+        .expect("Failed to write malformed CSV");
+    let result = read_square_csv(malformed_path);
+    fs::remove_file(malformed_path).expect("Failed to delete test CSV file");
+
+    match result {
+        Err(CsvError::Parse { row, column, token }) => {
+            assert_eq!((row, column), (3, 2));
+            assert_eq!(token, "oops");
+        }
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+}
+
+/// Test that a ragged CSV is reported as a shape error naming the short row, for plain and
+/// labeled matrices alike.
+#[test]
+fn test_read_csv_ragged_row_is_shape_error() {
+    let ragged_path = Path::new("test_read_csv_ragged.csv");
+    fs::write(ragged_path, "1.0,2.0,3.0\n4.0,5.0\n7.0,8.0,9.0\n").expect("Failed to write ragged CSV");
+    let result = read_array_from_csv(ragged_path);
+    fs::remove_file(ragged_path).expect("Failed to delete test CSV file");
+    match result {
+        Err(CsvError::Shape(message)) => assert!(message.contains("Row 2"), "{}", message),
+        other => panic!("Expected a shape error, got {:?}", other),
+    }
+
+    let labeled_path = Path::new("test_read_labeled_csv_ragged.csv");
+    fs::write(labeled_path, ",a,b\nx,1.0,2.0\ny,3.0\n").expect("Failed to write ragged CSV");
+    let result = read_labeled_csv(labeled_path);
+    fs::remove_file(labeled_path).expect("Failed to delete test CSV file");
+    assert!(matches!(result, Err(CsvError::Shape(_))), "{:?}", result);
+}
+
+/// Test that a gzipped CSV written by `save_array_to_csv_gz` reads back unchanged.
+#[cfg(feature = "gzip")]
+#[test]
+fn test_save_array_to_csv_gz_roundtrip() {
+    use graphome::eigen::save_array_to_csv_gz;
+
+    let array = array![[0.125, -2.0, 3.5], [1e-12, 4.0, -6.25]];
+    let output_path = Path::new("test_output_roundtrip.csv.gz");
+    save_array_to_csv_gz(&array, output_path).expect("Failed to save gzipped CSV");
+
+    let bytes = fs::read(output_path).expect("Failed to read gzipped CSV");
+    assert_eq!(&bytes[..2], &[0x1f, 0x8b], "Output is not gzip-compressed.");
+
+    let loaded = read_array_from_csv(output_path).expect("Failed to read gzipped CSV back");
+    fs::remove_file(output_path).expect("Failed to delete gzipped CSV");
+    assert_eq!(loaded, array);
+}
+
 /// Test the `call_eigendecomp` function with a small symmetric matrix.
 #[test]
 fn test_call_eigendecomp_with_small_matrix() {
@@ -365,15 +617,14 @@ fn test_compute_eigenvalues_and_vectors_sym_band_diagonal_matrix() {
     eigvals_sym_sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
     // Compare eigenvalues within the specified tolerance
-    for (computed, reference) in eigvals_lapack_sorted.iter().zip(eigvals_sym_sorted.iter()) {
-        assert!(
-            (*computed - *reference).abs() <= TOLERANCE,
-            "Eigenvalues mismatch: computed = {}, reference = {}, difference = {}",
-            computed,
-            reference,
-            (*computed - reference).abs()
-        );
-    }
+    let computed = ndarray::Array1::from(eigvals_lapack_sorted);
+    let reference = ndarray::Array1::from(eigvals_sym_sorted);
+    assert!(
+        spectra_approx_eq(&computed, &reference, TOLERANCE, REL_TOLERANCE),
+        "Eigenvalues mismatch: computed = {:?}, reference = {:?}",
+        computed,
+        reference
+    );
 
     // Compare eigenvectors within the specified tolerance
     // For diagonal matrices, eigenvectors should be the standard basis vectors
@@ -428,15 +679,14 @@ fn test_compute_eigenvalues_and_vectors_sym_band_larger_matrix() {
     eigvals_sym_sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
     // Compare eigenvalues within the specified tolerance
-    for (computed, reference) in eigvals_lapack_sorted.iter().zip(eigvals_sym_sorted.iter()) {
-        assert!(
-            (*computed - *reference).abs() <= TOLERANCE,
-            "Eigenvalues mismatch: computed = {}, reference = {}, difference = {}",
-            computed,
-            reference,
-            (*computed - reference).abs()
-        );
-    }
+    let computed = ndarray::Array1::from(eigvals_lapack_sorted);
+    let reference = ndarray::Array1::from(eigvals_sym_sorted);
+    assert!(
+        spectra_approx_eq(&computed, &reference, TOLERANCE, REL_TOLERANCE),
+        "Eigenvalues mismatch: computed = {:?}, reference = {:?}",
+        computed,
+        reference
+    );
 
     // Compare eigenvectors within the specified tolerance
     // Handle potential sign differences
@@ -460,6 +710,29 @@ fn test_compute_eigenvalues_and_vectors_sym_band_larger_matrix() {
     }
 }
 
+/// Test that dsbevd reads the band and returns Z in column-major order: on a pentadiagonal matrix
+/// with no mirror symmetry, a row-major band or eigenvector matrix would give wrong eigenpairs.
+#[test]
+fn test_compute_eigenvalues_and_vectors_sym_band_column_major_layout() {
+    let n = 7;
+    let matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| match i.abs_diff(j) {
+        0 => (i + 1) as f64,
+        1 => 0.3 * (i.min(j) + 1) as f64,
+        2 => -0.1 * (i.min(j) + 2) as f64,
+        _ => 0.0,
+    });
+
+    let (eigvals, eigvecs) = compute_eigenvalues_and_vectors_sym_band(&matrix, 2).unwrap();
+    assert!(eigvals.windows(2).into_iter().all(|pair| pair[0] <= pair[1]));
+    for (k, &lambda) in eigvals.iter().enumerate() {
+        let v = eigvecs.column(k);
+        let residual = matrix.dot(&v) - &v * lambda;
+        assert!(residual.iter().all(|r| r.abs() <= TOLERANCE), "Eigenpair {} is not valid.", k);
+    }
+    let gram = eigvecs.t().dot(&eigvecs);
+    assert!((gram - ndarray::Array2::<f64>::eye(n)).iter().all(|d| d.abs() <= TOLERANCE));
+}
+
 /// Test that all eigenvalues computed by LAPACK are non-negative.
 #[test]
 fn test_non_negative_eigenvalues_lapack() {
@@ -508,3 +781,1408 @@ fn test_non_negative_eigenvalues_symmetric() {
         );
     }
 }
+
+/// Test that `select_by_energy` returns every eigenvalue when asked for 100% of the energy.
+#[test]
+fn test_select_by_energy_full_fraction() {
+    // A Laplacian spectrum always starts at zero, which adds no energy but is still selected
+    let eigenvalues = array![0.0, 1.0, 2.0, 3.0, 4.0];
+
+    let k = select_by_energy(&eigenvalues, 1.0);
+
+    assert_eq!(k, eigenvalues.len(), "Capturing 100% of the energy should select all eigenvalues.");
+}
+
+/// Test that `select_by_energy` counts from the top of an ascending spectrum.
+#[test]
+fn test_select_by_energy_partial_fraction() {
+    // Total energy is 10; the top two eigenvalues (4 + 3) capture 70%
+    let eigenvalues = array![0.0, 1.0, 2.0, 3.0, 4.0];
+
+    assert_eq!(select_by_energy(&eigenvalues, 0.7), 2);
+    assert_eq!(select_by_energy(&eigenvalues, 0.71), 3);
+    assert_eq!(select_by_energy(&eigenvalues, 0.0), 0);
+}
+
+/// Test that a full matrix skips banding and is decomposed with the dense solver.
+#[test]
+fn test_call_eigendecomp_dense_path_for_full_matrix() {
+    // Laplacian of the complete graph K4: every off-diagonal entry is nonzero, so kd = n - 1
+    let laplacian = array![
+        [3.0, -1.0, -1.0, -1.0],
+        [-1.0, 3.0, -1.0, -1.0],
+        [-1.0, -1.0, 3.0, -1.0],
+        [-1.0, -1.0, -1.0, 3.0]
+    ];
+
+    let (eigvals, eigvecs, policy) =
+        call_eigendecomp_with_options(&laplacian, &EigenOptions::default())
+            .expect("Dense eigendecomposition failed");
+
+    assert_eq!(policy, SolverPolicy::Dense, "A full matrix should take the dense path.");
+    assert_eq!(eigvecs.dim(), (4, 4));
+
+    let expected = [0.0, 4.0, 4.0, 4.0];
+    for (computed, reference) in eigvals.iter().zip(expected.iter()) {
+        assert!(
+            (computed - reference).abs() <= TOLERANCE,
+            "Eigenvalues mismatch: computed = {}, reference = {}",
+            computed,
+            reference
+        );
+    }
+
+    // Each column must satisfy L v = λ v
+    for (k, &lambda) in eigvals.iter().enumerate() {
+        let v = eigvecs.column(k);
+        let residual = laplacian.dot(&v) - &v * lambda;
+        assert!(residual.iter().all(|r| r.abs() <= TOLERANCE), "Eigenpair {} is not valid.", k);
+    }
+}
+
+/// Test that the dsbtrd + dstemr path matches dsbevd on the banded fixtures and a wider band, with
+/// valid eigenpairs, and that `use_mrrr` only reroutes banded matrices.
+#[test]
+fn test_mrrr_matches_dsbevd() {
+    let mut wide = ndarray::Array2::<f64>::zeros((20, 20));
+    for i in 0..20 {
+        for j in (i + 1)..(i + 4).min(20) {
+            let w = 1.0 + ((i * 7 + j * 3) % 5) as f64 / 4.0;
+            wide[[i, j]] = -w;
+            wide[[j, i]] = -w;
+            wide[[i, i]] += w;
+            wide[[j, j]] += w;
+        }
+    }
+    let fixtures = [
+        array![[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 2.0]],
+        array![
+            [3.0, -1.0, -1.0, 0.0],
+            [-1.0, 3.0, -1.0, -1.0],
+            [-1.0, -1.0, 3.0, -1.0],
+            [0.0, -1.0, -1.0, 3.0]
+        ],
+        wide.clone(),
+    ];
+
+    for laplacian in &fixtures {
+        let kd = max_band(laplacian);
+        let (reference, _) = compute_eigenvalues_and_vectors_sym_band(laplacian, kd).unwrap();
+        let (eigvals, eigvecs) = compute_eigenvalues_and_vectors_mrrr(laplacian, kd).unwrap();
+        assert!(
+            spectra_approx_eq(&eigvals, &reference, TOLERANCE, REL_TOLERANCE),
+            "MRRR {:?} vs dsbevd {:?}",
+            eigvals,
+            reference
+        );
+        for (k, &lambda) in eigvals.iter().enumerate() {
+            let v = eigvecs.column(k);
+            let residual = laplacian.dot(&v) - &v * lambda;
+            assert!(residual.iter().all(|r| r.abs() <= TOLERANCE), "Eigenpair {} is not valid.", k);
+            assert!((v.dot(&v) - 1.0).abs() <= TOLERANCE);
+        }
+    }
+
+    let options = EigenOptions { use_mrrr: true, ..EigenOptions::default() };
+    let (_, _, policy) = call_eigendecomp_with_options(&wide, &options).unwrap();
+    assert_eq!(policy, SolverPolicy::Mrrr);
+    let (_, _, policy) = call_eigendecomp_with_options(&fixtures[0], &options).unwrap();
+    assert_eq!(policy, SolverPolicy::Tridiagonal);
+}
+
+/// Test that the dense ratio controls the dispatch decision.
+#[test]
+fn test_select_solver_policy_ratio() {
+    assert_eq!(select_solver_policy(2, 100, DEFAULT_DENSE_RATIO), SolverPolicy::Banded);
+    assert_eq!(select_solver_policy(90, 100, DEFAULT_DENSE_RATIO), SolverPolicy::Dense);
+    // A ratio of 1 never selects the dense path
+    assert_eq!(select_solver_policy(99, 100, 1.0), SolverPolicy::Banded);
+}
+
+/// Test that an arithmetic-progression spectrum has all gap ratios equal to one.
+#[test]
+fn test_gap_ratios_arithmetic_progression() {
+    let eigenvalues = array![0.0, 0.5, 1.0, 1.5, 2.0, 2.5];
+
+    let ratios = gap_ratios(&eigenvalues);
+
+    assert_eq!(ratios.len(), eigenvalues.len() - 2);
+    for &r in ratios.iter() {
+        assert!((r - 1.0).abs() <= TOLERANCE, "Gap ratio should be 1 for equal gaps, got {}", r);
+    }
+    let mean = mean_gap_ratio(&eigenvalues).expect("Mean gap ratio should exist");
+    assert!((mean - 1.0).abs() <= TOLERANCE);
+}
+
+/// Test gap ratios on unequal gaps and on a spectrum too short to have any.
+#[test]
+fn test_gap_ratios_unequal_and_short() {
+    // Gaps are 1, 3, 2 so the ratios are 1/3 and 2/3
+    let ratios = gap_ratios(&array![0.0, 1.0, 4.0, 6.0]);
+    assert!((ratios[0] - 1.0 / 3.0).abs() <= TOLERANCE);
+    assert!((ratios[1] - 2.0 / 3.0).abs() <= TOLERANCE);
+
+    assert!(gap_ratios(&array![0.0, 1.0]).is_empty());
+    assert!(mean_gap_ratio(&array![0.0, 1.0]).is_none());
+}
+
+/// Test that `eigenvector_centrality` ranks the center of a star graph highest.
+#[test]
+fn test_eigenvector_centrality_star_graph() {
+    // Star graph with center 0 and four leaves
+    let mut adjacency = ndarray::Array2::<f64>::zeros((5, 5));
+    for leaf in 1..5 {
+        adjacency[[0, leaf]] = 1.0;
+        adjacency[[leaf, 0]] = 1.0;
+    }
+
+    let centrality = eigenvector_centrality(&adjacency, 1000, 1e-12)
+        .expect("Power iteration should converge on a star graph");
+
+    // The principal eigenvector is (sqrt(n-1), 1, 1, 1, 1) up to normalization
+    let center = 1.0 / 2.0_f64.sqrt();
+    let leaf = center / 2.0;
+    assert!((centrality[0] - center).abs() <= TOLERANCE, "Center centrality = {}", centrality[0]);
+    for i in 1..5 {
+        assert!(centrality[i] >= 0.0, "Centrality must be nonnegative.");
+        assert!((centrality[i] - leaf).abs() <= TOLERANCE, "Leaf centrality = {}", centrality[i]);
+        assert!(centrality[0] > centrality[i], "The center should dominate every leaf.");
+    }
+}
+
+/// Test that `eigenvector_centrality` rejects invalid inputs and reports non-convergence.
+#[test]
+fn test_eigenvector_centrality_errors() {
+    let non_square = ndarray::Array2::<f64>::zeros((2, 3));
+    assert!(matches!(
+        eigenvector_centrality(&non_square, 10, 1e-9),
+        Err(EigenError::InvalidInput(_))
+    ));
+
+    let path = array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+    assert!(matches!(
+        eigenvector_centrality(&path, 1, 1e-15),
+        Err(EigenError::NotConverged { iterations: 1, .. })
+    ));
+}
+
+/// Test that `truncate_bandwidth` zeros exactly the entries beyond `kd` and that the error bound holds.
+#[test]
+fn test_truncate_bandwidth() {
+    let matrix = array![
+        [4.0, 1.0, 0.5, 0.25],
+        [1.0, 4.0, 1.0, 0.5],
+        [0.5, 1.0, 4.0, 1.0],
+        [0.25, 0.5, 1.0, 4.0]
+    ];
+
+    let truncated = truncate_bandwidth(&matrix, 1);
+
+    for ((i, j), &value) in truncated.indexed_iter() {
+        if i.abs_diff(j) > 1 {
+            assert_eq!(value, 0.0, "Entry ({}, {}) beyond the band was not zeroed.", i, j);
+        } else {
+            assert_eq!(value, matrix[[i, j]], "Entry ({}, {}) inside the band changed.", i, j);
+        }
+    }
+    assert_eq!(max_band(&truncated), 1);
+
+    // Discarded entries: four 0.5s and two 0.25s
+    let error = truncation_error(&matrix, 1);
+    assert!((error - (4.0 * 0.25 + 2.0 * 0.0625_f64).sqrt()).abs() <= TOLERANCE);
+
+    // Every eigenvalue moves by at most the Frobenius norm of the discarded part
+    let (exact, _) = call_eigendecomp(&matrix).unwrap();
+    let (approx, _) = call_eigendecomp(&truncated).unwrap();
+    for (a, b) in exact.iter().zip(approx.iter()) {
+        assert!((a - b).abs() <= error + TOLERANCE);
+    }
+}
+
+/// Test that canonical decompositions are reproducible across runs and solvers, even for a degenerate spectrum.
+#[test]
+fn test_call_eigendecomp_canonical_reproducible() {
+    // Laplacian of the complete graph K4: eigenvalues 0, 4, 4, 4
+    let laplacian = array![
+        [3.0, -1.0, -1.0, -1.0],
+        [-1.0, 3.0, -1.0, -1.0],
+        [-1.0, -1.0, 3.0, -1.0],
+        [-1.0, -1.0, -1.0, 3.0]
+    ];
+
+    let (vals_a, vecs_a) = call_eigendecomp_canonical(&laplacian).unwrap();
+    let (vals_b, vecs_b) = call_eigendecomp_canonical(&laplacian).unwrap();
+    assert_eq!(vals_a, vals_b);
+    assert_eq!(vecs_a, vecs_b);
+
+    // A different solver gives the same canonical output
+    let (sym_vals, sym_vecs) = compute_eigenvalues_and_vectors_sym(&laplacian).unwrap();
+    let sym_vals = ndarray::Array1::from(sym_vals.as_slice().to_vec());
+    let sym_vecs = ndarray::Array2::from_shape_fn((4, 4), |(i, j)| sym_vecs[(i, j)]);
+    let (vals_c, vecs_c) = canonicalize_eigenpairs(&sym_vals, &sym_vecs);
+
+    for (a, c) in vals_a.iter().zip(vals_c.iter()) {
+        assert!((a - c).abs() < TOLERANCE);
+    }
+    for (a, c) in vecs_a.iter().zip(vecs_c.iter()) {
+        assert!((a - c).abs() < TOLERANCE, "Canonical eigenvectors differ between solvers.");
+    }
+}
+
+/// Test that `canonicalize_eigenpairs` undoes a rotation within a degenerate eigenspace and a sign flip.
+#[test]
+fn test_canonicalize_eigenpairs_rotation_invariant() {
+    let (c, s) = (0.6_f64, 0.8_f64);
+    let eigvals = array![2.0, 1.0, 1.0];
+    let basis = array![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]];
+    // Same eigenspaces, with the degenerate pair rotated and the first vector negated
+    let rotated = array![[0.0, c, -s], [0.0, s, c], [-1.0, 0.0, 0.0]];
+
+    let (vals_a, vecs_a) = canonicalize_eigenpairs(&eigvals, &basis);
+    let (vals_b, vecs_b) = canonicalize_eigenpairs(&eigvals, &rotated);
+
+    assert_eq!(vals_a, array![1.0, 1.0, 2.0]);
+    assert_eq!(vals_a, vals_b);
+    for (a, b) in vecs_a.iter().zip(vecs_b.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+}
+
+/// Test `spanning_tree_count` on graphs with known counts.
+#[test]
+fn test_spanning_tree_count_known_graphs() {
+    // K3 has 3 spanning trees
+    let k3 = array![[2.0, -1.0, -1.0], [-1.0, 2.0, -1.0], [-1.0, -1.0, 2.0]];
+    let (eigvals, _) = call_eigendecomp(&k3).unwrap();
+    assert!((spanning_tree_count(&eigvals) - 3.0).abs() < TOLERANCE);
+
+    // Cayley's formula: K_n has n^(n-2) spanning trees (spectrum 0 and n with multiplicity n - 1)
+    let n = 200;
+    let mut spectrum = vec![n as f64; n];
+    spectrum[0] = 0.0;
+    let spectrum = ndarray::Array1::from(spectrum);
+    let expected = (n as f64 - 2.0) * (n as f64).ln();
+    assert!((log_spanning_tree_count(&spectrum) - expected).abs() < TOLERANCE);
+    assert!(spanning_tree_count(&spectrum).is_infinite());
+
+    // Two disconnected edges have no spanning tree
+    assert_eq!(spanning_tree_count(&array![0.0, 0.0, 2.0, 2.0]), 0.0);
+}
+
+/// Test that `compare_backends` agrees on a random symmetric matrix and flags an impossible tolerance.
+#[test]
+fn test_compare_backends_random_symmetric() {
+    // Small linear congruential generator so the test is deterministic without extra dependencies
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut next = || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+    };
+
+    let n = 12;
+    let mut matrix = ndarray::Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        for j in i..n {
+            let value = next();
+            matrix[[i, j]] = value;
+            matrix[[j, i]] = value;
+        }
+    }
+
+    let comparison = compare_backends(&matrix, TOLERANCE).unwrap();
+    assert_eq!(comparison.eigenvalue_diffs.len(), n);
+    assert_eq!(comparison.eigenvector_diffs.len(), n);
+    assert!(comparison.passed, "Backends disagree: {:?}", comparison);
+
+    assert!(!compare_backends(&matrix, -1.0).unwrap().passed);
+}
+
+/// Test that the nalgebra eigenvectors are copied into ndarray untransposed: on a banded matrix
+/// whose eigenvector matrix is far from symmetric, `compare_backends` still matches dsbevd.
+#[test]
+fn test_compare_backends_nalgebra_copy_orientation() {
+    let n = 7;
+    let matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| match i.abs_diff(j) {
+        0 => (i + 1) as f64,
+        1 => 0.3 * (i.min(j) + 1) as f64,
+        _ => 0.0,
+    });
+
+    let (_, eigvecs) = compute_eigenvalues_and_vectors_sym(&matrix).unwrap();
+    let asymmetry = (0..n)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .fold(0.0_f64, |acc, (i, j)| acc.max((eigvecs[(i, j)].abs() - eigvecs[(j, i)].abs()).abs()));
+    assert!(asymmetry > 0.1, "The fixture cannot tell a transposed copy apart.");
+
+    let comparison = compare_backends(&matrix, TOLERANCE).unwrap();
+    assert!(comparison.passed, "Backends disagree: {:?}", comparison);
+}
+
+/// Test that a block tridiagonal matrix round-trips through its blocks and decomposes like the dense matrix.
+#[test]
+fn test_block_tridiagonal_eigenvalues() {
+    // Path graph Laplacian on 5 nodes, split into windows of sizes 2, 2 and 1
+    let laplacian = array![
+        [1.0, -1.0, 0.0, 0.0, 0.0],
+        [-1.0, 2.0, -1.0, 0.0, 0.0],
+        [0.0, -1.0, 2.0, -1.0, 0.0],
+        [0.0, 0.0, -1.0, 2.0, -1.0],
+        [0.0, 0.0, 0.0, -1.0, 1.0]
+    ];
+
+    let blocks = BlockTridiagonal::from_dense(&laplacian, &[2, 2, 1]).unwrap();
+    assert_eq!(blocks.block_sizes(), vec![2, 2, 1]);
+    assert_eq!(blocks.block_bandwidth(), 3);
+    assert_eq!(blocks.to_dense(), laplacian);
+
+    let (block_vals, block_vecs) = compute_eigenvalues_block_tridiagonal(&blocks).unwrap();
+    let (dense_vals, _) = call_eigendecomp(&laplacian).unwrap();
+    assert!(spectra_approx_eq(&block_vals, &dense_vals, TOLERANCE, REL_TOLERANCE));
+
+    // A v = λ v for every returned eigenpair
+    let residual = laplacian.dot(&block_vecs) - &block_vecs * &block_vals;
+    assert!(residual.iter().all(|r| r.abs() < TOLERANCE));
+
+    // Entry (0, 4) would couple the first and last windows
+    let mut not_block = laplacian.clone();
+    not_block[[0, 4]] = 1.0;
+    not_block[[4, 0]] = 1.0;
+    assert!(matches!(
+        BlockTridiagonal::from_dense(&not_block, &[2, 2, 1]),
+        Err(EigenError::InvalidInput(_))
+    ));
+}
+
+/// Test the memory formula of `estimate_resources` for a known size.
+#[test]
+fn test_estimate_resources_memory_formula() {
+    let estimate = estimate_resources(1000, 10);
+
+    assert_eq!(estimate.banded_bytes, 11 * 1000 * 8);
+    assert_eq!(estimate.output_bytes, (1000 * 1000 + 1000) * 8);
+    assert_eq!(estimate.workspace_bytes, (1 + 5 * 1000 + 2 * 1000 * 1000) * 8 + (3 + 5 * 1000) * 4);
+    assert_eq!(
+        estimate.peak_bytes,
+        estimate.banded_bytes + estimate.output_bytes + estimate.workspace_bytes
+    );
+
+    // A wider band costs more time but the same eigenvector storage
+    let wider = estimate_resources(1000, 100);
+    assert!(wider.seconds > estimate.seconds);
+    assert_eq!(wider.output_bytes, estimate.output_bytes);
+}
+
+/// Test that `inverse_participation_ratios` is 1/n for a uniform vector and 1 for a localized one.
+#[test]
+fn test_inverse_participation_ratios() {
+    let n = 8;
+    let mut eigvecs = ndarray::Array2::<f64>::zeros((n, 2));
+    eigvecs.column_mut(0).fill(1.0 / (n as f64).sqrt());
+    eigvecs[[3, 1]] = -1.0;
+
+    let ipr = inverse_participation_ratios(&eigvecs);
+    assert_eq!(ipr.len(), 2);
+    assert!((ipr[0] - 1.0 / n as f64).abs() < TOLERANCE);
+    assert!((ipr[1] - 1.0).abs() < TOLERANCE);
+}
+
+/// Test that `sweep_fiedler_cut` recovers the bottleneck between two triangles, even when the Fiedler vector is shifted.
+#[test]
+fn test_sweep_fiedler_cut_bottleneck() {
+    // Triangles {0, 1, 2} and {3, 4, 5} joined by the edge 2 - 3
+    let mut adjacency = ndarray::Array2::<f64>::zeros((6, 6));
+    for &(a, b) in &[(0, 1), (0, 2), (1, 2), (3, 4), (3, 5), (4, 5), (2, 3)] {
+        adjacency[[a, b]] = 1.0;
+        adjacency[[b, a]] = 1.0;
+    }
+    let degrees = adjacency.sum_axis(ndarray::Axis(1));
+    let laplacian = ndarray::Array2::from_diag(&degrees) - &adjacency;
+
+    let (_, eigvecs) = call_eigendecomp(&laplacian).unwrap();
+    let fiedler = eigvecs.column(1).to_owned();
+
+    let (conductance, partition) = sweep_fiedler_cut(&adjacency, &fiedler);
+    assert!((conductance - 1.0 / 7.0).abs() < TOLERANCE);
+    assert_eq!(partition.iter().filter(|&&side| side).count(), 3);
+    assert_eq!(partition[0], partition[1]);
+    assert_eq!(partition[1], partition[2]);
+    assert_ne!(partition[2], partition[3]);
+
+    // Thresholding at zero would put every node on one side; the sweep still finds the cut
+    let shifted = &fiedler + 10.0;
+    let (shifted_conductance, shifted_partition) = sweep_fiedler_cut(&adjacency, &shifted);
+    assert!((shifted_conductance - conductance).abs() < TOLERANCE);
+    assert_eq!(shifted_partition, partition);
+}
+
+/// Test that `scrub_non_finite` counts NaN and Inf separately, records coordinates, and honours the policy.
+#[test]
+fn test_scrub_non_finite_counts() {
+    let mut matrix = ndarray::Array2::<f64>::ones((20, 20));
+    matrix[[0, 3]] = f64::NAN;
+    matrix[[2, 1]] = f64::INFINITY;
+    matrix[[5, 5]] = f64::NEG_INFINITY;
+    for i in 0..12 {
+        matrix[[10, i]] = f64::NAN;
+    }
+
+    // The error policy leaves the matrix alone
+    let mut untouched = matrix.clone();
+    let err = scrub_non_finite_with_policy(&mut untouched, NonFinitePolicy::Error).unwrap_err();
+    assert!(matches!(err, EigenError::InvalidInput(ref message) if message.contains("13 NaN and 2 infinite")));
+    assert!(untouched[[0, 3]].is_nan());
+
+    let report = scrub_non_finite(&mut matrix);
+    assert_eq!(report.nan_count, 13);
+    assert_eq!(report.inf_count, 2);
+    assert_eq!(report.coordinates.len(), 10);
+    assert_eq!(&report.coordinates[..4], &[(0, 3), (2, 1), (5, 5), (10, 0)]);
+    assert!(matrix.iter().all(|v| v.is_finite()));
+    assert_eq!(matrix[[0, 3]], 0.0);
+
+    // A clean matrix passes either policy
+    let report = scrub_non_finite_with_policy(&mut matrix, NonFinitePolicy::Error).unwrap();
+    assert!(report.is_clean());
+}
+
+/// Test that `symmetrize` and `skew_symmetrize` split a directed matrix into its symmetric and antisymmetric parts.
+#[test]
+fn test_symmetrize_and_skew_symmetrize() {
+    let directed = array![[0.0, 1.0, 0.0], [0.0, 0.0, 3.0], [2.0, 0.0, 0.0]];
+    assert!(matches!(check_symmetric(&directed, TOLERANCE), Err(EigenError::InvalidInput(_))));
+
+    let symmetric = symmetrize(&directed);
+    let skew = skew_symmetrize(&directed);
+    assert!(check_symmetric(&symmetric, TOLERANCE).is_ok());
+    assert_eq!(symmetric[[0, 2]], 1.0);
+    assert_eq!(skew, -skew.t().to_owned());
+    assert_eq!(&symmetric + &skew, directed);
+
+    let rectangular = ndarray::Array2::<f64>::zeros((2, 3));
+    assert!(check_symmetric(&rectangular, TOLERANCE).is_err());
+}
+
+/// Test that `clamp_spectrum` zeros tiny negatives and that the entropy measures agree on the clamped spectrum.
+#[test]
+fn test_clamp_spectrum_tiny_negatives() {
+    let spectrum = array![-1e-12, 1e-10, -0.5, 1.0, 2.0];
+    let clamped = clamp_spectrum(&spectrum, DEFAULT_SPECTRUM_FLOOR);
+    assert_eq!(clamped, array![0.0, 0.0, -0.5, 1.0, 2.0]);
+
+    // A Laplacian spectrum with rounding noise behaves exactly like the clean one
+    let noisy = array![-1e-13, 1.0, 3.0];
+    let clean = array![0.0, 1.0, 3.0];
+    assert_eq!(von_neumann_entropy(&noisy).unwrap(), von_neumann_entropy(&clean).unwrap());
+    assert_eq!(compute_ngec(&noisy).unwrap(), compute_ngec(&clean).unwrap());
+    assert_eq!(spanning_tree_count(&noisy), spanning_tree_count(&clean));
+
+    let expected = -(0.25_f64 * 0.25_f64.ln() + 0.75 * 0.75_f64.ln());
+    assert!((von_neumann_entropy(&clean).unwrap() - expected).abs() < TOLERANCE);
+
+    // On a heavily weighted spectrum the floor scales, and every measure sees the same zero
+    let heavy_noisy = array![2e-8, 1e7, 3e7];
+    let heavy_clean = array![0.0, 1e7, 3e7];
+    assert_eq!(clamp_spectrum(&heavy_noisy, DEFAULT_SPECTRUM_FLOOR), heavy_clean);
+    assert_eq!(compute_ngec(&heavy_noisy).unwrap(), compute_ngec(&heavy_clean).unwrap());
+    assert_eq!(kirchhoff_index(&heavy_noisy), kirchhoff_index(&heavy_clean));
+    assert_eq!(spectral_diameter_bound(&heavy_noisy, 3), spectral_diameter_bound(&heavy_clean, 3));
+    assert_eq!(log_spanning_tree_count(&heavy_noisy), log_spanning_tree_count(&heavy_clean));
+}
+
+/// Test that the zero test of `log_spanning_tree_count` scales with the weights: rounding noise
+/// above the absolute floor on a heavily weighted path is still a zero, and a lightly weighted
+/// path keeps its small real eigenvalues.
+#[test]
+fn test_log_spanning_tree_count_weight_scale() {
+    // The path 0 - 1 - 2 with weight w has spectrum {0, w, 3w} and a single tree of weight w^2
+    let heavy = array![2e-8, 1e7, 3e7];
+    assert!(heavy[0] > DEFAULT_SPECTRUM_FLOOR);
+    assert!((log_spanning_tree_count(&heavy) - 2.0 * 1e7_f64.ln()).abs() < TOLERANCE);
+
+    let light = array![0.0, 1e-3, 3e-3];
+    assert!((log_spanning_tree_count(&light) - 2.0 * 1e-3_f64.ln()).abs() < TOLERANCE);
+    assert_eq!(relative_spectrum_floor(&light, DEFAULT_SPECTRUM_FLOOR), DEFAULT_SPECTRUM_FLOOR);
+    assert!((relative_spectrum_floor(&heavy, DEFAULT_SPECTRUM_FLOOR) - 3e-2).abs() < TOLERANCE);
+}
+
+/// Test `log_pseudo_determinant` against hand-computed values for the path on three nodes.
+#[test]
+fn test_log_pseudo_determinant_path_graph() {
+    // The path 0 - 1 - 2 has Laplacian eigenvalues 0, 1 and 3
+    let laplacian = array![[1.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 1.0]];
+    let (eigvals, _) = call_eigendecomp(&laplacian).unwrap();
+
+    let log_pdet = log_pseudo_determinant(&eigvals, DEFAULT_SPECTRUM_FLOOR);
+    assert!((log_pdet - 3.0_f64.ln()).abs() < TOLERANCE);
+
+    // ln(n * τ) with a single spanning tree
+    assert!((log_pdet - (3.0_f64.ln() + log_spanning_tree_count(&eigvals))).abs() < TOLERANCE);
+
+    // Raising the tolerance drops eigenvalues, and dropping all of them leaves the empty product
+    assert!((log_pseudo_determinant(&eigvals, 2.0) - 3.0_f64.ln()).abs() < TOLERANCE);
+    assert_eq!(log_pseudo_determinant(&eigvals, 10.0), 0.0);
+}
+
+/// Test that tridiagonal matrices take the dstevd fast path and match the banded solver.
+#[test]
+fn test_tridiagonal_path_matches_banded() {
+    let n = 10;
+    let laplacian = path_laplacian(n);
+
+    assert_eq!(select_solver_policy(1, n as i32, DEFAULT_DENSE_RATIO), SolverPolicy::Tridiagonal);
+    // The fast path wins even where the dense ratio would pick dsyevd
+    assert_eq!(select_solver_policy(1, 2, DEFAULT_DENSE_RATIO), SolverPolicy::Tridiagonal);
+
+    let (eigvals, eigvecs, policy) = call_eigendecomp_with_options(&laplacian, &EigenOptions::default()).unwrap();
+    assert_eq!(policy, SolverPolicy::Tridiagonal);
+
+    let (banded_vals, _) = compute_eigenvalues_and_vectors_sym_band(&laplacian, 1).unwrap();
+    assert!(spectra_approx_eq(&eigvals, &banded_vals, TOLERANCE, REL_TOLERANCE));
+
+    // Path graph spectrum: 2 - 2 cos(pi k / n)
+    for (k, value) in eigvals.iter().enumerate() {
+        let expected = 2.0 - 2.0 * (std::f64::consts::PI * k as f64 / n as f64).cos();
+        assert!((value - expected).abs() < TOLERANCE);
+    }
+
+    let residual = laplacian.dot(&eigvecs) - &eigvecs * &eigvals;
+    assert!(residual.iter().all(|r| r.abs() < TOLERANCE));
+
+    let (direct_vals, _) = compute_eigenvalues_and_vectors_tridiagonal(&laplacian).unwrap();
+    assert_eq!(direct_vals, eigvals);
+}
+
+/// Test that `banded_to_display` lays out the 3x3 fixture as one row per matrix row and one column per diagonal.
+#[test]
+fn test_banded_to_display_layout() {
+    let matrix = array![
+        [1.0, 2.0, 0.0],
+        [2.0, 3.0, 4.0],
+        [0.0, 4.0, 5.0]
+    ];
+    let display = banded_to_display(&to_banded_format(&matrix, 1), 1);
+
+    assert_eq!(display.dim(), (3, 2));
+    assert_eq!(display.column(0).to_vec(), vec![1.0, 3.0, 5.0]);
+    assert_eq!(display[[0, 1]], 2.0);
+    assert_eq!(display[[1, 1]], 4.0);
+    assert!(display[[2, 1]].is_nan(), "Padding past the last row should be NaN.");
+}
+
+/// Test that a two-component graph has two trivial modes and the embedding skips both.
+#[test]
+fn test_spectral_embedding_skips_trivial_modes() {
+    // Two disjoint paths 0-1-2 and 3-4-5
+    let laplacian = array![
+        [1.0, -1.0, 0.0, 0.0, 0.0, 0.0],
+        [-1.0, 2.0, -1.0, 0.0, 0.0, 0.0],
+        [0.0, -1.0, 1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0, -1.0, 0.0],
+        [0.0, 0.0, 0.0, -1.0, 2.0, -1.0],
+        [0.0, 0.0, 0.0, 0.0, -1.0, 1.0]
+    ];
+    let (eigvals, eigvecs) = call_eigendecomp(&laplacian).unwrap();
+    assert_eq!(count_trivial_modes(&eigvals, 1e-9), 2);
+
+    let embedding = spectral_embedding(&eigvals, &eigvecs, 2, 1e-9);
+    assert_eq!(embedding.dim(), (6, 2));
+    for (k, column) in embedding.columns().into_iter().enumerate() {
+        // Each column is an eigenvector of eigenvalue 1, orthogonal to both component indicators
+        let image = laplacian.dot(&column);
+        assert!((&image - &column).iter().all(|x| x.abs() < TOLERANCE), "column {} is not a λ = 1 mode", k);
+        assert!(column.slice(s![..3]).sum().abs() < TOLERANCE);
+        assert!(column.slice(s![3..]).sum().abs() < TOLERANCE);
+    }
+}
+
+/// Test that the dsbevd workspace query returns a positive size that grows with eigenvectors.
+#[test]
+fn test_optimal_workspace_query() {
+    let with_vectors = optimal_workspace(10, 2, 'V').unwrap();
+    let values_only = optimal_workspace(10, 2, 'N').unwrap();
+    assert!(values_only > 0);
+    assert!(with_vectors >= values_only);
+    assert!(with_vectors >= 1 + 5 * 10 + 2 * 10 * 10);
+
+    // Bad parameters are errors rather than panics
+    assert_eq!(optimal_workspace(10, 2, 'X').unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    let too_large = usize::try_from(i64::from(i32::MAX) + 1).unwrap();
+    assert_eq!(optimal_workspace(too_large, 2, 'V').unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
+
+/// Test that parallel per-component decomposition matches the serial one and the full spectrum.
+#[test]
+fn test_decompose_by_components_parallel_matches_serial() {
+    // Components {0, 2, 5} (path 0 - 2 - 5), {1, 4} (an edge) and {3} (isolated), interleaved
+    let mut adjacency = ndarray::Array2::<f64>::zeros((6, 6));
+    for (u, v) in [(0, 2), (2, 5), (1, 4)] {
+        adjacency[[u, v]] = 1.0;
+        adjacency[[v, u]] = 1.0;
+    }
+    let laplacian = &ndarray::Array2::<f64>::from_diag(&adjacency.sum_axis(ndarray::Axis(1))) - &adjacency;
+
+    let serial = decompose_by_components(&laplacian).unwrap();
+    let parallel = decompose_by_components_parallel(&laplacian).unwrap();
+    assert_eq!(serial, parallel);
+
+    let nodes: Vec<Vec<usize>> = parallel.iter().map(|c| c.nodes.clone()).collect();
+    assert_eq!(nodes, vec![vec![0, 2, 5], vec![1, 4], vec![3]]);
+
+    // Each eigenvector, scattered back through its index map, is an eigenvector of the full Laplacian
+    for component in &parallel {
+        for (k, &lambda) in component.eigvals.iter().enumerate() {
+            let mut full = ndarray::Array1::<f64>::zeros(6);
+            for (row, &node) in component.nodes.iter().enumerate() {
+                full[node] = component.eigvecs[[row, k]];
+            }
+            let residual = laplacian.dot(&full) - &full * lambda;
+            assert!(residual.iter().all(|x| x.abs() < TOLERANCE));
+        }
+    }
+
+    let mut combined: Vec<f64> = parallel.iter().flat_map(|c| c.eigvals.to_vec()).collect();
+    combined.sort_by(f64::total_cmp);
+    let (full_vals, _) = call_eigendecomp(&laplacian).unwrap();
+    for (a, b) in combined.iter().zip(full_vals.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+}
+
+/// Test that the energy of the complete graph K_n is 2(n - 1).
+#[test]
+fn test_graph_energy_complete_graph() {
+    for n in [2, 4, 7] {
+        let complete = ndarray::Array2::<f64>::ones((n, n)) - ndarray::Array2::<f64>::eye(n);
+        let (eigvals, _) = call_eigendecomp(&complete).unwrap();
+        let expected = 2.0 * (n - 1) as f64;
+        assert!((graph_energy(&eigvals) - expected).abs() < TOLERANCE, "K_{}", n);
+    }
+}
+
+/// Test that Chung's diameter bound is at least the true diameter of path graphs (n - 1), and
+/// that disconnected and complete graphs get infinity and 1.
+#[test]
+fn test_spectral_diameter_bound_on_paths() {
+    for n in [3, 6, 12] {
+        let mut adjacency = ndarray::Array2::<f64>::zeros((n, n));
+        for i in 0..n - 1 {
+            adjacency[[i, i + 1]] = 1.0;
+            adjacency[[i + 1, i]] = 1.0;
+        }
+        let (eigvals, _) = call_eigendecomp(&graphome::laplacian::combinatorial_laplacian(&adjacency)).unwrap();
+        let bound = spectral_diameter_bound(&eigvals, n);
+        assert!(bound.is_finite() && bound >= (n - 1) as f64, "P_{}: bound {}", n, bound);
+    }
+
+    let disconnected = array![0.0, 0.0, 2.0, 2.0];
+    assert_eq!(spectral_diameter_bound(&disconnected, 4), f64::INFINITY);
+    let complete = array![0.0, 4.0, 4.0, 4.0];
+    assert_eq!(spectral_diameter_bound(&complete, 4), 1.0);
+}
+
+/// Test the Kirchhoff index of P_3 (resistances 1, 1 and 2) and of K_4 (six unit-conductance
+/// pairs at resistance 1/2).
+#[test]
+fn test_kirchhoff_index_small_graphs() {
+    let path = array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+    let (eigvals, _) = call_eigendecomp(&graphome::laplacian::combinatorial_laplacian(&path)).unwrap();
+    assert!((kirchhoff_index(&eigvals) - 4.0).abs() < TOLERANCE);
+
+    let complete = array![0.0, 4.0, 4.0, 4.0];
+    assert!((kirchhoff_index(&complete) - 3.0).abs() < TOLERANCE);
+
+    // Two disjoint edges: no path joins the components, so the resistance sum is infinite
+    let two_edges = array![
+        [0.0, 1.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+        [0.0, 0.0, 1.0, 0.0]
+    ];
+    let (eigvals, _) = call_eigendecomp(&graphome::laplacian::combinatorial_laplacian(&two_edges)).unwrap();
+    assert_eq!(kirchhoff_index(&eigvals), f64::INFINITY);
+}
+
+/// Test the Estrada index of the triangle and its log form on a spectrum that overflows f64.
+#[test]
+fn test_estrada_index_triangle() {
+    // The triangle's adjacency has eigenvalues 2, -1, -1
+    let triangle = array![[0.0, 1.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0]];
+    let (eigvals, _) = call_eigendecomp(&triangle).unwrap();
+
+    let expected = 2f64.exp() + 2.0 * (-1f64).exp();
+    assert!((estrada_index(&eigvals) - expected).abs() < TOLERANCE);
+    assert!((log_estrada_index(&eigvals) - expected.ln()).abs() < TOLERANCE);
+
+    let large = array![1000.0, 999.0, 0.0];
+    assert!(estrada_index(&large).is_infinite());
+    let expected_log = 1000.0 + (1.0 + (-1f64).exp()).ln();
+    assert!((log_estrada_index(&large) - expected_log).abs() < TOLERANCE);
+}
+
+/// Test that the dsbtrd tridiagonal factor has the same eigenvalues as the banded matrix.
+#[test]
+fn test_reduce_to_tridiagonal_preserves_spectrum() {
+    let n = 7;
+    let kd = 3;
+    let mut matrix = ndarray::Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        for j in i..n.min(i + kd + 1) {
+            let value = if i == j { 4.0 + i as f64 } else { 1.0 / (1 + i + 2 * j) as f64 };
+            matrix[[i, j]] = value;
+            matrix[[j, i]] = value;
+        }
+    }
+
+    let (diagonal, off_diagonal) = reduce_to_tridiagonal(&to_banded_format(&matrix, kd as i32), kd, n).unwrap();
+    assert_eq!(diagonal.len(), n);
+    assert_eq!(off_diagonal.len(), n - 1);
+
+    let mut tridiagonal = ndarray::Array2::<f64>::from_diag(&diagonal);
+    for i in 0..n - 1 {
+        tridiagonal[[i, i + 1]] = off_diagonal[i];
+        tridiagonal[[i + 1, i]] = off_diagonal[i];
+    }
+    let (tridiagonal_vals, _) = compute_eigenvalues_and_vectors_tridiagonal(&tridiagonal).unwrap();
+    let (banded_vals, _) = compute_eigenvalues_and_vectors_sym_band(&matrix, kd as i32).unwrap();
+    assert!(spectra_approx_eq(&tridiagonal_vals, &banded_vals, TOLERANCE, REL_TOLERANCE));
+}
+
+/// Test that the adjacency-spectrum NGEC of a path is finite and in range, and fails without edges.
+#[test]
+fn test_compute_ngec_from_adjacency_path() {
+    let path = array![
+        [0.0, 1.0, 0.0, 0.0],
+        [1.0, 0.0, 1.0, 0.0],
+        [0.0, 1.0, 0.0, 1.0],
+        [0.0, 0.0, 1.0, 0.0]
+    ];
+    let ngec = compute_ngec_from_adjacency(&path).unwrap();
+    assert!(ngec.is_finite());
+    assert!(ngec > 0.0 && ngec <= 1.0);
+
+    let empty = ndarray::Array2::<f64>::zeros((3, 3));
+    assert!(matches!(compute_ngec_from_adjacency(&empty), Err(EigenError::InvalidInput(_))));
+}
+
+/// Test that the f32 eigenvectors are within f32 rounding of the f64 ones.
+#[test]
+fn test_call_eigendecomp_f32_matches_f64() {
+    let laplacian = array![
+        [2.0, -1.0, 0.0, -1.0],
+        [-1.0, 3.0, -1.0, -1.0],
+        [0.0, -1.0, 1.0, 0.0],
+        [-1.0, -1.0, 0.0, 2.0]
+    ];
+    let (eigvals, eigvecs) = call_eigendecomp(&laplacian).expect("f64 decomposition failed");
+    let (eigvals_f32, eigvecs_f32) = call_eigendecomp_f32(&laplacian).expect("f32 decomposition failed");
+
+    assert_eq!(eigvals, eigvals_f32);
+    assert_eq!(eigvecs_f32.dim(), eigvecs.dim());
+    for (single, double) in eigvecs_f32.iter().zip(eigvecs.iter()) {
+        assert!((*single as f64 - double).abs() <= f32::EPSILON as f64 * double.abs().max(1e-30));
+    }
+}
+
+/// Test that streaming a CSV adjacency to a banded Laplacian matches banding the dense Laplacian.
+#[test]
+fn test_csv_laplacian_to_banded_matches_dense_route() {
+    // Cycle 0-1-2-3-4 with a chord 1-3 and a self-loop on 2: bandwidth 4
+    let mut adjacency = ndarray::Array2::<f64>::zeros((5, 5));
+    for (u, v, w) in [(0, 1, 1.0), (1, 2, 2.0), (2, 3, 1.0), (3, 4, 0.5), (4, 0, 1.0), (1, 3, 3.0)] {
+        adjacency[[u, v]] = w;
+        adjacency[[v, u]] = w;
+    }
+    adjacency[[2, 2]] = 1.5;
+    let file = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+    save_array_to_csv_dsbevd(&adjacency, file.path()).expect("Failed to write CSV");
+
+    let kd = csv_max_band(file.path()).expect("Failed to scan the bandwidth");
+    assert_eq!(kd as i32, max_band(&adjacency));
+
+    let laplacian = graphome::laplacian::combinatorial_laplacian(&adjacency);
+    for kd in [kd, kd + 1] {
+        let streamed = csv_laplacian_to_banded(file.path(), kd, LaplacianKind::Combinatorial)
+            .expect("Failed to band the CSV");
+        assert_eq!(streamed, to_banded_format(&laplacian, kd as i32));
+    }
+
+    for kind in [LaplacianKind::Normalized, LaplacianKind::Signless] {
+        let streamed = csv_laplacian_to_banded(file.path(), kd, kind).expect("Failed to band the CSV");
+        let dense = to_banded_format(&graphome::laplacian::build_laplacian(&adjacency, kind), kd as i32);
+        for (s, d) in streamed.iter().zip(dense.iter()) {
+            assert!((s - d).abs() <= 1e-12, "{} Laplacian: streamed {} vs dense {}", kind, s, d);
+        }
+    }
+
+    assert!(matches!(
+        csv_laplacian_to_banded(file.path(), 2, LaplacianKind::Combinatorial),
+        Err(CsvError::Shape(_))
+    ));
+    assert!(matches!(
+        csv_laplacian_to_banded(file.path(), kd, LaplacianKind::RandomWalk),
+        Err(CsvError::Io(error)) if error.kind() == std::io::ErrorKind::InvalidInput
+    ));
+}
+
+/// Test that the 4-cycle's Laplacian spectrum {0, 2, 2, 4} has one 2-fold multiplet.
+#[test]
+fn test_degeneracy_structure_cycle() {
+    let laplacian = array![
+        [2.0, -1.0, 0.0, -1.0],
+        [-1.0, 2.0, -1.0, 0.0],
+        [0.0, -1.0, 2.0, -1.0],
+        [-1.0, 0.0, -1.0, 2.0]
+    ];
+    let (eigvals, _) = call_eigendecomp(&laplacian).expect("Eigendecomposition failed");
+
+    let structure = degeneracy_structure(&eigvals, 1e-8);
+    let multiplicities: Vec<usize> = structure.iter().map(|&(_, m)| m).collect();
+    assert_eq!(multiplicities, vec![1, 2, 1]);
+    for (&(value, _), expected) in structure.iter().zip([0.0, 2.0, 4.0]) {
+        assert!((value - expected).abs() < TOLERANCE, "Multiplet at {} instead of {}", value, expected);
+    }
+
+    assert!(degeneracy_structure(&array![], 1e-8).is_empty());
+}
+
+/// Test that two solvers agree on the degenerate eigenvectors of a 6-cycle after canonicalization.
+#[test]
+fn test_canonicalize_degenerate_across_solvers() {
+    // The 6-cycle's Laplacian spectrum is {0, 1, 1, 3, 3, 4}
+    let n = 6;
+    let laplacian = cycle_laplacian(n);
+
+    let (lapack_vals, mut lapack_vecs) = call_eigendecomp(&laplacian).unwrap();
+    let (sym_vals, sym_vecs) = compute_eigenvalues_and_vectors_sym(&laplacian).unwrap();
+    let sym_vals = ndarray::Array1::from(sym_vals.as_slice().to_vec());
+    let mut sym_vecs = ndarray::Array2::from_shape_fn((n, n), |(i, j)| sym_vecs[(i, j)]);
+
+    canonicalize_degenerate(&lapack_vals, &mut lapack_vecs, 1e-8);
+    canonicalize_degenerate(&sym_vals, &mut sym_vecs, 1e-8);
+
+    for multiplet in [1..3, 3..5] {
+        let lapack = lapack_vecs.slice(s![.., multiplet.clone()]);
+        let sym = sym_vecs.slice(s![.., multiplet]);
+        assert!(
+            lapack.iter().zip(sym.iter()).all(|(a, b)| (a - b).abs() < TOLERANCE),
+            "Degenerate bases differ:\n{}\n{}",
+            lapack,
+            sym
+        );
+        // Still an orthonormal eigenbasis
+        assert!((lapack.t().dot(&lapack) - ndarray::Array2::<f64>::eye(2)).iter().all(|v| v.abs() < TOLERANCE));
+    }
+}
+
+/// Test that in-place banding matches `to_banded_format` for C- and Fortran-order inputs.
+#[test]
+fn test_into_banded_format_matches_to_banded_format() {
+    let n = 7;
+    let matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+        let offset = i.abs_diff(j);
+        if offset <= 2 {
+            1.0 + (i + j) as f64 + offset as f64 * 0.5
+        } else {
+            0.0
+        }
+    });
+
+    for kd in [0, 1, 2, 3, n - 1, n + 2] {
+        let expected = to_banded_format(&matrix, kd as i32);
+        assert_eq!(into_banded_format(matrix.clone(), kd), expected, "C order, kd = {}", kd);
+
+        let fortran = ndarray::Array2::from_shape_vec(
+            ndarray::ShapeBuilder::f((n, n)),
+            matrix.t().iter().copied().collect(),
+        )
+        .unwrap();
+        assert_eq!(fortran, matrix);
+        assert!(!fortran.is_standard_layout());
+        assert_eq!(into_banded_format(fortran, kd), expected, "Fortran order, kd = {}", kd);
+    }
+}
+
+/// Test that the banded matvec matches the dense product, including the mirrored lower triangle.
+#[test]
+fn test_banded_matvec_matches_dense() {
+    let n = 6;
+    let kd = 2;
+    let matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+        if i.abs_diff(j) <= kd {
+            1.0 + (i * j) as f64 - i.abs_diff(j) as f64 * 0.75
+        } else {
+            0.0
+        }
+    });
+    let x = ndarray::Array1::from_iter((0..n).map(|i| 1.0 - 0.3 * i as f64));
+
+    let banded = to_banded_format(&matrix, kd as i32);
+    let y = banded_matvec(&banded, kd, n, &x);
+    let expected = matrix.dot(&x);
+    assert!(y.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < TOLERANCE), "{} != {}", y, expected);
+}
+
+/// Test that the block product matches `banded_matvec` applied column by column.
+#[test]
+fn test_banded_matmat_matches_columnwise_matvec() {
+    let n = 7;
+    let kd = 3;
+    let matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| {
+        if i.abs_diff(j) <= kd {
+            2.0 - 0.5 * i.abs_diff(j) as f64 + 0.1 * (i + j) as f64
+        } else {
+            0.0
+        }
+    });
+    let x = ndarray::Array2::from_shape_fn((n, 3), |(i, k)| ((i * 3 + k) as f64 * 0.9).sin());
+
+    let banded = to_banded_format(&matrix, kd as i32);
+    let y = banded_matmat(&banded, kd, n, &x);
+    assert_eq!(y.dim(), (n, 3));
+    for (k, column) in x.columns().into_iter().enumerate() {
+        let expected = banded_matvec(&banded, kd, n, &column.to_owned());
+        assert!(y.column(k).iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < TOLERANCE));
+    }
+}
+
+/// Test that the smallest-magnitude eigenvalue is the one nearest zero, not the most negative.
+#[test]
+fn test_smallest_magnitude_eigenvalue_straddling_zero() {
+    // Path P3 adjacency has spectrum {-sqrt(2), 0, sqrt(2)}; shifting by 0.1 puts 0.1 nearest zero
+    let shifted = array![[0.1, 1.0, 0.0], [1.0, 0.1, 1.0], [0.0, 1.0, 0.1]];
+    let value = smallest_magnitude_eigenvalue(&shifted).expect("Inverse iteration failed");
+    assert!((value - 0.1).abs() < TOLERANCE, "Got {}", value);
+
+    // Spectrum {-3, 0.5, 2}: the answer is 0.5, although -3 is algebraically smallest
+    let diagonal = ndarray::Array2::from_diag(&array![2.0, -3.0, 0.5]);
+    let value = smallest_magnitude_eigenvalue(&diagonal).expect("Inverse iteration failed");
+    assert!((value - 0.5).abs() < TOLERANCE, "Got {}", value);
+
+    // A singular matrix has 0 as its smallest-magnitude eigenvalue
+    let singular = array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+    assert!(smallest_magnitude_eigenvalue(&singular).unwrap().abs() < TOLERANCE);
+}
+
+/// Test that the banded shift-invert matches a full decomposition on a wider pentadiagonal matrix
+/// whose zero-free diagonal still forces row interchanges in the band LU.
+#[test]
+fn test_smallest_magnitude_eigenvalue_banded_matches_full() {
+    let n = 40;
+    let matrix = ndarray::Array2::from_shape_fn((n, n), |(i, j)| match i.abs_diff(j) {
+        0 => 0.05 * (i % 7) as f64 - 0.13,
+        1 => 1.0,
+        2 => -0.5,
+        _ => 0.0,
+    });
+    let (eigvals, _) = call_eigendecomp(&matrix).expect("Eigendecomposition failed");
+    let expected = eigvals.iter().copied().min_by(|a, b| a.abs().partial_cmp(&b.abs()).unwrap()).unwrap();
+
+    let value = smallest_magnitude_eigenvalue(&matrix).expect("Inverse iteration failed");
+    assert!((value - expected).abs() < 1e-8, "Got {}, expected {}", value, expected);
+}
+
+/// Test that a labeled CSV has a names header and index column, and round-trips.
+#[test]
+fn test_save_array_to_csv_labeled_round_trip() {
+    let mut gfa = tempfile::NamedTempFile::new().expect("Failed to create temp file");
+    std::io::Write::write_all(&mut gfa, b"S\tseg1\t*\nS\tseg2\t*\nS\tseg3\t*\nL\tseg1\t+\tseg2\t+\t0M\nL\tseg2\t+\tseg3\t+\t0M\n")
+        .expect("Failed to write GFA");
+    let (adjacency, names) = graphome::convert::load_gfa(gfa.path()).expect("Failed to load GFA");
+    let laplacian = graphome::laplacian::combinatorial_laplacian(&adjacency);
+
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path = dir.path().join("laplacian.csv");
+    save_array_to_csv_labeled(&laplacian, &names, &names, &path).expect("Failed to write labeled CSV");
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines[0], ",seg1,seg2,seg3");
+    assert!(lines[1].starts_with("seg1,"));
+    assert_eq!(lines.len(), 4);
+
+    let labeled = read_labeled_csv(&path).expect("Failed to read labeled CSV");
+    assert_eq!(labeled.matrix, laplacian);
+    assert_eq!(labeled.row_names, names);
+    assert_eq!(labeled.column_names, names);
+
+    // Eigenvectors keep node names on the rows and eigenvalue indices on the columns
+    let (_, eigvecs) = call_eigendecomp(&laplacian).unwrap();
+    let indices: Vec<String> = (0..3).map(|k| format!("v{}", k)).collect();
+    save_array_to_csv_labeled(&eigvecs, &names, &indices, &path).unwrap();
+    let labeled = read_labeled_csv(&path).unwrap();
+    assert_eq!(labeled.matrix, eigvecs);
+    assert_eq!((labeled.row_names, labeled.column_names), (names.clone(), indices));
+
+    assert!(save_array_to_csv_labeled(&laplacian, &names[..2], &names, &path).is_err());
+}
+
+/// Test that k-means recovers three separated clusters, reproducibly for a fixed seed.
+#[test]
+fn test_kmeans_separated_clusters() {
+    let centers = [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)];
+    let offsets = [(0.0, 0.0), (0.3, 0.1), (-0.2, 0.25), (0.1, -0.3)];
+    let mut points = ndarray::Array2::<f64>::zeros((12, 2));
+    for (i, (c, o)) in (0..3).flat_map(|c| offsets.iter().map(move |o| (c, o))).enumerate() {
+        points[[i, 0]] = centers[c].0 + o.0;
+        points[[i, 1]] = centers[c].1 + o.1;
+    }
+
+    for seed in [0, 1, 42, 12345] {
+        let labels = kmeans(&points, 3, seed);
+        assert_eq!(labels, vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2], "seed = {}", seed);
+        assert_eq!(labels, kmeans(&points, 3, seed));
+    }
+
+    // Two triangles joined by one edge split into the two triangles
+    let mut adjacency = ndarray::Array2::<f64>::zeros((6, 6));
+    for (u, v) in [(0, 1), (0, 2), (1, 2), (3, 4), (3, 5), (4, 5), (2, 3)] {
+        adjacency[[u, v]] = 1.0;
+        adjacency[[v, u]] = 1.0;
+    }
+    assert_eq!(spectral_clustering(&adjacency, 2, 7).unwrap(), vec![0, 0, 0, 1, 1, 1]);
+}
+
+/// Test that squared commute-time embedding distances on a path are `vol` times the resistance.
+#[test]
+fn test_commute_time_embedding_path() {
+    // Path 0 - 1 - 2 - 3: vol = 6 and the effective resistance between i and j is |i - j|
+    let laplacian = array![
+        [1.0, -1.0, 0.0, 0.0],
+        [-1.0, 2.0, -1.0, 0.0],
+        [0.0, -1.0, 2.0, -1.0],
+        [0.0, 0.0, -1.0, 1.0]
+    ];
+    let (eigvals, eigvecs) = call_eigendecomp(&laplacian).unwrap();
+    let embedding = commute_time_embedding(&eigvals, &eigvecs);
+    assert_eq!(embedding.dim(), (4, 3));
+
+    for i in 0..4 {
+        for j in 0..4 {
+            let diff = &embedding.row(i) - &embedding.row(j);
+            let commute_time = 6.0 * i.abs_diff(j) as f64;
+            assert!((diff.dot(&diff) - commute_time).abs() < TOLERANCE, "C({}, {}) = {}", i, j, diff.dot(&diff));
+        }
+    }
+}
+
+/// Test the tridiagonal fast path on a large path Laplacian: detection, storage and solver routing.
+#[test]
+fn test_tridiagonal_fast_path_large_path() {
+    let n = 1000;
+    let mut laplacian = path_laplacian(n);
+    assert!(is_tridiagonal(&laplacian));
+
+    // The slice-copied storage matches the element-wise definition for both triangles
+    let upper = to_banded_format(&laplacian, 1);
+    let lower = to_banded_format_uplo(&laplacian, 1, 'L').unwrap();
+    for j in 0..n {
+        assert_eq!(upper[[1, j]], laplacian[[j, j]]);
+        assert_eq!(lower[[0, j]], laplacian[[j, j]]);
+        if j > 0 {
+            assert_eq!(upper[[0, j]], laplacian[[j - 1, j]]);
+        }
+        if j + 1 < n {
+            assert_eq!(lower[[1, j]], laplacian[[j + 1, j]]);
+        }
+    }
+    assert_eq!(upper[[0, 0]], 0.0);
+    assert_eq!(lower[[1, n - 1]], 0.0);
+
+    let (eigvals, _, policy) = call_eigendecomp_with_options(&laplacian, &EigenOptions::default()).unwrap();
+    assert_eq!(policy, SolverPolicy::Tridiagonal);
+    let expected = 2.0 - 2.0 * (std::f64::consts::PI / n as f64).cos();
+    assert!((eigvals[1] - expected).abs() < TOLERANCE);
+
+    // One entry past the superdiagonal disables the fast path
+    laplacian[[0, 2]] = 0.5;
+    laplacian[[2, 0]] = 0.5;
+    assert!(!is_tridiagonal(&laplacian));
+}
+
+/// Test that solver eigenvectors pass the orthonormality check and scaled or mixed ones fail.
+#[test]
+fn test_check_orthonormal() {
+    let laplacian = array![[1.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 1.0]];
+    let (_, eigvecs) = call_eigendecomp(&laplacian).unwrap();
+    assert!(check_orthonormal(&eigvecs, 1e-10).is_ok());
+
+    let mut scaled = eigvecs.clone();
+    scaled.column_mut(1).mapv_inplace(|v| v * 1.01);
+    match check_orthonormal(&scaled, 1e-10) {
+        Err(EigenError::InvalidInput(message)) => assert!(message.contains("column 1 "), "{}", message),
+        other => panic!("Expected a failure, got {:?}", other),
+    }
+
+    let mut mixed = eigvecs.clone();
+    let first = eigvecs.column(0).to_owned();
+    mixed.column_mut(2).scaled_add(0.1, &first);
+    match check_orthonormal(&mixed, 1e-3) {
+        Err(EigenError::InvalidInput(message)) => assert!(message.contains("columns 0 and 2"), "{}", message),
+        other => panic!("Expected a failure, got {:?}", other),
+    }
+}
+
+/// Test that the spectral radius of K_n is n - 1, and of a bipartite star is sqrt(leaves).
+#[test]
+fn test_spectral_radius_complete_graph() {
+    for n in [2, 5, 10] {
+        let complete = ndarray::Array2::<f64>::ones((n, n)) - ndarray::Array2::<f64>::eye(n);
+        let radius = spectral_radius(&complete, 1e-12).unwrap();
+        assert!((radius - (n - 1) as f64).abs() < TOLERANCE, "K_{}: {}", n, radius);
+    }
+
+    // The star K_{1,4} has spectrum {-2, 0, 0, 0, 2}
+    let mut star = ndarray::Array2::<f64>::zeros((5, 5));
+    for leaf in 1..5 {
+        star[[0, leaf]] = 1.0;
+        star[[leaf, 0]] = 1.0;
+    }
+    assert!((spectral_radius(&star, 1e-12).unwrap() - 2.0).abs() < TOLERANCE);
+}
+
+/// Test that every `--format` writer produces a non-empty file that reads back as the same data,
+/// and that the formats without names reject labeled output.
+#[test]
+fn test_output_format_dispatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let matrix = array![[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 1.5]];
+    let vector = array![0.0, 1.25, 3.5];
+
+    for format in [OutputFormat::Csv, OutputFormat::Npy, OutputFormat::Mtx, OutputFormat::Json] {
+        let matrix_path = dir.path().join(format!("matrix.{}", format.extension()));
+        let vector_path = dir.path().join(format!("vector.{}", format.extension()));
+        save_matrix(&matrix, &matrix_path, format).unwrap();
+        save_vector(&vector, &vector_path, format).unwrap();
+        assert!(fs::metadata(&matrix_path).unwrap().len() > 0, "{:?}", format);
+        assert!(fs::metadata(&vector_path).unwrap().len() > 0, "{:?}", format);
+
+        match format {
+            OutputFormat::Csv => {
+                assert_eq!(graphome::eigen::read_array_from_csv(&matrix_path).unwrap(), matrix);
+                let row = graphome::eigen::read_array_from_csv(&vector_path).unwrap();
+                assert_eq!(row.row(0), vector);
+            }
+            OutputFormat::Npy => {
+                assert_eq!(load_array_from_npy(&matrix_path).unwrap(), matrix);
+                let header = fs::read(&vector_path).unwrap();
+                assert!(String::from_utf8_lossy(&header[..128]).contains("'shape': (3,)"));
+            }
+            OutputFormat::Mtx => {
+                let contents = fs::read_to_string(&matrix_path).unwrap();
+                let lines: Vec<&str> = contents.lines().collect();
+                assert_eq!(lines[0], "%%MatrixMarket matrix coordinate real general");
+                assert_eq!(lines[1], "3 3 7");
+                assert_eq!(lines.len(), 2 + 7);
+                assert!(lines.contains(&"3 3 1.5"));
+                let contents = fs::read_to_string(&vector_path).unwrap();
+                assert_eq!(contents.lines().nth(1), Some("3 1 2"));
+            }
+            OutputFormat::Json => {
+                assert_eq!(fs::read_to_string(&vector_path).unwrap().trim(), "[0, 1.25, 3.5]");
+                let contents = fs::read_to_string(&matrix_path).unwrap();
+                let flat: String = contents.chars().filter(|c| !c.is_whitespace()).collect();
+                assert_eq!(flat, "[[2,-1,0],[-1,2,-1],[0,-1,1.5]]");
+            }
+        }
+    }
+
+    for (format, supported) in [
+        (OutputFormat::Csv, true),
+        (OutputFormat::Npy, false),
+        (OutputFormat::Mtx, false),
+        (OutputFormat::Json, true),
+    ] {
+        assert!(format.check_supports(OutputData::Matrix).is_ok());
+        assert!(format.check_supports(OutputData::Vector).is_ok());
+        assert_eq!(format.check_supports(OutputData::LabeledMatrix).is_ok(), supported, "{:?}", format);
+    }
+}
+
+/// Test that inverse iteration from a shift near λ2 recovers the Fiedler pair of a path graph.
+#[test]
+fn test_inverse_iteration_fiedler_vector() {
+    let n = 8;
+    let laplacian = path_laplacian(n);
+    let (eigvals, eigvecs) = call_eigendecomp(&laplacian).unwrap();
+
+    // λ2 = 2 - 2 cos(π / 8) ≈ 0.152; λ3 ≈ 0.586
+    let (value, vector) = inverse_iteration(&laplacian, 0.2, 50, 1e-10).unwrap();
+    assert!((value - eigvals[1]).abs() < TOLERANCE);
+    assert!((vector.dot(&vector) - 1.0).abs() < TOLERANCE);
+    assert!((vector.dot(&eigvecs.column(1)).abs() - 1.0).abs() < TOLERANCE);
+
+    // A shift exactly on an eigenvalue still converges to it
+    let (value, _) = inverse_iteration(&laplacian, eigvals[1], 50, 1e-10).unwrap();
+    assert!((value - eigvals[1]).abs() < TOLERANCE);
+
+    // One step from a shift midway between λ2 and λ3 is not enough
+    assert!(matches!(
+        inverse_iteration(&laplacian, (eigvals[1] + eigvals[2]) / 2.0, 1, 1e-10),
+        Err(EigenError::NotConverged { iterations: 1, .. })
+    ));
+}
+
+/// Test that a spawned decomposition gives the synchronous result through both `join` and
+/// polling with `try_result`.
+#[test]
+fn test_spawn_eigendecomp_matches_synchronous() {
+    let n = 40;
+    let laplacian = cycle_laplacian(n);
+    let (expected_vals, expected_vecs) = call_eigendecomp(&laplacian).unwrap();
+
+    let (eigvals, eigvecs) = spawn_eigendecomp(laplacian.clone()).join().unwrap();
+    assert_eq!(eigvals, expected_vals);
+    assert_eq!(eigvecs, expected_vecs);
+
+    let mut handle = spawn_eigendecomp(laplacian);
+    let polled = loop {
+        if let Some(result) = handle.try_result() {
+            break result.as_ref().unwrap().0.clone();
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    };
+    assert_eq!(polled, expected_vals);
+    assert!(handle.try_result().is_some());
+    assert_eq!(handle.join().unwrap().0, expected_vals);
+}
+
+/// Test that a job still running at its time limit reports a timeout promptly, that a generous
+/// limit returns the normal result, and that a failed decomposition keeps its `io::ErrorKind`.
+#[test]
+fn test_call_eigendecomp_with_timeout() {
+    // The worker blocks until the test releases it, so it cannot finish before the limit
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+    let worker_barrier = std::sync::Arc::clone(&barrier);
+    let started = std::time::Instant::now();
+    let limit = std::time::Duration::from_millis(20);
+    let blocked = move || {
+        worker_barrier.wait();
+        call_eigendecomp(&array![[1.0]])
+    };
+    match run_eigendecomp_with_timeout(blocked, limit) {
+        Err(EigenError::Timeout { limit: reported }) => assert_eq!(reported, limit),
+        other => panic!("Expected a timeout, got {:?}", other.map(|(vals, _)| vals.len())),
+    }
+    assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    barrier.wait();
+
+    let small = array![[2.0, -1.0], [-1.0, 2.0]];
+    let (eigvals, _) = call_eigendecomp_with_timeout(&small, std::time::Duration::from_secs(60)).unwrap();
+    assert!((eigvals[0] - 1.0).abs() < TOLERANCE && (eigvals[1] - 3.0).abs() < TOLERANCE);
+
+    let failing = || Err(std::io::Error::new(std::io::ErrorKind::NotFound, "missing input"));
+    let error = run_eigendecomp_with_timeout(failing, std::time::Duration::from_secs(60)).unwrap_err();
+    assert_eq!(
+        error,
+        EigenError::Io {
+            kind: std::io::ErrorKind::NotFound,
+            message: "missing input".to_string()
+        }
+    );
+    assert_eq!(std::io::Error::from(error).kind(), std::io::ErrorKind::NotFound);
+
+    let not_converged = EigenError::NotConverged { iterations: 3, residual: 0.5 };
+    assert_eq!(EigenError::from(std::io::Error::from(not_converged.clone())), not_converged);
+}
+
+/// Test that a bandwidth above `max_band` is rejected before decomposing, and that the cap does
+/// not affect matrices within it.
+#[test]
+fn test_max_band_guard_errors_early() {
+    // A cycle's wrap-around edge puts an entry in the corner, so kd = n - 1
+    let n = 30;
+    let laplacian = cycle_laplacian(n);
+
+    let capped = EigenOptions { max_band: Some(4), ..EigenOptions::default() };
+    let error = call_eigendecomp_with_options(&laplacian, &capped).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(error.to_string().contains("kd = 29"), "{}", error);
+
+    let roomy = EigenOptions { max_band: Some(n), ..EigenOptions::default() };
+    let (eigvals, _, _) = call_eigendecomp_with_options(&laplacian, &roomy).unwrap();
+    assert_eq!(eigvals, call_eigendecomp(&laplacian).unwrap().0);
+
+    assert!(check_max_band(3, None).is_ok());
+    assert!(check_max_band(3, Some(3)).is_ok());
+    assert!(check_max_band(4, Some(3)).is_err());
+}
+
+/// Test that the bisection iterator yields the full spectrum in ascending order, matching a
+/// dense decomposition, including a degenerate spectrum and the banded entry point.
+#[test]
+fn test_eigenvalue_iter_matches_decomposition() {
+    // An irregular weighted tridiagonal matrix
+    let n = 25;
+    let diag = ndarray::Array1::from_iter((0..n).map(|i| 2.0 + (i as f64 * 0.7).sin()));
+    let off = ndarray::Array1::from_iter((0..n - 1).map(|i| -1.0 - 0.3 * (i as f64 * 1.3).cos()));
+    let mut matrix = ndarray::Array2::<f64>::from_diag(&diag);
+    for i in 0..n - 1 {
+        matrix[[i, i + 1]] = off[i];
+        matrix[[i + 1, i]] = off[i];
+    }
+    let (expected, _) = compute_eigenvalues_and_vectors_sym(&matrix).unwrap();
+    let mut expected = expected.iter().copied().collect::<Vec<f64>>();
+    expected.sort_by(f64::total_cmp);
+
+    let iter = EigenvalueIter::new(diag, &off);
+    assert_eq!(iter.len(), n);
+    let values: Vec<f64> = iter.collect();
+    assert_eq!(values.len(), n);
+    assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+    for (value, exact) in values.iter().zip(&expected) {
+        assert!((value - exact).abs() < 1e-12, "{} vs {}", value, exact);
+    }
+
+    // The Laplacian of K_4 in banded storage has spectrum {0, 4, 4, 4}
+    let laplacian = ndarray::Array2::<f64>::eye(4) * 4.0 - ndarray::Array2::<f64>::ones((4, 4));
+    let banded = to_banded_format(&laplacian, 3);
+    let values: Vec<f64> = EigenvalueIter::from_banded(&banded, 3, 4).unwrap().collect();
+    for (value, exact) in values.iter().zip([0.0, 4.0, 4.0, 4.0]) {
+        assert!((value - exact).abs() < TOLERANCE, "{:?}", values);
+    }
+}
+
+/// Test that large eigenvalues a few ULPs apart pass under relative tolerance but not under a
+/// tight absolute one, and that small eigenvalues fall back to the absolute tolerance.
+#[test]
+fn test_spectra_approx_eq_tolerances() {
+    let a = ndarray::Array1::from(vec![0.0, 1.0e8, 2.0e8]);
+    let b = ndarray::Array1::from(vec![1.0e-12, 1.0e8 + 1.0e-6, 2.0e8 - 2.0e-6]);
+
+    assert!(!spectra_approx_eq(&a, &b, 1e-9, 0.0));
+    assert!(spectra_approx_eq(&a, &b, 1e-9, 1e-12));
+    // With no absolute tolerance, zero versus 1e-12 passes only once the relative tolerance reaches
+    // 1, since their gap equals the larger of the two
+    assert!(!spectra_approx_eq(&a, &b, 0.0, 1e-12));
+    assert!(spectra_approx_eq(&a, &b, 0.0, 1.0));
+
+    assert!(!spectra_approx_eq(&a, &a.slice(ndarray::s![..2]).to_owned(), 1.0, 1.0));
+    let with_nan = ndarray::Array1::from(vec![0.0, f64::NAN, 2.0e8]);
+    assert!(!spectra_approx_eq(&a, &with_nan, 1.0, 1.0));
+}
+
+/// Test that a bridge has spanning-edge centrality 1 while the edges of a triangle, each missing
+/// from one of its three spanning trees, score 2/3.
+#[test]
+fn test_spanning_edge_centrality_bridge_versus_cycle() {
+    // Triangle 0 - 1 - 2 with the bridge 2 - 3 (weight 2, which must not matter for a bridge)
+    let laplacian = array![
+        [2.0, -1.0, -1.0, 0.0],
+        [-1.0, 2.0, -1.0, 0.0],
+        [-1.0, -1.0, 4.0, -2.0],
+        [0.0, 0.0, -2.0, 2.0]
+    ];
+    let (eigvals, eigvecs) = call_eigendecomp(&laplacian).unwrap();
+
+    let edges = [(0, 1), (1, 2), (0, 2), (2, 3)];
+    let centrality = spanning_edge_centrality(&eigvals, &eigvecs, &edges);
+    for k in 0..3 {
+        assert!((centrality[k] - 2.0 / 3.0).abs() < TOLERANCE, "{}", centrality);
+    }
+    assert!((centrality[3] - 1.0).abs() < TOLERANCE, "{}", centrality);
+    // Foster's theorem: the scores sum to n - 1
+    assert!((centrality.sum() - 3.0).abs() < TOLERANCE);
+
+    let non_edge = spanning_edge_centrality(&eigvals, &eigvecs, &[(0, 3)]);
+    assert!(non_edge[0].abs() < TOLERANCE);
+}
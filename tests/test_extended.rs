@@ -0,0 +1,67 @@
+// tests/test_extended.rs
+
+//! Unit tests for the extended module (requires the `f128` feature).
+
+#![cfg(feature = "f128")]
+
+use graphome::eigen::call_eigendecomp;
+use graphome::extended::{symmetric_eigenvalues_extended, DoubleDouble};
+use ndarray::prelude::*;
+
+/// Test the double-double arithmetic against values f64 cannot represent.
+#[test]
+fn test_double_double_arithmetic() {
+    let one = DoubleDouble::ONE;
+    let tiny = DoubleDouble::from(1e-20);
+
+    let sum = one + tiny;
+    assert_eq!(sum.hi, 1.0);
+    assert_eq!(sum.lo, 1e-20);
+    assert_eq!((sum - one).to_f64(), 1e-20);
+
+    let third = one / DoubleDouble::from(3.0);
+    let back = third * DoubleDouble::from(3.0) - one;
+    assert!(back.abs().to_f64() < 1e-31);
+
+    let root = DoubleDouble::from(2.0).sqrt();
+    let squared = root * root - DoubleDouble::from(2.0);
+    assert!(squared.abs().to_f64() < 1e-31);
+}
+
+/// Test that eigenvalues coinciding in f64 are separated in extended precision.
+#[test]
+fn test_extended_separates_f64_degenerate_eigenvalues() {
+    // Eigenvalues 1 - sqrt(2) δ, 1 and 1 + sqrt(2) δ, which all round to 1 in f64
+    let delta = 1e-20;
+    let matrix = array![[1.0, delta, delta], [delta, 1.0, 0.0], [delta, 0.0, 1.0]];
+
+    let (f64_vals, _) = call_eigendecomp(&matrix).unwrap();
+    // In f64 the rounding error of each eigenvalue dwarfs the 1e-20 gap
+    for value in f64_vals.iter() {
+        assert!((value - 1.0).abs() < 1e-12, "f64 eigenvalue {:e} is not 1.", value);
+    }
+
+    let extended = symmetric_eigenvalues_extended(&matrix).unwrap();
+    let offsets: Vec<f64> = extended.iter().map(|v| (*v - DoubleDouble::ONE).to_f64()).collect();
+    let expected = [-2f64.sqrt() * delta, 0.0, 2f64.sqrt() * delta];
+    for (offset, expected) in offsets.iter().zip(expected.iter()) {
+        assert!((offset - expected).abs() < 1e-30, "offset {:e} vs {:e}", offset, expected);
+    }
+}
+
+/// Test that extended precision agrees with LAPACK on an ordinary Laplacian.
+#[test]
+fn test_extended_matches_lapack_on_path_laplacian() {
+    let laplacian = array![
+        [1.0, -1.0, 0.0, 0.0],
+        [-1.0, 2.0, -1.0, 0.0],
+        [0.0, -1.0, 2.0, -1.0],
+        [0.0, 0.0, -1.0, 1.0]
+    ];
+
+    let (f64_vals, _) = call_eigendecomp(&laplacian).unwrap();
+    let extended = symmetric_eigenvalues_extended(&laplacian).unwrap();
+    for (a, b) in extended.iter().zip(f64_vals.iter()) {
+        assert!((a.to_f64() - b).abs() < 1e-12);
+    }
+}
@@ -6,6 +6,10 @@ use graphome::eigen::{
     save_nalgebra_matrix_to_csv,
     save_nalgebra_vector_to_csv,
     save_array_to_csv_dsbevd,
+    save_array_to_npy,
+    read_array_from_csv,
+    to_banded_format,
+    EmbeddingScaling,
 };
 
 use nalgebra::{DMatrix, DVector, SymmetricEigen};
@@ -24,21 +28,6 @@ mod tests {
     use std::fs::File;
     use std::io::Read;
 
-    /// Helper function to read edges from the binary edge list file
-    fn read_edges_from_file(path: &std::path::Path) -> io::Result<HashSet<(u32, u32)>> {
-        let mut edges = HashSet::new();
-        let mut file = File::open(path)?;
-        let mut buffer = [0u8; 8];
-
-        while let Ok(_) = file.read_exact(&mut buffer) {
-            let from = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
-            let to = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]);
-            edges.insert((from, to));
-        }
-
-        Ok(edges)
-    }
-
     /// Helper function to create a mock .gam file with given edges
     fn create_mock_gam_file<P: AsRef<Path>>(path: P, edges: &[(u32, u32)]) -> io::Result<()> {
         let file = File::create(path)?;
@@ -206,7 +195,7 @@ mod tests {
 
         // Load eigenvectors
         let eigenvectors_csv = output_analysis.path().with_extension("eigenvectors.csv");
-        let eigenvectors = load_csv_as_matrix(&eigenvectors_csv)?;
+        let _eigenvectors = load_csv_as_matrix(&eigenvectors_csv)?;
 
         Ok(())
     }
@@ -228,7 +217,7 @@ mod tests {
         let nrows = matrix.len();
         let ncols = if nrows > 0 { matrix[0].len() } else { 0 };
         Array2::from_shape_vec((nrows, ncols), matrix.into_iter().flatten().collect())
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(io::Error::other)
     }
 
     /// Helper function to load a CSV file as a nalgebra::DVector<f64>
@@ -453,7 +442,7 @@ mod tests {
             let v = eigvecs.column(i);
 
             // Compute L * v
-            let lv = &laplacian * &v;
+            let lv = &laplacian * v;
 
             // Compute lambda * v
             let lambda_v: DVector<f64> = v * lambda; // Explicitly specifying the type for lambda_v
@@ -481,7 +470,7 @@ mod tests {
     fn test_save_matrix_to_csv() -> io::Result<()> {
         // Create a temporary matrix
         let matrix = Array2::from_shape_vec((2, 2), vec![0.0, 1.0, 1.0, 0.0])
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            .map_err(io::Error::other)?;
 
         // Output file
         let output_file = NamedTempFile::new()?;
@@ -560,4 +549,623 @@ mod tests {
 
         Ok(())
     }
+
+    /// Test that the progress callback sees every phase in order, ending at completion
+    #[test]
+    fn test_extract_progress_reports_phases() -> io::Result<()> {
+        let mut gfa_file = NamedTempFile::new()?;
+        writeln!(gfa_file, "S\t1\t*")?;
+        writeln!(gfa_file, "S\t2\t*")?;
+        writeln!(gfa_file, "L\t1\t+\t2\t+\t10M")?;
+        let output_gam = NamedTempFile::new()?;
+        convert_gfa_to_edge_list(gfa_file.path(), output_gam.path())?;
+        let output_analysis = NamedTempFile::new()?;
+
+        let mut reports: Vec<(String, f64)> = Vec::new();
+        extract::extract_and_analyze_submatrix_with_progress(
+            output_gam.path(),
+            0,
+            1,
+            output_analysis.path(),
+            &ExtractOptions::default(),
+            &mut |phase: &str, fraction: f64| reports.push((phase.to_string(), fraction)),
+        )?;
+
+        let phases: Vec<&str> = reports.iter().map(|(phase, _)| phase.as_str()).collect();
+        let mut expected: Vec<&str> = EXTRACT_PHASES.to_vec();
+        expected.push("done");
+        assert_eq!(phases, expected);
+        assert!(reports.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert_eq!(reports.last().unwrap().1, 1.0);
+
+        Ok(())
+    }
+
+    /// Test that `max_band` is checked against the edges, so nothing is written for a wide band
+    #[test]
+    fn test_extract_max_band_rejects_before_saving() -> io::Result<()> {
+        // Edges 0-1 and 1-5 give a bandwidth of 4
+        let gam_file = NamedTempFile::new()?;
+        create_mock_gam_file(gam_file.path(), &[(0, 1), (1, 5)])?;
+        let output = NamedTempFile::new()?;
+        let laplacian_path = output.path().with_extension("laplacian.csv");
+
+        let capped = ExtractOptions { max_band: Some(3), quiet: true, ..Default::default() };
+        let error = extract::extract_and_analyze_submatrix_with_progress(
+            gam_file.path(),
+            0,
+            5,
+            output.path(),
+            &capped,
+            &mut NoProgress,
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(!laplacian_path.exists());
+
+        let roomy = ExtractOptions { max_band: Some(4), quiet: true, ..Default::default() };
+        extract::extract_and_analyze_submatrix_with_progress(
+            gam_file.path(),
+            0,
+            5,
+            output.path(),
+            &roomy,
+            &mut NoProgress,
+        )?;
+        assert!(laplacian_path.exists());
+        std::fs::remove_file(&laplacian_path)?;
+        for extension in ["eigenvectors.csv", "eigenvalues.csv"] {
+            std::fs::remove_file(output.path().with_extension(extension))?;
+        }
+
+        Ok(())
+    }
+
+    /// Test that the Fiedler ranking of a path puts its endpoints at the two extremes
+    #[test]
+    fn test_rank_gfa_by_fiedler_path_endpoints() -> io::Result<()> {
+        // Path a - b - c - d - e, with segments declared out of order
+        let mut gfa_file = NamedTempFile::new()?;
+        for name in ["c", "a", "e", "b", "d"] {
+            writeln!(gfa_file, "S\t{}\t*", name)?;
+        }
+        for (from, to) in [("a", "b"), ("b", "c"), ("c", "d"), ("d", "e")] {
+            writeln!(gfa_file, "L\t{}\t+\t{}\t+\t0M", from, to)?;
+        }
+        let output_tsv = NamedTempFile::new()?;
+
+        extract::rank_gfa_by_fiedler(gfa_file.path(), output_tsv.path())?;
+
+        let contents = std::fs::read_to_string(output_tsv.path())?;
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "segment\tfiedler");
+        assert_eq!(lines.len(), 6);
+
+        let ranking: Vec<(&str, f64)> = lines[1..]
+            .iter()
+            .map(|line| {
+                let (name, value) = line.split_once('\t').unwrap();
+                (name, value.parse().unwrap())
+            })
+            .collect();
+        assert!(ranking.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        let ends: HashSet<&str> = [ranking[0].0, ranking[4].0].into_iter().collect();
+        assert_eq!(ends, ["a", "e"].into_iter().collect());
+        assert_eq!(ranking[2].0, "c");
+        assert!(ranking[2].1.abs() < 1e-6);
+
+        Ok(())
+    }
+
+    /// Test that spectral distances across three GFA files form a symmetric labeled 3x3 matrix
+    #[test]
+    fn test_write_spectral_distances_three_graphs() -> io::Result<()> {
+        let dir = tempdir()?;
+        // A path, a triangle, and a larger path, so every pair differs; the .txt file is ignored
+        std::fs::write(dir.path().join("a.gfa"), "S\t1\t*\nS\t2\t*\nS\t3\t*\nL\t1\t+\t2\t+\t0M\nL\t2\t+\t3\t+\t0M\n")?;
+        std::fs::write(
+            dir.path().join("b.gfa"),
+            "S\t1\t*\nS\t2\t*\nS\t3\t*\nL\t1\t+\t2\t+\t0M\nL\t2\t+\t3\t+\t0M\nL\t1\t+\t3\t+\t0M\n",
+        )?;
+        std::fs::write(
+            dir.path().join("c.gfa"),
+            "S\t1\t*\nS\t2\t*\nS\t3\t*\nS\t4\t*\nL\t1\t+\t2\t+\t0M\nL\t2\t+\t3\t+\t0M\nL\t3\t+\t4\t+\t0M\n",
+        )?;
+        std::fs::write(dir.path().join("notes.txt"), "not a graph")?;
+        let output = dir.path().join("dist.tsv");
+
+        let pattern = dir.path().join("*.gfa");
+        extract::write_spectral_distances(pattern.to_str().unwrap(), &output)?;
+
+        let contents = std::fs::read_to_string(&output)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "\ta\tb\tc");
+        assert_eq!(lines.len(), 4);
+
+        let mut distances = Array2::<f64>::zeros((3, 3));
+        for (i, line) in lines[1..].iter().enumerate() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            assert_eq!(fields[0], ["a", "b", "c"][i]);
+            for j in 0..3 {
+                distances[[i, j]] = fields[j + 1].parse().unwrap();
+            }
+        }
+        for i in 0..3 {
+            assert_eq!(distances[[i, i]], 0.0);
+            for j in 0..3 {
+                assert_eq!(distances[[i, j]], distances[[j, i]]);
+                if i != j {
+                    assert!(distances[[i, j]] > 0.0);
+                }
+            }
+        }
+        // Path P3 has spectrum {0, 1, 3} and the triangle {0, 3, 3}
+        assert!((distances[[0, 1]] - 2.0).abs() < 1e-6);
+
+        Ok(())
+    }
+
+    /// Test that a cached banded matrix yields only the requested eigenpairs
+    #[test]
+    fn test_eigen_range_from_banded_npy() -> io::Result<()> {
+        let dir = tempdir()?;
+        let banded_path = dir.path().join("lap.npy");
+        let output = dir.path().join("eigs.csv");
+
+        // Laplacian of the path P5, eigenvalues 2 - 2 cos(k π / 5)
+        let n = 5;
+        let mut laplacian = Array2::<f64>::zeros((n, n));
+        for i in 0..n - 1 {
+            laplacian[[i, i + 1]] = -1.0;
+            laplacian[[i + 1, i]] = -1.0;
+            laplacian[[i, i]] += 1.0;
+            laplacian[[i + 1, i + 1]] += 1.0;
+        }
+        save_array_to_npy(&to_banded_format(&laplacian, 1), &banded_path)?;
+
+        extract::eigen_range_from_banded(&banded_path, 2, 3, &output)?;
+
+        let eigvals = read_array_from_csv(&output).unwrap();
+        assert_eq!(eigvals.dim(), (1, 2));
+        for (j, k) in (1..=2).enumerate() {
+            let expected = 2.0 - 2.0 * (k as f64 * std::f64::consts::PI / n as f64).cos();
+            assert!((eigvals[[0, j]] - expected).abs() < 1e-6);
+        }
+
+        let eigvecs = read_array_from_csv(output.with_extension("eigenvectors.csv")).unwrap();
+        assert_eq!(eigvecs.dim(), (n, 2));
+        for j in 0..2 {
+            let residual = laplacian.dot(&eigvecs.column(j)) - &eigvecs.column(j) * eigvals[[0, j]];
+            assert!(residual.iter().all(|r| r.abs() < 1e-6));
+        }
+
+        Ok(())
+    }
+
+    /// Test that the embedding TSV has one row per segment and the requested number of dimensions
+    #[test]
+    fn test_embed_gfa_dimensions() -> io::Result<()> {
+        let dir = tempdir()?;
+        let gfa = dir.path().join("ring.gfa");
+        let mut contents = String::new();
+        for i in 1..=6 {
+            contents.push_str(&format!("S\t{}\t*\n", i));
+        }
+        for i in 1..=6 {
+            contents.push_str(&format!("L\t{}\t+\t{}\t+\t0M\n", i, i % 6 + 1));
+        }
+        std::fs::write(&gfa, contents)?;
+
+        for scaling in [EmbeddingScaling::None, EmbeddingScaling::Diffusion, EmbeddingScaling::Commute] {
+            let output = dir.path().join("embed.tsv");
+            extract::embed_gfa(&gfa, 3, scaling, &output)?;
+
+            let contents = std::fs::read_to_string(&output)?;
+            let lines: Vec<&str> = contents.lines().collect();
+            assert_eq!(lines[0], "segment\tdim1\tdim2\tdim3");
+            assert_eq!(lines.len(), 7, "{:?}", scaling);
+            for (i, line) in lines[1..].iter().enumerate() {
+                let fields: Vec<&str> = line.split('\t').collect();
+                assert_eq!(fields[0], (i + 1).to_string());
+                assert_eq!(fields.len(), 4);
+                assert!(fields[1..].iter().all(|field| field.parse::<f64>().is_ok()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Test that windowed_ngec scores each full window from its own submatrix
+    #[test]
+    fn test_windowed_ngec() -> io::Result<()> {
+        // A path on 0..3, a triangle on 3..6, isolated nodes 6..9, and a trailing node 9
+        let mut adjacency = Array2::<f64>::zeros((10, 10));
+        for &(u, v) in &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (3, 5), (8, 9)] {
+            adjacency[[u, v]] = 1.0;
+            adjacency[[v, u]] = 1.0;
+        }
+
+        let track = extract::windowed_ngec(&adjacency, 3, 3)?;
+        let starts: Vec<usize> = track.iter().map(|&(start, _)| start).collect();
+        assert_eq!(starts, vec![0, 3, 6]);
+
+        let path_ngec = graphome::eigen::compute_ngec(&array![0.0, 1.0, 3.0])?;
+        let triangle_ngec = graphome::eigen::compute_ngec(&array![0.0, 3.0, 3.0])?;
+        assert!((track[0].1 - path_ngec).abs() < 1e-6);
+        assert!((track[1].1 - triangle_ngec).abs() < 1e-6);
+        assert!(track[2].1.is_nan(), "edgeless window: {}", track[2].1);
+
+        // Overlapping windows
+        assert_eq!(extract::windowed_ngec(&adjacency, 4, 2)?.len(), 4);
+
+        // Zero lengths are rejected instead of panicking
+        for (window, step) in [(0, 1), (3, 0)] {
+            let error = extract::windowed_ngec(&adjacency, window, step).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        }
+        let missing = std::path::PathBuf::from("missing.gfa");
+        let error = extract::ngec_track_gfa(&missing, 0, 1, &missing).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        Ok(())
+    }
+
+    /// Test that ngec_track_gfa writes non-overlapping bedGraph records, by rGFA coordinate when
+    /// tagged and by node index otherwise
+    #[test]
+    fn test_ngec_track_bedgraph() -> io::Result<()> {
+        let dir = tempdir()?;
+
+        // A path whose lexicographic segment order is the reverse of its reference order
+        let rgfa = dir.path().join("linear.gfa");
+        let mut contents = String::new();
+        for (i, name) in ["f", "e", "d", "c", "b", "a"].iter().enumerate() {
+            contents.push_str(&format!("S\t{}\tACGTACGTAC\tSN:Z:chr1\tSO:i:{}\tSR:i:0\n", name, i * 10));
+        }
+        for pair in ["f", "e", "d", "c", "b", "a"].windows(2) {
+            contents.push_str(&format!("L\t{}\t+\t{}\t+\t0M\n", pair[0], pair[1]));
+        }
+        std::fs::write(&rgfa, contents)?;
+
+        let output = dir.path().join("track.bedgraph");
+        extract::ngec_track_gfa(&rgfa, 3, 3, &output)?;
+        let records: Vec<Vec<String>> = std::fs::read_to_string(&output)?
+            .lines()
+            .map(|line| line.split('\t').map(str::to_string).collect())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0][..3], ["chr1", "0", "30"]);
+        assert_eq!(records[1][..3], ["chr1", "30", "60"]);
+        // Both windows are a 3-node path
+        assert_eq!(records[0][3], records[1][3]);
+
+        // Without tags, overlapping windows are keyed by node index and clipped to the step
+        let plain = dir.path().join("ring.gfa");
+        let mut contents = String::new();
+        for i in 1..=6 {
+            contents.push_str(&format!("S\t{}\t*\n", i));
+        }
+        for i in 1..=6 {
+            contents.push_str(&format!("L\t{}\t+\t{}\t+\t0M\n", i, i % 6 + 1));
+        }
+        std::fs::write(&plain, contents)?;
+
+        extract::ngec_track_gfa(&plain, 4, 2, &output)?;
+        let contents = std::fs::read_to_string(&output)?;
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, (start, end)) in lines.iter().zip([(0, 2), (2, 4)]) {
+            let fields: Vec<&str> = line.split('\t').collect();
+            assert_eq!(fields[..3], ["ring", &start.to_string()[..], &end.to_string()[..]]);
+            let ngec: f64 = fields[3].parse().unwrap();
+            assert!((0.0..=1.0).contains(&ngec));
+        }
+
+        Ok(())
+    }
+
+    /// Test that filtering by tag keeps only the matching nodes and the edges among them
+    #[test]
+    fn test_extract_by_tag_induced_subgraph() {
+        // Path 0 - 1 - 2 - 3 - 4 with nodes 0, 1 and 3 on chr1
+        let mut adjacency = Array2::<f64>::zeros((5, 5));
+        for i in 0..4 {
+            adjacency[[i, i + 1]] = (i + 1) as f64;
+            adjacency[[i + 1, i]] = (i + 1) as f64;
+        }
+        let names: Vec<String> = (1..=5).map(|i| i.to_string()).collect();
+        let tags: Vec<String> = ["chr1", "chr1", "chr2", "chr1", "chr2"].iter().map(|t| t.to_string()).collect();
+
+        let (sub, kept) = extract::extract_by_tag(&adjacency, &names, &tags, "chr1");
+        assert_eq!(kept, vec![0, 1, 3]);
+        assert_eq!(sub, array![[0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+
+        let (sub, kept) = extract::extract_by_tag(&adjacency, &names, &tags, "chr3");
+        assert!(kept.is_empty());
+        assert_eq!(sub.dim(), (0, 0));
+    }
+
+    /// Test that comparing a triangle with a triangle plus a separate edge pads the spectra and
+    /// reports every difference as sample minus reference
+    #[test]
+    fn test_compare_gfa_triangle_and_extra_component() -> io::Result<()> {
+        let dir = tempdir()?;
+        let reference = dir.path().join("ref.gfa");
+        let sample = dir.path().join("sample.gfa");
+        let triangle = "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t+\tc\t+\t0M\nL\ta\t+\tc\t+\t0M\n";
+        std::fs::write(&reference, triangle)?;
+        std::fs::write(&sample, format!("{}S\td\t*\nS\te\t*\nL\td\t+\te\t+\t0M\n", triangle))?;
+
+        let comparison = extract::compare_gfa(&reference, &sample)?;
+
+        // {0, 3, 3} padded to {0, 0, 0, 3, 3} against {0, 0, 2, 3, 3}
+        assert!((comparison.spectral_distance - 2.0).abs() < 1e-6);
+        assert_eq!(comparison.component_difference, 1);
+        // The sample is disconnected, so its algebraic connectivity is zero
+        assert!((comparison.algebraic_connectivity_difference + 3.0).abs() < 1e-6);
+        let expected_ngec = graphome::eigen::compute_ngec(&array![0.0, 0.0, 2.0, 3.0, 3.0])?
+            - graphome::eigen::compute_ngec(&array![0.0, 3.0, 3.0])?;
+        assert!((comparison.ngec_difference - expected_ngec).abs() < 1e-6);
+
+        let same = extract::compare_gfa(&reference, &reference)?;
+        assert_eq!(same.component_difference, 0);
+        assert!(same.spectral_distance.abs() < 1e-6 && same.ngec_difference.abs() < 1e-12);
+
+        Ok(())
+    }
+
+    /// Test that the Laplacian selected in `ExtractOptions` reaches the GFA subcommands, and that a
+    /// non-symmetric variant is rejected before any file is read
+    #[test]
+    fn test_gfa_subcommands_use_selected_laplacian() -> io::Result<()> {
+        use graphome::laplacian::LaplacianKind;
+
+        let dir = tempdir()?;
+        let reference = dir.path().join("ref.gfa");
+        let sample = dir.path().join("sample.gfa");
+        let triangle = "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t+\tc\t+\t0M\nL\ta\t+\tc\t+\t0M\n";
+        std::fs::write(&reference, triangle)?;
+        std::fs::write(&sample, format!("{}S\td\t*\nS\te\t*\nL\td\t+\te\t+\t0M\n", triangle))?;
+
+        // Normalized spectra: {0, 1.5, 1.5} for the triangle and {0, 2} for the extra edge
+        let normalized = extract::ExtractOptions { kind: LaplacianKind::Normalized, ..Default::default() };
+        let comparison = extract::compare_gfa_with_options(&reference, &sample, &normalized, &mut NoProgress)?;
+        let expected = graphome::eigen::spectral_distance(&array![0.0, 1.5, 1.5], &array![0.0, 0.0, 1.5, 1.5, 2.0]);
+        assert!((comparison.spectral_distance - expected).abs() < 1e-6);
+        assert!((comparison.algebraic_connectivity_difference + 1.5).abs() < 1e-6);
+
+        let track = dir.path().join("track.bedgraph");
+        let ranking = dir.path().join("ranking.tsv");
+        let embedding = dir.path().join("embed.tsv");
+        let distances = dir.path().join("dist.tsv");
+        let pattern = dir.path().join("*.gfa");
+        let random_walk = extract::ExtractOptions { kind: LaplacianKind::RandomWalk, ..Default::default() };
+        let results = [
+            extract::compare_gfa_with_options(&reference, &sample, &random_walk, &mut NoProgress).map(|_| ()),
+            extract::ngec_track_gfa_with_options(&sample, 2, 1, &random_walk, &track, &mut NoProgress),
+            extract::rank_gfa_by_fiedler_with_options(&sample, &random_walk, &ranking, &mut NoProgress),
+            extract::embed_gfa_with_options(&sample, 2, EmbeddingScaling::None, &random_walk, &embedding, &mut NoProgress),
+            extract::write_spectral_distances_with_options(pattern.to_str().unwrap(), &random_walk, &distances, &mut NoProgress),
+        ];
+        for result in results {
+            assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        }
+        assert!(!track.exists() && !ranking.exists() && !embedding.exists() && !distances.exists());
+
+        Ok(())
+    }
+
+    /// Test that `ExtractOptions::clamp_floor` reaches the NGEC of `compare` and of every window
+    #[test]
+    fn test_clamp_floor_reaches_compare_and_windows() -> io::Result<()> {
+        use graphome::eigen::compute_ngec_with_floor;
+
+        let dir = tempdir()?;
+        let reference = dir.path().join("ref.gfa");
+        let sample = dir.path().join("sample.gfa");
+        let triangle = "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t+\tc\t+\t0M\nL\ta\t+\tc\t+\t0M\n";
+        std::fs::write(&reference, triangle)?;
+        std::fs::write(&sample, format!("{}S\td\t*\nS\te\t*\nL\td\t+\te\t+\t0M\n", triangle))?;
+
+        // A floor of 0.8, scaled by the largest eigenvalue 3 to 2.4, clamps the sample's
+        // eigenvalue 2 but neither graph's 3s
+        let options = extract::ExtractOptions { clamp_floor: 0.8, ..Default::default() };
+        let comparison = extract::compare_gfa_with_options(&reference, &sample, &options, &mut NoProgress)?;
+        let expected = compute_ngec_with_floor(&array![0.0, 0.0, 2.0, 3.0, 3.0], 0.8)?
+            - compute_ngec_with_floor(&array![0.0, 3.0, 3.0], 0.8)?;
+        assert!((comparison.ngec_difference - expected).abs() < 1e-6);
+        let default = extract::compare_gfa(&reference, &sample)?;
+        assert!((comparison.ngec_difference - default.ngec_difference).abs() > 1e-3);
+
+        // The path 0 - 1 - 2 has spectrum {0, 1, 3}; a floor of 0.5 (1.5 once scaled) clamps the 1
+        let path = array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+        let options = extract::ExtractOptions { clamp_floor: 0.5, ..Default::default() };
+        let track = extract::windowed_ngec_with_options(&path, 3, 1, &options)?;
+        let expected = compute_ngec_with_floor(&array![0.0, 1.0, 3.0], 0.5)?;
+        assert_eq!(track.len(), 1);
+        assert!((track[0].1 - expected).abs() < 1e-6);
+        assert!((track[0].1 - extract::windowed_ngec(&path, 3, 1)?[0].1).abs() > 1e-3);
+
+        Ok(())
+    }
+
+    /// Test that `fiedler-rank` and `embed` write labeled JSON, and reject formats without names
+    /// before reading the GFA
+    #[test]
+    fn test_fiedler_rank_and_embed_formats() -> io::Result<()> {
+        use graphome::eigen::OutputFormat;
+
+        let dir = tempdir()?;
+        let gfa = dir.path().join("path.gfa");
+        std::fs::write(&gfa, "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t+\tc\t+\t0M\n")?;
+        let json = extract::ExtractOptions { format: OutputFormat::Json, ..Default::default() };
+
+        let ranking = dir.path().join("ranking.json");
+        extract::rank_gfa_by_fiedler_with_options(&gfa, &json, &ranking, &mut NoProgress)?;
+        let contents = std::fs::read_to_string(&ranking)?;
+        assert!(contents.contains("\"columns\": [\"fiedler\"]"), "{}", contents);
+        // Rows follow the ranking, so the middle segment sits between the two ends
+        assert!(
+            contents.contains("\"rows\": [\"a\", \"b\", \"c\"]") || contents.contains("\"rows\": [\"c\", \"b\", \"a\"]"),
+            "{}",
+            contents
+        );
+
+        let embedding = dir.path().join("embed.json");
+        extract::embed_gfa_with_options(&gfa, 2, EmbeddingScaling::None, &json, &embedding, &mut NoProgress)?;
+        let contents = std::fs::read_to_string(&embedding)?;
+        assert!(contents.contains("\"rows\": [\"a\", \"b\", \"c\"]"), "{}", contents);
+        assert!(contents.contains("\"columns\": [\"dim1\", \"dim2\"]"), "{}", contents);
+
+        let missing = dir.path().join("missing.gfa");
+        let out = dir.path().join("out");
+        for format in [OutputFormat::Npy, OutputFormat::Mtx] {
+            let options = extract::ExtractOptions { format, ..Default::default() };
+            let errors = [
+                extract::rank_gfa_by_fiedler_with_options(&missing, &options, &out, &mut NoProgress).unwrap_err(),
+                extract::embed_gfa_with_options(&missing, 2, EmbeddingScaling::None, &options, &out, &mut NoProgress).unwrap_err(),
+            ];
+            for error in errors {
+                assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{:?}", format);
+            }
+        }
+        assert!(!out.exists());
+
+        Ok(())
+    }
+
+    /// Test that `extract --quiet` prints only the NGEC line, while the default run also prints
+    /// the status lines, solver choice and heatmaps
+    #[test]
+    fn test_extract_quiet_prints_only_ngec() -> io::Result<()> {
+        let dir = tempdir()?;
+        let gam_path = dir.path().join("triangle.gam");
+        create_mock_gam_file(&gam_path, &[(0, 1), (1, 0), (1, 2), (2, 1), (0, 2), (2, 0)])?;
+
+        let run = |extra: &[&str]| -> io::Result<String> {
+            let output = std::process::Command::new(env!("CARGO_BIN_EXE_graphome"))
+                .args(["extract", "--input", gam_path.to_str().unwrap(), "--start-node", "0", "--end-node", "2"])
+                .args(["--output", dir.path().join("out.gam").to_str().unwrap()])
+                .args(extra)
+                .output()?;
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+
+        let quiet = run(&["--quiet"])?;
+        let lines: Vec<&str> = quiet.lines().collect();
+        assert_eq!(lines.len(), 1, "{}", quiet);
+        assert!(lines[0].starts_with("✅ NGEC: "), "{}", quiet);
+
+        let verbose = run(&[])?;
+        assert!(verbose.contains("📂 Loading adjacency matrix"));
+        assert!(verbose.contains("🧮 Decomposed with LAPACK's"));
+        assert!(verbose.contains("Eigenvalues:"));
+        assert!(verbose.contains(lines[0]));
+
+        Ok(())
+    }
+
+    /// Records the phases and printed lines of a subcommand, and how often it suspended the display
+    #[derive(Default)]
+    struct RecordingProgress {
+        phases: Vec<String>,
+        lines: Vec<String>,
+        suspended: usize,
+    }
+
+    impl Progress for RecordingProgress {
+        fn phase(&mut self, phase: &str, _fraction: f64) {
+            self.phases.push(phase.to_string());
+        }
+
+        fn suspend(&mut self, print: &mut dyn FnMut()) {
+            self.suspended += 1;
+            print();
+        }
+
+        fn println(&mut self, line: &str) {
+            self.lines.push(line.to_string());
+        }
+    }
+
+    /// Test that every line goes through `Progress`, with the heatmaps printed while suspended, and
+    /// that `quiet` keeps only the results
+    #[test]
+    fn test_subcommands_print_through_progress() -> io::Result<()> {
+        let dir = tempdir()?;
+        let gam_path = dir.path().join("triangle.gam");
+        create_mock_gam_file(&gam_path, &[(0, 1), (1, 0), (1, 2), (2, 1), (0, 2), (2, 0)])?;
+        let output = dir.path().join("out.gam");
+        let extract_with = |options: &ExtractOptions, progress: &mut RecordingProgress| {
+            extract::extract_and_analyze_submatrix_with_progress(&gam_path, 0, 2, &output, options, progress)
+        };
+
+        let mut verbose = RecordingProgress::default();
+        extract_with(&ExtractOptions::default(), &mut verbose)?;
+        assert_eq!(verbose.suspended, 1);
+        assert!(verbose.lines.iter().any(|line| line.starts_with("📂 Loading adjacency matrix")));
+        let ngec: Vec<&String> = verbose.lines.iter().filter(|line| line.starts_with("✅ NGEC: ")).collect();
+        assert_eq!(ngec.len(), 1);
+
+        let mut quiet = RecordingProgress::default();
+        extract_with(&ExtractOptions { quiet: true, ..Default::default() }, &mut quiet)?;
+        assert_eq!((quiet.suspended, quiet.lines.iter().collect::<Vec<_>>()), (0, ngec));
+        assert_eq!(quiet.phases, verbose.phases);
+
+        let gfa = dir.path().join("triangle.gfa");
+        std::fs::write(&gfa, "S\ta\t*\nS\tb\t*\nS\tc\t*\nL\ta\t+\tb\t+\t0M\nL\tb\t+\tc\t+\t0M\n")?;
+        let mut progress = RecordingProgress::default();
+        extract::compare_gfa_with_options(&gfa, &gfa, &ExtractOptions { quiet: true, ..Default::default() }, &mut progress)?;
+        assert_eq!(progress.phases, ["reference", "sample", "done"]);
+        assert_eq!(progress.lines.len(), 5);
+        assert!(progress.lines[0].starts_with("📊 ") && progress.lines[1].starts_with("spectral_distance\t"));
+
+        let mut progress = RecordingProgress::default();
+        let ranking = dir.path().join("ranking.tsv");
+        let quiet = ExtractOptions { quiet: true, ..Default::default() };
+        extract::rank_gfa_by_fiedler_with_options(&gfa, &quiet, &ranking, &mut progress)?;
+        assert_eq!(progress.phases, ["loading", "fiedler", "saving", "done"]);
+        assert!(progress.lines.is_empty());
+
+        Ok(())
+    }
+
+    /// Test that ngec_track_gfa windows each contig on its own and drops empty records
+    #[test]
+    fn test_ngec_track_per_contig() -> io::Result<()> {
+        let dir = tempdir()?;
+        let gfa = dir.path().join("contigs.gfa");
+
+        // Paths of 4 nodes on chr1, 3 on chr2, and 3 zero-length segments on chr3, joined end to end
+        let mut contents = String::new();
+        let mut segments = Vec::new();
+        for (contig, count, sequence) in [("chr1", 4, "ACGTACGTAC"), ("chr2", 3, "ACGTACGTAC"), ("chr3", 3, "*")] {
+            for i in 0..count {
+                let name = format!("{}_{}", contig, i);
+                let offset = if sequence == "*" { 0 } else { i * 10 };
+                contents.push_str(&format!("S\t{}\t{}\tSN:Z:{}\tSO:i:{}\n", name, sequence, contig, offset));
+                segments.push(name);
+            }
+        }
+        for pair in segments.windows(2) {
+            contents.push_str(&format!("L\t{}\t+\t{}\t+\t0M\n", pair[0], pair[1]));
+        }
+        std::fs::write(&gfa, contents)?;
+
+        let output = dir.path().join("track.bedgraph");
+        extract::ngec_track_gfa(&gfa, 3, 3, &output)?;
+        let records: Vec<Vec<String>> = std::fs::read_to_string(&output)?
+            .lines()
+            .map(|line| line.split('\t').map(str::to_string).collect())
+            .collect();
+
+        // chr1's fourth node is a partial window, and chr3's record would be empty
+        assert_eq!(records.len(), 2, "{:?}", records);
+        assert_eq!(records[0][..3], ["chr1", "0", "30"]);
+        assert_eq!(records[1][..3], ["chr2", "0", "30"]);
+        // Both windows are a 3-node path, with no edge leaking in from the neighboring contig
+        assert_eq!(records[0][3], records[1][3]);
+
+        Ok(())
+    }
 }
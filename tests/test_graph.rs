@@ -0,0 +1,428 @@
+// tests/test_graph.rs
+
+//! Unit tests for the graph module.
+
+use graphome::eigen::adjacency_matrix_to_ndarray;
+use graphome::extract::load_adjacency_matrix;
+use graphome::graph::{
+    coarsen, degree_assortativity, drop_isolated, isolated_nodes, knn_graph, knn_graph_weighted, line_graph_adjacency,
+    reorder_for_bandwidth, threshold_graph, threshold_graph_weighted, AdjacencyBuilder, DuplicateEdgePolicy,
+    SparseAdjacency, StreamingAdjacency, WeightKernel,
+};
+use ndarray::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use tempfile::tempdir;
+
+const TOLERANCE: f64 = 1e-6;
+
+/// Test that the threshold graph links exactly the pairs closer than the threshold.
+#[test]
+fn test_threshold_graph_edge_set() {
+    let distances = array![
+        [0.0, 1.0, 3.0, 0.5],
+        [1.0, 0.0, 2.5, 4.0],
+        [3.0, 2.5, 0.0, 2.0],
+        [0.5, 4.0, 2.0, 0.0]
+    ];
+
+    let binary = threshold_graph(&distances, 2.5, false);
+    let expected = array![
+        [0.0, 1.0, 0.0, 1.0],
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+        [1.0, 0.0, 1.0, 0.0]
+    ];
+    assert_eq!(binary, expected);
+
+    let weighted = threshold_graph(&distances, 2.5, true);
+    assert!((weighted[[0, 1]] - 1.5).abs() < TOLERANCE);
+    assert!((weighted[[0, 3]] - 2.0).abs() < TOLERANCE);
+    assert!((weighted[[2, 3]] - 0.5).abs() < TOLERANCE);
+    assert_eq!(weighted[[1, 2]], 0.0);
+    assert_eq!(weighted.diag().sum(), 0.0);
+    assert_eq!(weighted, weighted.t());
+}
+
+/// Test that building edge-by-edge from a .gam file matches the batch loader.
+#[test]
+fn test_adjacency_builder_matches_batch_loader() {
+    let dir = tempdir().unwrap();
+    let gam_path = dir.path().join("graph.gam");
+
+    // Bidirectional edges, as written by `convert_gfa_to_edge_list`
+    let edges: Vec<(u32, u32)> = vec![(0, 1), (1, 0), (1, 2), (2, 1), (2, 4), (4, 2), (3, 3)];
+    {
+        let mut writer = BufWriter::new(File::create(&gam_path).unwrap());
+        for &(a, b) in &edges {
+            writer.write_all(&a.to_le_bytes()).unwrap();
+            writer.write_all(&b.to_le_bytes()).unwrap();
+        }
+    }
+
+    let batch_edges = load_adjacency_matrix(&gam_path, 0, 4).unwrap();
+    let batch = adjacency_matrix_to_ndarray(&batch_edges, 0, 4);
+
+    let streamed = AdjacencyBuilder::from_edge_list(&gam_path, DuplicateEdgePolicy::Overwrite).unwrap();
+    assert_eq!(streamed.num_nodes(), 5);
+    assert_eq!(streamed.num_edges(), 4);
+    assert_eq!(streamed.build(), batch);
+    assert_eq!(streamed.build_sparse().to_dense(), batch);
+
+    // The mirrored half of each edge is not a duplicate, so no policy changes the weights
+    for policy in [DuplicateEdgePolicy::Sum, DuplicateEdgePolicy::Error] {
+        let streamed = AdjacencyBuilder::from_edge_list(&gam_path, policy).unwrap();
+        assert_eq!(streamed.build(), batch, "{:?}", policy);
+    }
+
+    let mut incremental = AdjacencyBuilder::new();
+    for &(a, b) in &edges {
+        incremental.add_edge(a as usize, b as usize, 1.0).unwrap();
+    }
+    assert_eq!(incremental.build(), batch);
+}
+
+/// Test the duplicate-edge policies and the sparse accessors.
+#[test]
+fn test_adjacency_builder_duplicate_policies() {
+    let cases = [
+        (DuplicateEdgePolicy::Overwrite, 3.0),
+        (DuplicateEdgePolicy::First, 2.0),
+        (DuplicateEdgePolicy::Sum, 5.0),
+        (DuplicateEdgePolicy::Max, 3.0),
+    ];
+    for (policy, expected) in cases {
+        let mut builder = AdjacencyBuilder::with_policy(policy);
+        builder.add_edge(0, 1, 2.0).unwrap();
+        builder.add_edge(1, 0, 3.0).unwrap();
+        assert_eq!(builder.num_edges(), 1);
+        assert_eq!(builder.build()[[0, 1]], expected, "{:?}", policy);
+        assert_eq!(builder.build()[[1, 0]], expected, "{:?}", policy);
+    }
+
+    let mut strict = AdjacencyBuilder::with_policy(DuplicateEdgePolicy::Error);
+    strict.add_edge(0, 1, 2.0).unwrap();
+    let error = strict.add_edge(1, 0, 3.0).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(strict.build()[[0, 1]], 2.0);
+
+    let mut sum = AdjacencyBuilder::with_policy(DuplicateEdgePolicy::Sum);
+    sum.add_edge(0, 1, 2.0).unwrap();
+    sum.add_edge(1, 0, 3.0).unwrap();
+    sum.add_edge(1, 2, 0.5).unwrap();
+    assert_eq!(sum.build()[[0, 1]], 5.0);
+
+    let sparse = sum.build_sparse();
+    assert_eq!(sparse.num_nodes, 3);
+    assert_eq!(sparse.nnz(), 4);
+    assert_eq!(sparse.get(2, 1), 0.5);
+    assert_eq!(sparse.get(0, 2), 0.0);
+
+    let x = array![1.0, 2.0, 3.0];
+    let expected = sum.build().dot(&x);
+    for (a, b) in sparse.matvec(&x).iter().zip(expected.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+}
+
+/// Test that the kNN graph of two well-separated clusters connects each cluster but not the two.
+#[test]
+fn test_knn_graph_two_clusters() {
+    let points = array![
+        [0.0, 0.0],
+        [0.1, 0.0],
+        [0.0, 0.1],
+        [10.0, 10.0],
+        [10.1, 10.0],
+        [10.0, 10.1]
+    ];
+
+    let adjacency = knn_graph(&points, 2);
+    assert_eq!(adjacency, adjacency.t());
+    for i in 0..6 {
+        assert_eq!(adjacency[[i, i]], 0.0);
+        for j in 0..6 {
+            let same_cluster = (i < 3) == (j < 3);
+            let expected = if same_cluster && i != j { 1.0 } else { 0.0 };
+            assert_eq!(adjacency[[i, j]], expected, "Unexpected edge ({}, {}).", i, j);
+        }
+    }
+
+    let gaussian = knn_graph_weighted(&points, 2, WeightKernel::Gaussian { sigma: 0.1 });
+    let expected = (-0.01_f64 / 0.02).exp();
+    assert!((gaussian[[0, 1]] - expected).abs() < TOLERANCE);
+    assert_eq!(gaussian[[0, 3]], 0.0);
+}
+
+/// Test that the file-backed matvec matches the in-memory sparse matvec.
+#[test]
+fn test_streaming_adjacency_matches_in_memory() {
+    let dir = tempdir().unwrap();
+    let gam_path = dir.path().join("graph.gam");
+
+    let links = [(0u32, 1u32), (1, 2), (2, 3), (3, 0), (1, 4)];
+    {
+        let mut writer = BufWriter::new(File::create(&gam_path).unwrap());
+        for &(a, b) in &links {
+            for (from, to) in [(a, b), (b, a)] {
+                writer.write_all(&from.to_le_bytes()).unwrap();
+                writer.write_all(&to.to_le_bytes()).unwrap();
+            }
+        }
+    }
+
+    let streaming = StreamingAdjacency::open(&gam_path).unwrap();
+    let in_memory = AdjacencyBuilder::from_edge_list(&gam_path, DuplicateEdgePolicy::Overwrite)
+        .unwrap()
+        .build_sparse();
+    assert_eq!(streaming.num_nodes(), 5);
+    assert_eq!(streaming.degrees(), &array![2.0, 3.0, 2.0, 2.0, 1.0]);
+
+    let x = array![0.5, -1.0, 2.0, 0.25, 3.0];
+    let expected = in_memory.matvec(&x);
+    for (a, b) in streaming.matvec(&x).unwrap().iter().zip(expected.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+
+    let laplacian = graphome::laplacian::combinatorial_laplacian(&in_memory.to_dense());
+    let expected = laplacian.dot(&x);
+    for (a, b) in streaming.laplacian_matvec(&x).unwrap().iter().zip(expected.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+
+    assert!(streaming.matvec(&array![1.0]).is_err());
+
+    // A node ID past the count found at `open` is an error, not a panic
+    {
+        let mut writer = std::fs::OpenOptions::new().append(true).open(&gam_path).unwrap();
+        writer.write_all(&9u32.to_le_bytes()).unwrap();
+        writer.write_all(&0u32.to_le_bytes()).unwrap();
+    }
+    let error = streaming.matvec(&x).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // A trailing partial record is rejected instead of being read as the end of the file
+    {
+        let mut writer = std::fs::OpenOptions::new().append(true).open(&gam_path).unwrap();
+        writer.write_all(&[1, 0, 0]).unwrap();
+    }
+    let error = StreamingAdjacency::open(&gam_path).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("truncated"), "{}", error);
+}
+
+/// Test that heavy-edge coarsening halves a path and preserves the total edge weight.
+#[test]
+fn test_coarsen_heavy_edge_matching() {
+    // Path 0-1-2-3-4-5 whose heavy edges (0,1), (2,3), (4,5) form a perfect matching
+    let mut adjacency = Array2::<f64>::zeros((6, 6));
+    for (u, v, w) in [(0, 1, 3.0), (1, 2, 1.0), (2, 3, 2.0), (3, 4, 0.5), (4, 5, 4.0)] {
+        adjacency[[u, v]] = w;
+        adjacency[[v, u]] = w;
+    }
+
+    let (coarse, mapping) = coarsen(&adjacency);
+    assert_eq!(mapping, vec![0, 0, 1, 1, 2, 2]);
+    assert_eq!(coarse.dim(), (3, 3));
+    assert!((coarse.sum() - adjacency.sum()).abs() < TOLERANCE);
+    assert!((coarse[[0, 1]] - 1.0).abs() < TOLERANCE);
+    assert!((coarse[[1, 2]] - 0.5).abs() < TOLERANCE);
+    assert!((coarse[[2, 2]] - 8.0).abs() < TOLERANCE);
+    assert_eq!(coarse, coarse.t());
+}
+
+/// Test that isolated nodes (a self-loop alone does not count as an edge) are found and dropped.
+#[test]
+fn test_drop_isolated_nodes() {
+    // Node 1 has no edges, node 3 only a self-loop; 0 - 2 - 4 is a path
+    let mut adjacency = Array2::<f64>::zeros((5, 5));
+    for (u, v) in [(0, 2), (2, 4)] {
+        adjacency[[u, v]] = 1.0;
+        adjacency[[v, u]] = 1.0;
+    }
+    adjacency[[3, 3]] = 1.0;
+
+    assert_eq!(isolated_nodes(&adjacency), vec![1, 3]);
+
+    let (reduced, kept) = drop_isolated(&adjacency);
+    assert_eq!(kept, vec![0, 2, 4]);
+    assert_eq!(reduced, array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+    assert!(isolated_nodes(&reduced).is_empty());
+}
+
+/// Test that the line graph of a triangle is a triangle, and of a path is a shorter path.
+#[test]
+fn test_line_graph_adjacency() {
+    let triangle = array![[0.0, 1.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0]];
+    let (line_graph, edges) = line_graph_adjacency(&triangle);
+    assert_eq!(edges, vec![(0, 1), (0, 2), (1, 2)]);
+    assert_eq!(line_graph, triangle);
+
+    // A path 0 - 1 - 2 - 3: the line graph is the path of its three edges
+    let mut path = Array2::<f64>::zeros((4, 4));
+    for (u, v) in [(0, 1), (1, 2), (2, 3)] {
+        path[[u, v]] = 1.0;
+        path[[v, u]] = 1.0;
+    }
+    let (line_graph, edges) = line_graph_adjacency(&path);
+    assert_eq!(edges, vec![(0, 1), (1, 2), (2, 3)]);
+    assert_eq!(line_graph, array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+}
+
+/// Test the degree assortativity of a star (-1), a path (-1/2), and a regular cycle (undefined).
+#[test]
+fn test_degree_assortativity() {
+    let mut star = Array2::<f64>::zeros((5, 5));
+    for leaf in 1..5 {
+        star[[0, leaf]] = 1.0;
+        star[[leaf, 0]] = 1.0;
+    }
+    assert!((degree_assortativity(&star) + 1.0).abs() < TOLERANCE);
+
+    // Path 0 - 1 - 2 - 3 with degrees 1, 2, 2, 1
+    let mut path = Array2::<f64>::zeros((4, 4));
+    for (u, v) in [(0, 1), (1, 2), (2, 3)] {
+        path[[u, v]] = 1.0;
+        path[[v, u]] = 1.0;
+    }
+    assert!((degree_assortativity(&path) + 0.5).abs() < TOLERANCE);
+
+    let mut cycle = Array2::<f64>::zeros((6, 6));
+    for u in 0..6 {
+        cycle[[u, (u + 1) % 6]] = 1.0;
+        cycle[[(u + 1) % 6, u]] = 1.0;
+    }
+    assert!(degree_assortativity(&cycle).is_nan());
+}
+
+/// Test that the kernels give binary, heat-kernel, and inverse-distance weights on the same points.
+#[test]
+fn test_weight_kernels_on_same_points() {
+    // Collinear points at 0, 1 and 3
+    let points = array![[0.0], [1.0], [3.0]];
+    let distances = array![[0.0, 1.0, 3.0], [1.0, 0.0, 2.0], [3.0, 2.0, 0.0]];
+
+    let binary = knn_graph_weighted(&points, 2, WeightKernel::Binary);
+    let gaussian = knn_graph_weighted(&points, 2, WeightKernel::Gaussian { sigma: 1.0 });
+    let inverse = knn_graph_weighted(&points, 2, WeightKernel::Inverse);
+    for (i, j) in [(0, 1), (0, 2), (1, 2)] {
+        let d: f64 = distances[[i, j]];
+        assert_eq!(binary[[i, j]], 1.0);
+        assert!((gaussian[[i, j]] - (-d * d / 2.0).exp()).abs() < TOLERANCE);
+        assert!((inverse[[i, j]] - 1.0 / d).abs() < TOLERANCE);
+    }
+    // The closest pair is weighted most strongly by both distance-decaying kernels
+    assert!(gaussian[[0, 1]] > gaussian[[1, 2]] && gaussian[[1, 2]] > gaussian[[0, 2]]);
+    assert!(inverse[[0, 1]] > inverse[[1, 2]] && inverse[[1, 2]] > inverse[[0, 2]]);
+
+    // The threshold graph applies the same kernel to the pairs it keeps
+    assert_eq!(threshold_graph_weighted(&distances, 2.5, WeightKernel::Gaussian { sigma: 1.0 })[[1, 2]], gaussian[[1, 2]]);
+    assert_eq!(threshold_graph_weighted(&distances, 2.5, WeightKernel::Inverse)[[0, 2]], 0.0);
+    let linear = threshold_graph_weighted(&distances, 2.5, WeightKernel::Linear { cutoff: 2.5 });
+    assert_eq!(linear, threshold_graph(&distances, 2.5, true));
+    assert!((linear[[0, 1]] - 1.5).abs() < TOLERANCE);
+}
+
+/// Test that reverse Cuthill-McKee recovers the bandwidth of a scrambled path, and reports no
+/// gain on a path that is already in order.
+#[test]
+fn test_reorder_for_bandwidth_report() {
+    // The path visits the nodes in this scrambled order, so the edge 6 - 1 spans 5 diagonals
+    let walk = [0, 3, 6, 1, 4, 2, 5];
+    let mut scrambled = Array2::<f64>::zeros((7, 7));
+    for pair in walk.windows(2) {
+        scrambled[[pair[0], pair[1]]] = 1.0;
+        scrambled[[pair[1], pair[0]]] = 1.0;
+    }
+
+    let (reordered, order, report) = reorder_for_bandwidth(&scrambled);
+    assert_eq!(report.original_band, 5);
+    assert_eq!(report.reordered_band, 1);
+    assert!((report.reduction_percent() - 80.0).abs() < TOLERANCE);
+    assert!(report.improved());
+    for (new_u, &old_u) in order.iter().enumerate() {
+        for (new_v, &old_v) in order.iter().enumerate() {
+            assert_eq!(reordered[[new_u, new_v]], scrambled[[old_u, old_v]]);
+        }
+    }
+
+    let mut path = Array2::<f64>::zeros((7, 7));
+    for i in 0..6 {
+        path[[i, i + 1]] = 1.0;
+        path[[i + 1, i]] = 1.0;
+    }
+    let (_, _, report) = reorder_for_bandwidth(&path);
+    assert_eq!((report.original_band, report.reordered_band), (1, 1));
+    assert_eq!(report.reduction_percent(), 0.0);
+    assert!(!report.improved());
+}
+
+/// Test that a sparse adjacency survives a save/load round trip, with and without node names
+#[test]
+fn test_sparse_adjacency_save_load() {
+    let dense = array![[0.0, 1.5, 0.0], [1.5, 0.0, 2.0], [0.0, 2.0, 0.25]];
+    let sparse = SparseAdjacency::from_dense(&dense);
+    let names = vec!["s1".to_string(), "s2".to_string(), "ñode".to_string()];
+    let dir = tempdir().unwrap();
+
+    let named = dir.path().join("named.csr");
+    sparse.save_with_names(&named, &names).unwrap();
+    let (loaded, loaded_names) = SparseAdjacency::load_with_names(&named).unwrap();
+    assert_eq!(loaded, sparse);
+    assert_eq!(loaded_names, names);
+    assert_eq!(loaded.to_dense(), dense);
+
+    let bare = dir.path().join("bare.csr");
+    sparse.save(&bare).unwrap();
+    assert_eq!(SparseAdjacency::load(&bare).unwrap(), sparse);
+    assert!(SparseAdjacency::load_with_names(&bare).unwrap().1.is_empty());
+
+    assert!(sparse.save_with_names(dir.path().join("short.csr"), &names[..2]).is_err());
+
+    // A truncated file or one with another magic is rejected
+    let bytes = std::fs::read(&named).unwrap();
+    std::fs::write(&bare, &bytes[..bytes.len() - 3]).unwrap();
+    assert!(SparseAdjacency::load(&bare).is_err());
+    std::fs::write(&bare, b"GAM!rest").unwrap();
+    assert_eq!(SparseAdjacency::load(&bare).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// Test that a sparse adjacency file with a wrong version, an oversized count, an oversized name
+/// length or unsorted columns is rejected with `InvalidData` instead of panicking or allocating
+#[test]
+fn test_sparse_adjacency_load_rejects_corrupt_header() {
+    let sparse = SparseAdjacency::from_dense(&array![[0.0, 1.0, 2.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+    let names = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("graph.csr");
+    sparse.save_with_names(&path, &names).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+
+    let mut corruptions: Vec<Vec<u8>> = Vec::new();
+    // Version byte, then the node, entry and name counts after the 5-byte header
+    let mut version = bytes.clone();
+    version[4] = 99;
+    corruptions.push(version);
+    for offset in [5, 13, 21] {
+        let mut oversized = bytes.clone();
+        oversized[offset..offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        corruptions.push(oversized);
+    }
+    // The first name's length, just after indptr (4 words), indices and values (4 words each)
+    let name_offset = 29 + 8 * (4 + 4 + 4);
+    let mut long_name = bytes.clone();
+    long_name[name_offset..name_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    corruptions.push(long_name);
+    // Swap the two column indices of row 0, so they descend
+    let mut unsorted = bytes.clone();
+    let indices_offset = 29 + 8 * 4;
+    unsorted[indices_offset..indices_offset + 8].copy_from_slice(&bytes[indices_offset + 8..indices_offset + 16]);
+    unsorted[indices_offset + 8..indices_offset + 16].copy_from_slice(&bytes[indices_offset..indices_offset + 8]);
+    corruptions.push(unsorted);
+
+    for (case, corrupted) in corruptions.iter().enumerate() {
+        std::fs::write(&path, corrupted).unwrap();
+        let error = SparseAdjacency::load_with_names(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "case {}: {}", case, error);
+    }
+}
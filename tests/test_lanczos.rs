@@ -0,0 +1,244 @@
+// tests/test_lanczos.rs
+
+//! Unit tests for the lanczos module.
+
+use graphome::eigen::call_eigendecomp;
+use graphome::graph::StreamingAdjacency;
+use graphome::lanczos::{block_lanczos, lanczos_checkpointed, lanczos_with_history, LanczosState};
+use ndarray::prelude::*;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use tempfile::tempdir;
+
+const TOLERANCE: f64 = 1e-6;
+
+/// Builds the Laplacian of the path graph on `n` nodes.
+fn path_laplacian(n: usize) -> Array2<f64> {
+    let mut laplacian = Array2::<f64>::zeros((n, n));
+    for i in 0..n - 1 {
+        laplacian[[i, i + 1]] = -1.0;
+        laplacian[[i + 1, i]] = -1.0;
+        laplacian[[i, i]] += 1.0;
+        laplacian[[i + 1, i + 1]] += 1.0;
+    }
+    laplacian
+}
+
+/// A deterministic, non-symmetric start vector so no eigencomponent is missing.
+fn start_vector(n: usize) -> Array1<f64> {
+    Array1::from_iter((0..n).map(|i| 1.0 + i as f64 * 0.37))
+}
+
+/// Test that a run checkpointed and resumed mid-way matches an uninterrupted run.
+#[test]
+fn test_lanczos_checkpoint_resume_matches_uninterrupted() {
+    let n = 12;
+    let laplacian = path_laplacian(n);
+    let matvec = |x: &Array1<f64>| laplacian.dot(x);
+    let dir = tempdir().expect("Failed to create temp dir");
+    let checkpoint = dir.path().join("lanczos.ckpt");
+
+    // Uninterrupted run
+    let mut full = LanczosState::new(&start_vector(n)).unwrap();
+    for _ in 0..8 {
+        full.step(&matvec);
+    }
+
+    // Interrupted run: stop after 4 iterations, then resume from disk
+    let mut first_half = LanczosState::new(&start_vector(n)).unwrap();
+    lanczos_checkpointed(matvec, &mut first_half, 4, &checkpoint, 2)
+        .expect("Checkpointed run failed");
+    let mut resumed = LanczosState::resume_from(&checkpoint).expect("Failed to resume checkpoint");
+    assert_eq!(resumed, first_half, "Resumed state differs from the saved state.");
+    assert_eq!(resumed.iterations(), 4);
+
+    lanczos_checkpointed(matvec, &mut resumed, 8, &checkpoint, 0)
+        .expect("Resumed run failed");
+
+    assert_eq!(resumed.alphas, full.alphas, "Diagonal coefficients differ after resume.");
+    assert_eq!(resumed.betas, full.betas, "Off-diagonal coefficients differ after resume.");
+    assert_eq!(
+        resumed.ritz_values().unwrap(),
+        full.ritz_values().unwrap(),
+        "Ritz values differ after resume."
+    );
+}
+
+/// Test that a full-length Lanczos run reproduces the spectrum's extreme eigenvalues.
+#[test]
+fn test_lanczos_ritz_values_match_extremes() {
+    let n = 8;
+    let laplacian = path_laplacian(n);
+    let mut state = LanczosState::new(&start_vector(n)).unwrap();
+    while state.step(&|x: &Array1<f64>| laplacian.dot(x)) {}
+
+    let ritz = state.ritz_values().unwrap();
+    let (eigvals, _) = call_eigendecomp(&laplacian).unwrap();
+
+    assert!((ritz[0] - eigvals[0]).abs() <= TOLERANCE, "Smallest Ritz value = {}", ritz[0]);
+    assert!(
+        (ritz[ritz.len() - 1] - eigvals[n - 1]).abs() <= TOLERANCE,
+        "Largest Ritz value = {}",
+        ritz[ritz.len() - 1]
+    );
+}
+
+/// Test that resuming from a file that is not a checkpoint fails cleanly.
+#[test]
+fn test_lanczos_resume_rejects_invalid_file() {
+    let dir = tempdir().expect("Failed to create temp dir");
+    let path = dir.path().join("bogus.ckpt");
+    std::fs::write(&path, b"not a checkpoint").unwrap();
+
+    assert!(LanczosState::resume_from(&path).is_err());
+}
+
+/// Test that a checkpoint with a corrupted header or a truncated payload is rejected before any
+/// allocation, instead of aborting on a huge count.
+#[test]
+fn test_lanczos_resume_rejects_corrupted_header() {
+    let n = 6;
+    let laplacian = path_laplacian(n);
+    let mut state = LanczosState::new(&start_vector(n)).unwrap();
+    for _ in 0..3 {
+        state.step(&|x: &Array1<f64>| laplacian.dot(x));
+    }
+    let dir = tempdir().expect("Failed to create temp dir");
+    let path = dir.path().join("lanczos.ckpt");
+    state.save(&path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+
+    // The dimension word sits after the magic and version, the iteration count after it
+    for (offset, value) in [(8, u64::MAX), (16, u64::MAX), (16, n as u64 + 1), (8, n as u64 + 1)] {
+        let mut corrupted = bytes.clone();
+        corrupted[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        std::fs::write(&path, &corrupted).unwrap();
+        let error = LanczosState::resume_from(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "offset {} = {}", offset, value);
+    }
+
+    std::fs::write(&path, &bytes[..bytes.len() - 8]).unwrap();
+    assert_eq!(LanczosState::resume_from(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// Test that saving over an existing checkpoint replaces it in one step, leaving no temporary
+/// file behind.
+#[test]
+fn test_lanczos_save_replaces_checkpoint_atomically() {
+    let n = 6;
+    let laplacian = path_laplacian(n);
+    let mut state = LanczosState::new(&start_vector(n)).unwrap();
+    let dir = tempdir().expect("Failed to create temp dir");
+    let path = dir.path().join("lanczos.ckpt");
+
+    state.save(&path).unwrap();
+    for _ in 0..3 {
+        state.step(&|x: &Array1<f64>| laplacian.dot(x));
+    }
+    state.save(&path).unwrap();
+
+    assert_eq!(LanczosState::resume_from(&path).unwrap(), state);
+    let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(entries, vec![std::ffi::OsString::from("lanczos.ckpt")]);
+}
+
+/// Test that Lanczos driven by the streaming, file-backed Laplacian matches the in-memory run.
+#[test]
+fn test_lanczos_streaming_matvec_matches_in_memory() {
+    let n = 10;
+    let dir = tempdir().expect("Failed to create temp dir");
+    let gam_path = dir.path().join("path.gam");
+    {
+        let mut writer = BufWriter::new(File::create(&gam_path).unwrap());
+        for i in 0..(n as u32 - 1) {
+            for (from, to) in [(i, i + 1), (i + 1, i)] {
+                writer.write_all(&from.to_le_bytes()).unwrap();
+                writer.write_all(&to.to_le_bytes()).unwrap();
+            }
+        }
+    }
+
+    let streaming = StreamingAdjacency::open(&gam_path).unwrap();
+    let laplacian = path_laplacian(n);
+
+    let mut from_disk = LanczosState::new(&start_vector(n)).unwrap();
+    let mut in_memory = LanczosState::new(&start_vector(n)).unwrap();
+    for _ in 0..6 {
+        assert!(from_disk.try_step(&|x: &Array1<f64>| streaming.laplacian_matvec(x)).unwrap());
+        in_memory.step(&|x: &Array1<f64>| laplacian.dot(x));
+    }
+
+    for (a, b) in from_disk.alphas.iter().zip(in_memory.alphas.iter()) {
+        assert!((a - b).abs() <= TOLERANCE);
+    }
+    for (a, b) in from_disk.betas.iter().zip(in_memory.betas.iter()) {
+        assert!((a - b).abs() <= TOLERANCE);
+    }
+}
+
+/// Test that the Ritz-value history ends at the direct extreme eigenvalues and grows by one per step.
+#[test]
+fn test_lanczos_history_converges_to_extremes() {
+    let n = 8;
+    let laplacian = path_laplacian(n);
+    let (final_values, history) =
+        lanczos_with_history(|x: &Array1<f64>| laplacian.dot(x), n, n).unwrap();
+    let (eigvals, _) = call_eigendecomp(&laplacian).unwrap();
+
+    assert_eq!(history.len(), n);
+    for (k, ritz) in history.iter().enumerate() {
+        assert_eq!(ritz.len(), k + 1, "History entry {} has the wrong length.", k);
+    }
+    assert_eq!(&final_values, history.last().unwrap());
+    assert!((final_values[0] - eigvals[0]).abs() <= TOLERANCE, "Smallest Ritz value = {}", final_values[0]);
+    assert!(
+        (final_values[n - 1] - eigvals[n - 1]).abs() <= TOLERANCE,
+        "Largest Ritz value = {}",
+        final_values[n - 1]
+    );
+}
+
+/// Test that block Lanczos recovers both vectors of a 2-fold degenerate Fiedler space: three
+/// cliques joined in a ring, whose rotational symmetry makes the Fiedler value a double eigenvalue.
+#[test]
+fn test_block_lanczos_degenerate_fiedler_space() {
+    let (cliques, size) = (3, 10);
+    let n = cliques * size;
+    let mut adjacency = Array2::<f64>::zeros((n, n));
+    for c in 0..cliques {
+        for i in 0..size {
+            for j in 0..size {
+                if i != j {
+                    adjacency[[c * size + i, c * size + j]] = 1.0;
+                }
+            }
+        }
+        // Node 0 of each clique links to node 1 of the next
+        let (a, b) = (c * size, ((c + 1) % cliques) * size + 1);
+        adjacency[[a, b]] = 1.0;
+        adjacency[[b, a]] = 1.0;
+    }
+    let laplacian = Array2::from_diag(&adjacency.sum_axis(Axis(1))) - &adjacency;
+
+    let (values, vectors) = block_lanczos(|x: &Array2<f64>| laplacian.dot(x), n, 3, 5, 3);
+    assert_eq!(values.len(), 3);
+    assert_eq!(vectors.dim(), (n, 3));
+
+    let (expected, _) = call_eigendecomp(&laplacian).unwrap();
+    assert!((expected[1] - expected[2]).abs() < 1e-10, "Fiedler value should be degenerate");
+    for i in 0..3 {
+        assert!((values[i] - expected[i]).abs() < TOLERANCE, "{} vs {}", values, expected);
+    }
+
+    // Both Fiedler vectors are eigenvectors and orthonormal, so they span the whole eigenspace
+    for i in 1..3 {
+        let v = vectors.column(i);
+        let residual = laplacian.dot(&v) - &v * values[i];
+        assert!(residual.dot(&residual).sqrt() < 1e-4);
+    }
+    let gram = vectors.t().dot(&vectors);
+    for ((i, j), value) in gram.indexed_iter() {
+        let expected = if i == j { 1.0 } else { 0.0 };
+        assert!((value - expected).abs() < TOLERANCE);
+    }
+}
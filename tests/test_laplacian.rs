@@ -0,0 +1,277 @@
+// tests/test_laplacian.rs
+
+//! Unit tests for the laplacian module.
+
+use graphome::eigen::call_eigendecomp;
+use graphome::extract::check_laplacian_supported;
+use graphome::laplacian::*;
+use ndarray::prelude::*;
+use std::io;
+
+const TOLERANCE: f64 = 1e-6;
+
+/// Adjacency matrix of the path 0 - 1 - 2.
+fn path_adjacency() -> Array2<f64> {
+    array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0]]
+}
+
+/// Test that every builder produces the textbook matrix for a small path graph
+#[test]
+fn test_laplacian_variants_on_path() {
+    let adj = path_adjacency();
+    let s = 1.0 / 2f64.sqrt();
+
+    let expected_combinatorial = array![[1.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 1.0]];
+    let expected_normalized = array![[1.0, -s, 0.0], [-s, 1.0, -s], [0.0, -s, 1.0]];
+    let expected_random_walk = array![[1.0, -1.0, 0.0], [-0.5, 1.0, -0.5], [0.0, -1.0, 1.0]];
+    let expected_signless = array![[1.0, 1.0, 0.0], [1.0, 2.0, 1.0], [0.0, 1.0, 1.0]];
+
+    let cases = [
+        (LaplacianKind::Combinatorial, expected_combinatorial),
+        (LaplacianKind::Normalized, expected_normalized),
+        (LaplacianKind::RandomWalk, expected_random_walk),
+        (LaplacianKind::Signless, expected_signless),
+    ];
+
+    for (kind, expected) in cases.iter() {
+        let built = build_laplacian(&adj, *kind);
+        for (a, b) in built.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < TOLERANCE, "{} Laplacian mismatch", kind);
+        }
+    }
+}
+
+/// Test that isolated nodes get a zero row in the normalized and random-walk Laplacians
+#[test]
+fn test_laplacian_isolated_node() {
+    let mut adj = Array2::<f64>::zeros((3, 3));
+    adj[[0, 1]] = 1.0;
+    adj[[1, 0]] = 1.0;
+
+    for kind in [LaplacianKind::Normalized, LaplacianKind::RandomWalk] {
+        let built = build_laplacian(&adj, kind);
+        assert!(built.iter().all(|v| v.is_finite()));
+        assert!(built.row(2).iter().all(|v| v.abs() < TOLERANCE));
+    }
+}
+
+/// Test that only the non-symmetric random-walk variant is rejected for the symmetric solver
+#[test]
+fn test_check_laplacian_supported() {
+    assert!(check_laplacian_supported(LaplacianKind::Combinatorial).is_ok());
+    assert!(check_laplacian_supported(LaplacianKind::Normalized).is_ok());
+    assert!(check_laplacian_supported(LaplacianKind::Signless).is_ok());
+
+    let err = check_laplacian_supported(LaplacianKind::RandomWalk).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("random-walk"));
+}
+
+/// Test that `ngec_across_normalizations` returns three finite values in (0, 1] for a path graph
+#[test]
+fn test_ngec_across_normalizations_path() {
+    let comparison = ngec_across_normalizations(&path_adjacency()).unwrap();
+
+    for value in [comparison.combinatorial, comparison.normalized, comparison.signless] {
+        assert!(value.is_finite() && value > 0.0 && value <= 1.0, "NGEC out of range: {}", value);
+    }
+
+    // A bipartite graph has the same combinatorial and signless spectrum
+    assert!((comparison.combinatorial - comparison.signless).abs() < TOLERANCE);
+}
+
+/// Test that two disjoint triangles get two equal per-component NGEC values, and an isolated node NaN.
+#[test]
+fn test_ngec_per_component_two_triangles() {
+    let mut adj = Array2::<f64>::zeros((7, 7));
+    for &(u, v) in &[(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5)] {
+        adj[[u, v]] = 1.0;
+        adj[[v, u]] = 1.0;
+    }
+
+    let components = ngec_per_component(&adj).unwrap();
+    assert_eq!(components.len(), 3);
+    let triangle_ngec = graphome::eigen::compute_ngec(&array![0.0, 3.0, 3.0]).unwrap();
+    for &(size, ngec) in &components[..2] {
+        assert_eq!(size, 3);
+        assert!((ngec - triangle_ngec).abs() < TOLERANCE);
+    }
+    assert_eq!(components[2].0, 1);
+    assert!(components[2].1.is_nan());
+}
+
+/// Test that `dual_spectra` matches decomposing each Laplacian on its own, isolated node included.
+#[test]
+fn test_dual_spectra_matches_independent_decompositions() {
+    // A triangle with a pendant node, plus an isolated node
+    let mut adjacency = Array2::<f64>::zeros((5, 5));
+    for (u, v, w) in [(0, 1, 1.0), (1, 2, 2.0), (0, 2, 1.0), (2, 3, 0.5)] {
+        adjacency[[u, v]] = w;
+        adjacency[[v, u]] = w;
+    }
+
+    let (combinatorial, normalized) = dual_spectra(&adjacency).unwrap();
+    let (expected_combinatorial, _) = call_eigendecomp(&combinatorial_laplacian(&adjacency)).unwrap();
+    let (expected_normalized, _) = call_eigendecomp(&normalized_laplacian(&adjacency)).unwrap();
+
+    for (a, b) in combinatorial.iter().zip(expected_combinatorial.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+    for (a, b) in normalized.iter().zip(expected_normalized.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+}
+
+/// Test that the signed Laplacian of a graph with negative weights is positive semidefinite.
+#[test]
+fn test_signed_laplacian_is_psd() {
+    // An unbalanced triangle (one negative edge) plus a negative pendant edge
+    let mut adjacency = Array2::<f64>::zeros((4, 4));
+    for (u, v, w) in [(0, 1, 1.0), (1, 2, 1.0), (0, 2, -2.0), (2, 3, -0.5)] {
+        adjacency[[u, v]] = w;
+        adjacency[[v, u]] = w;
+    }
+
+    // The standard Laplacian is indefinite here
+    let (standard_vals, _) = call_eigendecomp(&combinatorial_laplacian(&adjacency)).unwrap();
+    assert!(standard_vals[0] < -TOLERANCE);
+
+    let signed = signed_laplacian(&adjacency);
+    assert!((signed[[2, 2]] - 3.5).abs() < TOLERANCE);
+    let (signed_vals, _) = call_eigendecomp(&signed).unwrap();
+    assert!(signed_vals.iter().all(|&value| value > -TOLERANCE));
+    // Unbalanced, so even the smallest eigenvalue is strictly positive
+    assert!(signed_vals[0] > TOLERANCE);
+}
+
+/// Test that the bipartite Laplacian has zero diagonal-block adjacency, -B off the diagonal, and
+/// the degrees of both sides.
+#[test]
+fn test_bipartite_laplacian_block_structure() {
+    let biadjacency = array![[1.0, 0.0, 2.0], [0.0, 1.0, 1.0]];
+    let laplacian = bipartite_laplacian(&biadjacency);
+
+    assert_eq!(laplacian.dim(), (5, 5));
+    assert_eq!(laplacian, laplacian.t());
+    assert_eq!(laplacian.slice(s![..2, 2..]), -&biadjacency);
+    assert_eq!(laplacian.slice(s![2.., ..2]), -&biadjacency.t());
+    assert_eq!(laplacian.slice(s![..2, ..2]), Array2::from_diag(&array![3.0, 2.0]));
+    assert_eq!(laplacian.slice(s![2.., 2..]), Array2::from_diag(&array![1.0, 1.0, 3.0]));
+
+    // A connected bipartite graph has a single zero eigenvalue
+    let (eigvals, _) = call_eigendecomp(&laplacian).unwrap();
+    assert!(eigvals[0].abs() < TOLERANCE);
+    assert!(eigvals[1] > TOLERANCE);
+}
+
+/// Test that the modularity matrix is symmetric with zero row sums and the expected entries.
+#[test]
+fn test_modularity_matrix_row_sums() {
+    let adjacency = path_adjacency();
+    let modularity = modularity_matrix(&adjacency);
+    let total = adjacency.sum();
+
+    assert_eq!(modularity, modularity.t());
+    for row_sum in modularity.sum_axis(Axis(1)).iter() {
+        assert!(row_sum.abs() < TOLERANCE, "Row sum = {}", row_sum);
+    }
+    let k = degrees(&adjacency);
+    assert!((modularity[[0, 1]] - (adjacency[[0, 1]] - k[0] * k[1] / total)).abs() < TOLERANCE);
+
+    assert_eq!(modularity_matrix(&Array2::<f64>::zeros((3, 3))), Array2::<f64>::zeros((3, 3)));
+}
+
+/// Test that the Newman split recovers two triangles joined by a bridge, and leaves a clique whole.
+#[test]
+fn test_newman_split_two_communities() -> io::Result<()> {
+    let mut adjacency = Array2::<f64>::zeros((6, 6));
+    for (u, v) in [(0, 1), (0, 2), (1, 2), (3, 4), (3, 5), (4, 5), (2, 3)] {
+        adjacency[[u, v]] = 1.0;
+        adjacency[[v, u]] = 1.0;
+    }
+    let split = newman_split(&adjacency)?;
+    assert_eq!(split, vec![true, true, true, false, false, false]);
+
+    let clique = Array2::<f64>::ones((4, 4)) - Array2::<f64>::eye(4);
+    assert_eq!(newman_split(&clique)?, vec![true; 4]);
+
+    Ok(())
+}
+
+/// Test that `call_eigendecomp` reproduces the closed-form spectra of P_50, C_50 and K_50
+#[test]
+fn test_decomposition_matches_analytic_spectra() {
+    let n = 50;
+    let mut path = Array2::<f64>::zeros((n, n));
+    let mut cycle = Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        let j = (i + 1) % n;
+        cycle[[i, j]] = 1.0;
+        cycle[[j, i]] = 1.0;
+        if j != 0 {
+            path[[i, j]] = 1.0;
+            path[[j, i]] = 1.0;
+        }
+    }
+    let complete = Array2::<f64>::ones((n, n)) - Array2::<f64>::eye(n);
+
+    let cases = [
+        ("path", path, path_graph_spectrum(n)),
+        ("cycle", cycle, cycle_graph_spectrum(n)),
+        ("complete", complete, complete_graph_spectrum(n)),
+    ];
+    for (name, adjacency, expected) in cases {
+        let (eigvals, _) = call_eigendecomp(&combinatorial_laplacian(&adjacency)).unwrap();
+        assert_eq!(eigvals.len(), n);
+        for (computed, exact) in eigvals.iter().zip(expected.iter()) {
+            assert!((computed - exact).abs() < TOLERANCE, "{}: {} vs {}", name, computed, exact);
+        }
+    }
+}
+
+/// Test that the degree matrix of the path is diagonal with its degrees, and that the inverse
+/// square root leaves isolated nodes at zero
+#[test]
+fn test_degree_matrix_on_path() {
+    let adj = path_adjacency();
+    let d = degree_matrix(&adj);
+    assert_eq!(d, Array2::from_diag(&array![1.0, 2.0, 1.0]));
+
+    let inv_sqrt = degree_matrix_inv_sqrt(&adj);
+    let expected = Array2::from_diag(&array![1.0, 1.0 / 2f64.sqrt(), 1.0]);
+    for (a, b) in inv_sqrt.iter().zip(expected.iter()) {
+        assert!((a - b).abs() < TOLERANCE);
+    }
+
+    // An isolated fourth node
+    let mut padded = Array2::<f64>::zeros((4, 4));
+    padded.slice_mut(s![..3, ..3]).assign(&adj);
+    let inv_sqrt = degree_matrix_inv_sqrt(&padded);
+    assert_eq!(inv_sqrt[[3, 3]], 0.0);
+    assert!(inv_sqrt.iter().all(|v| v.is_finite()));
+}
+
+/// Test that the normalized spectrum is symmetric about 1 for a bipartite graph (an even cycle)
+/// but not for a graph with an odd cycle
+#[test]
+fn test_bipartite_spectral_symmetry() {
+    let cycle = |n: usize| {
+        let mut adj = Array2::<f64>::zeros((n, n));
+        for i in 0..n {
+            adj[[i, (i + 1) % n]] = 1.0;
+            adj[[(i + 1) % n, i]] = 1.0;
+        }
+        adj
+    };
+
+    let (even, _) = call_eigendecomp(&normalized_laplacian(&cycle(6))).unwrap();
+    assert!(check_bipartite_spectral_symmetry(&even, TOLERANCE));
+
+    let (odd, _) = call_eigendecomp(&normalized_laplacian(&cycle(5))).unwrap();
+    assert!(!check_bipartite_spectral_symmetry(&odd, TOLERANCE));
+
+    // A bipartite star with unequal sides
+    let star = array![[0.0, 1.0, 1.0, 1.0], [1.0, 0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]];
+    let (star_vals, _) = call_eigendecomp(&normalized_laplacian(&star)).unwrap();
+    assert!(check_bipartite_spectral_symmetry(&star_vals, TOLERANCE));
+}
@@ -0,0 +1,86 @@
+// tests/test_parquet.rs
+
+//! Unit tests for the parquet module (requires the `parquet` feature).
+
+#![cfg(feature = "parquet")]
+
+use graphome::parquet::{read_parquet_schema, save_eigenvectors_parquet};
+use ndarray::prelude::*;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::RowAccessor;
+use std::fs::{self, File};
+use tempfile::tempdir;
+
+/// Test that the written table reads back with one name column, one column per eigenvector,
+/// and one row per node.
+#[test]
+fn test_save_eigenvectors_parquet_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("eigenvectors.parquet");
+
+    let eigvecs = array![[0.5, -0.5], [0.5, 0.5], [0.5, -0.5], [0.5, 0.5]];
+    let names: Vec<String> = ["s1", "s2", "segment_three", "s4"].iter().map(|s| s.to_string()).collect();
+    save_eigenvectors_parquet(&path, &eigvecs, &names).unwrap();
+
+    let bytes = fs::read(&path).unwrap();
+    assert_eq!(&bytes[..4], b"PAR1");
+    assert_eq!(&bytes[bytes.len() - 4..], b"PAR1");
+
+    let (columns, rows) = read_parquet_schema(&path).unwrap();
+    assert_eq!(columns, vec!["name", "eigenvector_0", "eigenvector_1"]);
+    assert_eq!(rows, 4);
+
+    // Twenty eigenvectors, so the footer lists more than fifteen columns
+    let wide = Array2::<f64>::from_shape_fn((4, 20), |(i, j)| (i * j) as f64);
+    save_eigenvectors_parquet(&path, &wide, &names).unwrap();
+    let (columns, rows) = read_parquet_schema(&path).unwrap();
+    assert_eq!(columns.len(), 21);
+    assert_eq!(columns[20], "eigenvector_19");
+    assert_eq!(rows, 4);
+}
+
+/// Test that a name list of the wrong length is rejected.
+#[test]
+fn test_save_eigenvectors_parquet_rejects_mismatched_names() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("bad.parquet");
+    let names = vec!["only".to_string()];
+    let error = save_eigenvectors_parquet(&path, &Array2::<f64>::zeros((3, 2)), &names).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+/// Test that the reference Parquet reader decodes every name and value as written.
+#[test]
+fn test_save_eigenvectors_parquet_reference_reader() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("eigenvectors.parquet");
+    let eigvecs = array![[0.125, -1.5, 1e-300], [-0.0, 2.0, f64::MAX], [3.25, -4.0, -7.5]];
+    let names: Vec<String> = ["s1", "ñode", ""].iter().map(|s| s.to_string()).collect();
+    save_eigenvectors_parquet(&path, &eigvecs, &names).unwrap();
+
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.metadata().num_row_groups(), 1);
+    let rows: Vec<_> = reader.get_row_iter(None).unwrap().map(|row| row.unwrap()).collect();
+    assert_eq!(rows.len(), 3);
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(row.get_string(0).unwrap(), &names[i]);
+        for j in 0..3 {
+            assert_eq!(row.get_double(j + 1).unwrap(), eigvecs[[i, j]]);
+        }
+    }
+}
+
+/// Test that a file whose footer length runs past the start of the file is rejected as
+/// invalid data instead of being allocated.
+#[test]
+fn test_read_parquet_schema_rejects_oversized_footer() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("corrupt.parquet");
+    let mut bytes = b"PAR1 not much here ".to_vec();
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    bytes.extend_from_slice(b"PAR1");
+    fs::write(&path, &bytes).unwrap();
+
+    let error = read_parquet_schema(&path).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,90 @@
+// tests/test_trace.rs
+
+//! Unit tests for the `tracing` instrumentation (requires the `trace` feature).
+
+#![cfg(feature = "trace")]
+
+use graphome::eigen::call_eigendecomp;
+use ndarray::prelude::*;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+/// A closed span as seen by `CaptureLayer`.
+#[derive(Debug, Clone, PartialEq)]
+struct Captured {
+    name: &'static str,
+    parent: Option<&'static str>,
+    fields: Vec<(String, u64)>,
+}
+
+/// Collects the integer fields of a span.
+struct FieldVisitor<'a>(&'a mut Vec<(String, u64)>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.push((field.name().to_string(), value));
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+/// A layer that records every span when it closes, with its parent and fields.
+struct CaptureLayer(Arc<Mutex<Vec<Captured>>>);
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for CaptureLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Vec::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        let span = ctx.span(id).unwrap();
+        let captured = Captured {
+            name: span.name(),
+            parent: span.parent().map(|parent| parent.name()),
+            fields,
+        };
+        span.extensions_mut().insert(captured);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = ctx.span(&id).unwrap();
+        let captured = span.extensions().get::<Captured>().cloned().unwrap();
+        self.0.lock().unwrap().push(captured);
+    }
+}
+
+/// Test that a capturing subscriber layer sees the banding, LAPACK and assembly spans of a banded
+/// decomposition, nested inside the top-level span and carrying the matrix dimensions.
+#[test]
+fn test_eigendecomp_records_expected_spans() {
+    // A pentadiagonal Laplacian-like matrix, so the banded dsbevd path is chosen
+    let n = 30;
+    let matrix = Array2::from_shape_fn((n, n), |(i, j)| match i.abs_diff(j) {
+        0 => 4.0,
+        1 | 2 => -1.0,
+        _ => 0.0,
+    });
+
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = Registry::default().with(CaptureLayer(Arc::clone(&records)));
+    tracing::subscriber::with_default(subscriber, || call_eigendecomp(&matrix).unwrap());
+
+    let captured = records.lock().unwrap().clone();
+    let names: Vec<&str> = captured.iter().map(|record| record.name).collect();
+    // Spans close innermost first
+    assert_eq!(names, vec!["banding", "lapack.dsbevd", "vector_assembly", "eigendecomp"]);
+
+    let top = captured.last().unwrap();
+    assert_eq!(top.parent, None);
+    assert_eq!(top.fields, vec![("n".to_string(), n as u64), ("kd".to_string(), 2)]);
+    assert!(captured[..3].iter().all(|record| record.parent == Some("eigendecomp")));
+    assert_eq!(captured[1].fields, top.fields);
+
+    // Nothing is recorded outside the subscriber's scope
+    call_eigendecomp(&matrix).unwrap();
+    assert_eq!(records.lock().unwrap().len(), 4);
+}